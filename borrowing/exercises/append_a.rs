@@ -0,0 +1,15 @@
+// Exercise: append_a
+//
+// `append_a` takes a mutable reference, so it can change the `String` it was
+// lent without taking ownership of it. Make it add the letter 'a' to the end
+// of the name.
+
+fn main() {
+    let mut name = String::from("Gabe");
+    append_a(&mut name);
+    println!("{}", name);
+}
+
+fn append_a(name: &mut String) {
+    todo!()
+}
@@ -0,0 +1,21 @@
+// Exercise: say_hello
+//
+// `say_hello` borrows a name instead of taking ownership of it, so whoever
+// calls it can keep using the name afterwards. Make it return
+// "Hello, <name>!".
+//
+// `main` calls it with both a `&String` and a `&str`. Why do both work? Look
+// for "Deref coercion" in the lesson.
+
+fn main() {
+    let name = String::from("Gabe");
+    println!("{}", say_hello(&name));
+    println!("{}", say_hello("Ferris"));
+
+    // We only lent `name` out, so it's still ours.
+    println!("{} is still here", name);
+}
+
+fn say_hello(name: &str) -> String {
+    todo!()
+}
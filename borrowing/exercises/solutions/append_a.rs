@@ -0,0 +1,14 @@
+// Solution: append_a
+//
+// The instructor's answer to the exercise of the same name. `club student
+// --check` makes sure that it passes the hidden tests.
+
+fn main() {
+    let mut name = String::from("Gabe");
+    append_a(&mut name);
+    println!("{}", name);
+}
+
+fn append_a(name: &mut String) {
+    name.push('a');
+}
@@ -0,0 +1,17 @@
+// Solution: say_hello
+//
+// The instructor's answer to the exercise of the same name. `club student
+// --check` makes sure that it passes the hidden tests.
+
+fn main() {
+    let name = String::from("Gabe");
+    println!("{}", say_hello(&name));
+    println!("{}", say_hello("Ferris"));
+
+    // We only lent `name` out, so it's still ours.
+    println!("{} is still here", name);
+}
+
+fn say_hello(name: &str) -> String {
+    format!("Hello, {}!", name)
+}
@@ -0,0 +1,21 @@
+#[test]
+fn appends_an_a() {
+    let mut name = String::from("Gabe");
+    append_a(&mut name);
+    assert_eq!(name, "Gabea");
+}
+
+#[test]
+fn appends_one_a_each_time() {
+    let mut name = String::from("Gabe");
+    append_a(&mut name);
+    append_a(&mut name);
+    assert_eq!(name, "Gabeaa");
+}
+
+#[test]
+fn appends_to_an_empty_string() {
+    let mut name = String::new();
+    append_a(&mut name);
+    assert_eq!(name, "a");
+}
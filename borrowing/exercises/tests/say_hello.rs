@@ -0,0 +1,18 @@
+#[test]
+fn greets_a_borrowed_string() {
+    let name = String::from("Gabe");
+    assert_eq!(say_hello(&name), "Hello, Gabe!");
+    // Still ours after lending it out.
+    assert_eq!(name, "Gabe");
+}
+
+#[test]
+fn greets_a_str() {
+    assert_eq!(say_hello("Ferris"), "Hello, Ferris!");
+}
+
+#[test]
+fn can_lend_the_same_name_twice() {
+    let name = String::from("Gabe");
+    assert_eq!(say_hello(&name), say_hello(&name));
+}
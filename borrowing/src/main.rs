@@ -12,6 +12,7 @@
 // to use your phone, you can let them borrow it with the assumption that
 // they will give it back.
 
+// EXPLAIN: ownership
 // This same concept applies to variables and values in Rust. Taking ownership
 // of something means that the owner of that value is _entirely_ responsible
 // for the value. This means that all memory mangement of the value is done
@@ -53,6 +54,7 @@
 // give up ownership of something (e.g. Using a value later), but just want
 // whatever is referencing it to be able to see or change the value.
 
+// EXPLAIN: reference-rules
 // Rust's ownership system places some restrictions on references, though.
 // These restrictions make sure that all references are valid, and won't ever
 // point to garbage data (looking at you, C and C++). To do this, you must
@@ -84,6 +86,7 @@
 // means that the compiler will always be able to validate references for you,
 // making sure that these bugs can never happen!
 
+// EXPLAIN: mutability
 // One other thing to note: values in Rust are immutable by default. This does
 // not mean that there is no mutability in Rust, but rather, it must be
 // explicitly declared that you want to make the value mutable. Immutable
@@ -126,9 +129,11 @@ fn main() {
     // anything with them is obviously not dangerous. If you have two mutable
     // references or a mutable reference and an immutable one and try to use
     // one of them, the compiler gives you an error.
+    // ```compile_fail(E0499): cannot borrow `name` as mutable more than once at a time
     // let name_mut1 = &mut name;
     // let name_mut2 = &mut name;
     // say_hello(&name_mut1);
+    // ```
 
     // `name` is dropped here, right before the main function exits! Since main()
     // owns the value (after all, it was defined in main), it will be dropped
@@ -139,11 +144,15 @@ fn main() {
 // This is due to something called Deref coercion, which allows some types to be
 // used as references of another type.
 fn say_hello(name: &str) {
+    // SOLUTION BEGIN: todo!("print a greeting for `name`")
     println!("Hello, {}!", name);
+    // SOLUTION END
 }
 
 fn append_a(name: &mut String) {
+    // SOLUTION BEGIN: todo!("push an 'a' onto the end of `name`")
     // This is similar to the .push_str() function, except that it only pushes
     // one character to the string.
     name.push('a');
+    // SOLUTION END
 }
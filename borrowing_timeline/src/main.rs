@@ -0,0 +1,63 @@
+// This is the last lesson again, but with `name` keeping a diary. It's a
+// `Traced` instead of a String, which works just like one, except that it
+// writes down every time it's lent out, given back, changed, moved or dropped,
+// along with the line that did it. At the end, we print the diary as a
+// timeline.
+
+// Don't worry about how Traced works (it's in traced.rs, if you're curious).
+// The part to look at is what it prints.
+
+mod traced;
+
+use traced::{Ref, RefMut, Traced};
+
+fn main() {
+    lesson();
+
+    println!();
+    traced::print_timeline();
+}
+
+fn lesson() {
+    let mut name = Traced::new("name", "Gabe");
+
+    // name.borrow() is our stand-in for &name.
+    say_hello(name.borrow());
+
+    name.push_str(" Love");
+    say_hello(name.borrow());
+
+    // And name.borrow_mut() is &mut name.
+    append_a(name.borrow_mut());
+
+    // Rust doesn't run any code when a value moves, so this move has to be
+    // spelled out for Traced to notice it. It's the same as
+    // `let full_name = name;`, and `name` can't be used after it.
+    let full_name = name.moved_to("full_name");
+    say_hello(full_name.borrow());
+
+    // `full_name` is dropped at the end of the scope that owns it, the `}`
+    // just below. Traced can't see a `}` either, so it's marked for it.
+    full_name.end_of_scope();
+}
+
+fn say_hello(name: Ref) {
+    println!("Hello, {}!", name);
+}
+
+fn append_a(mut name: RefMut) {
+    name.push('a');
+}
+
+// In the timeline, the line on the left is the owner, and every borrow
+// branches off it for as long as it lasts. Notice that:
+//
+// 1. Each borrow ends before the next one starts. The borrows never overlap,
+//    which is exactly what the borrowing rules ask for, and why the compiler
+//    was happy with the last lesson.
+// 2. The borrow lent to say_hello is given back on the same line it was lent
+//    out on, as soon as say_hello returns.
+// 3. `name` is changed through &mut name inside append_a, but the owner gets
+//    it back afterwards, changes and all.
+// 4. After the move, it's `full_name` that's responsible for the String, so
+//    the String is dropped when full_name goes out of scope.
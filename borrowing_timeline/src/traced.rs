@@ -0,0 +1,429 @@
+// A String that writes down everything that happens to it: when it's lent out
+// and given back, when it changes, when it moves to a new owner, and when it's
+// dropped. Each of those remembers the line of the lesson it happened on,
+// thanks to #[track_caller], which hands a function the location it was
+// called from.
+//
+// Borrows are the guards `Ref` and `RefMut`, like RefCell's. The borrow starts
+// when the guard is made and ends when the guard is dropped, which is exactly
+// when the compiler considers a reference passed to a function to be done
+// with.
+//
+// Rust moves values without running any code, so a move can't be noticed on
+// its own. This one has to be spelled out, with `moved_to`. Likewise the end
+// of a scope is only a `}`, which #[track_caller] can't see, so the line a
+// value is dropped on has to be marked with `end_of_scope`. A value dropped
+// without it shows up in the timeline with no line at all, rather than with
+// the wrong one.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::ops::Deref;
+use std::panic::Location;
+
+// The lesson, so the timeline can show the line each event happened on.
+const SOURCE: &str = include_str!("main.rs");
+
+#[derive(Debug, Clone)]
+enum Event {
+    Created {
+        owner: &'static str,
+        value: String,
+    },
+    Lent {
+        borrow: usize,
+        owner: &'static str,
+        mutable: bool,
+    },
+    GivenBack {
+        borrow: usize,
+        owner: &'static str,
+        mutable: bool,
+    },
+    Changed {
+        borrow: Option<usize>,
+        from: String,
+        to: String,
+    },
+    Moved {
+        from: &'static str,
+        to: &'static str,
+    },
+    Dropped {
+        owner: &'static str,
+        value: String,
+    },
+}
+
+thread_local! {
+    static LOG: RefCell<Vec<(Event, Option<&'static Location<'static>>)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_BORROW: Cell<usize> = const { Cell::new(0) };
+}
+
+fn log(event: Event, location: &'static Location<'static>) {
+    log_at(event, Some(location));
+}
+
+fn log_at(event: Event, location: Option<&'static Location<'static>>) {
+    LOG.with(|log| log.borrow_mut().push((event, location)));
+}
+
+fn next_borrow() -> usize {
+    NEXT_BORROW.with(|next| {
+        let borrow = next.get();
+        next.set(borrow + 1);
+        borrow
+    })
+}
+
+pub struct Traced {
+    // The name of the variable that owns the string, for the timeline.
+    owner: &'static str,
+    value: String,
+    // Where it's dropped, if that was marked with `end_of_scope`.
+    dropped_at: Option<&'static Location<'static>>,
+}
+
+impl Traced {
+    #[track_caller]
+    pub fn new(owner: &'static str, value: &str) -> Traced {
+        let location = Location::caller();
+        log(
+            Event::Created {
+                owner,
+                value: value.to_string(),
+            },
+            location,
+        );
+        Traced {
+            owner,
+            value: value.to_string(),
+            dropped_at: None,
+        }
+    }
+
+    // &name
+    #[track_caller]
+    pub fn borrow(&self) -> Ref<'_> {
+        let borrow = next_borrow();
+        let location = Location::caller();
+        log(
+            Event::Lent {
+                borrow,
+                owner: self.owner,
+                mutable: false,
+            },
+            location,
+        );
+        Ref {
+            traced: self,
+            borrow,
+            location,
+        }
+    }
+
+    // &mut name
+    #[track_caller]
+    pub fn borrow_mut(&mut self) -> RefMut<'_> {
+        let borrow = next_borrow();
+        let location = Location::caller();
+        log(
+            Event::Lent {
+                borrow,
+                owner: self.owner,
+                mutable: true,
+            },
+            location,
+        );
+        RefMut {
+            traced: self,
+            borrow,
+            location,
+        }
+    }
+
+    // The owner can change the string itself, since it was declared `mut`.
+    #[track_caller]
+    pub fn push_str(&mut self, string: &str) {
+        let from = self.value.clone();
+        self.value.push_str(string);
+        let to = self.value.clone();
+        log(
+            Event::Changed {
+                borrow: None,
+                from,
+                to,
+            },
+            Location::caller(),
+        );
+    }
+
+    // let to = name;
+    #[track_caller]
+    pub fn moved_to(mut self, to: &'static str) -> Traced {
+        let location = Location::caller();
+        log(
+            Event::Moved {
+                from: self.owner,
+                to,
+            },
+            location,
+        );
+        self.owner = to;
+        self
+    }
+
+    // The `}` at the end of the owner's scope, called just before it.
+    #[track_caller]
+    pub fn end_of_scope(mut self) {
+        self.dropped_at = Some(Location::caller());
+    }
+}
+
+impl Drop for Traced {
+    fn drop(&mut self) {
+        log_at(
+            Event::Dropped {
+                owner: self.owner,
+                value: self.value.clone(),
+            },
+            self.dropped_at,
+        );
+    }
+}
+
+pub struct Ref<'a> {
+    traced: &'a Traced,
+    borrow: usize,
+    location: &'static Location<'static>,
+}
+
+impl Deref for Ref<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.traced.value
+    }
+}
+
+impl fmt::Display for Ref<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.traced.value)
+    }
+}
+
+impl Drop for Ref<'_> {
+    fn drop(&mut self) {
+        log(
+            Event::GivenBack {
+                borrow: self.borrow,
+                owner: self.traced.owner,
+                mutable: false,
+            },
+            self.location,
+        );
+    }
+}
+
+pub struct RefMut<'a> {
+    traced: &'a mut Traced,
+    borrow: usize,
+    location: &'static Location<'static>,
+}
+
+impl RefMut<'_> {
+    #[track_caller]
+    pub fn push(&mut self, character: char) {
+        let from = self.traced.value.clone();
+        self.traced.value.push(character);
+        let to = self.traced.value.clone();
+        log(
+            Event::Changed {
+                borrow: Some(self.borrow),
+                from,
+                to,
+            },
+            Location::caller(),
+        );
+    }
+}
+
+impl Deref for RefMut<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.traced.value
+    }
+}
+
+impl Drop for RefMut<'_> {
+    fn drop(&mut self) {
+        log(
+            Event::GivenBack {
+                borrow: self.borrow,
+                owner: self.traced.owner,
+                mutable: true,
+            },
+            self.location,
+        );
+    }
+}
+
+// Prints everything that happened as a timeline, oldest first. The owner is
+// the line on the left, and every borrow branches off it to the right for as
+// long as it lasts:
+//
+//   22  ●     name is created, "Gabe"   let mut name = Traced::new("name", "Gabe");
+//   25  ├──┐  &name is lent out         say_hello(name.borrow());
+//   25  ├──┘  &name is given back
+pub fn print_timeline() {
+    let events = LOG.with(|log| log.borrow().clone());
+
+    // The borrow in each column to the right of the owner, if there is one.
+    let mut columns: Vec<Option<usize>> = Vec::new();
+    let mut rows = Vec::new();
+
+    for (event, location) in &events {
+        let (gutter, text) = match event {
+            Event::Created { owner, value } => (
+                String::from("●"),
+                format!("{} is created, {:?}", owner, value),
+            ),
+            Event::Lent {
+                borrow,
+                owner,
+                mutable,
+            } => {
+                let column = match columns.iter().position(Option::is_none) {
+                    Some(column) => column,
+                    None => {
+                        columns.push(None);
+                        columns.len() - 1
+                    }
+                };
+                columns[column] = Some(*borrow);
+                (
+                    branch(&columns, column, '┐'),
+                    format!("{} is lent out", reference(owner, *mutable)),
+                )
+            }
+            Event::GivenBack {
+                borrow,
+                owner,
+                mutable,
+            } => {
+                let column = columns
+                    .iter()
+                    .position(|slot| *slot == Some(*borrow))
+                    .unwrap_or(0);
+                let gutter = branch(&columns, column, '┘');
+                columns[column] = None;
+                while columns.last() == Some(&None) {
+                    columns.pop();
+                }
+                (
+                    gutter,
+                    format!("{} is given back", reference(owner, *mutable)),
+                )
+            }
+            Event::Changed { borrow, from, to } => {
+                let gutter = match borrow {
+                    Some(borrow) => columns
+                        .iter()
+                        .position(|slot| *slot == Some(*borrow))
+                        .map(|column| lines('│', &columns, Some((column, '✎'))))
+                        .unwrap_or_else(|| lines('│', &columns, None)),
+                    None => lines('✎', &columns, None),
+                };
+                (gutter, format!("changed, {:?} to {:?}", from, to))
+            }
+            Event::Moved { from, to } => (
+                lines('↓', &columns, None),
+                format!("moves from {} to {}", from, to),
+            ),
+            Event::Dropped { owner, value } => (
+                String::from("✕"),
+                format!("{} goes out of scope and {:?} is dropped", owner, value),
+            ),
+        };
+        // A borrow is given back at the end of the call it was lent to, which
+        // #[track_caller] can't see, so it borrows the line it was lent out
+        // on, and has no code of its own to show.
+        let own_line = !matches!(event, Event::GivenBack { .. });
+        rows.push((
+            location.map(|location| location.line()),
+            own_line,
+            gutter,
+            text,
+        ));
+    }
+
+    let gutter_width = rows
+        .iter()
+        .map(|(_, _, gutter, _)| gutter.chars().count())
+        .max()
+        .unwrap_or(0);
+    let text_width = rows
+        .iter()
+        .map(|(_, _, _, text)| text.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    for (line, own_line, gutter, text) in rows {
+        let code = match line {
+            Some(line) if own_line => SOURCE.lines().nth(line as usize - 1).unwrap_or("").trim(),
+            _ => "",
+        };
+        let row = format!(
+            "{:>4}  {}{}  {}{}  {}",
+            line.map(|line| line.to_string()).unwrap_or_default(),
+            gutter,
+            " ".repeat(gutter_width - gutter.chars().count()),
+            text,
+            " ".repeat(text_width - text.chars().count()),
+            code
+        );
+        println!("{}", row.trim_end());
+    }
+}
+
+fn reference(owner: &str, mutable: bool) -> String {
+    if mutable {
+        format!("&mut {}", owner)
+    } else {
+        format!("&{}", owner)
+    }
+}
+
+// The owner and every column that's in use, with one of them marked.
+fn lines(owner: char, columns: &[Option<usize>], mark: Option<(usize, char)>) -> String {
+    let mut gutter = owner.to_string();
+    for (column, slot) in columns.iter().enumerate() {
+        gutter.push_str("  ");
+        gutter.push(match (slot, mark) {
+            (_, Some((marked, mark))) if marked == column => mark,
+            (Some(_), _) => '│',
+            (None, _) => ' ',
+        });
+    }
+    gutter
+}
+
+// A borrow leaving the owner or coming back to it, crossing every column on
+// the way.
+fn branch(columns: &[Option<usize>], to: usize, end: char) -> String {
+    let mut gutter = String::from("├");
+    for (column, slot) in columns.iter().enumerate() {
+        if column < to {
+            gutter.push_str("──");
+            gutter.push(if slot.is_some() { '┼' } else { '─' });
+        } else if column == to {
+            gutter.push_str("──");
+            gutter.push(end);
+        } else {
+            gutter.push_str("  ");
+            gutter.push(if slot.is_some() { '│' } else { ' ' });
+        }
+    }
+    gutter
+}
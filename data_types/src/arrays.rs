@@ -0,0 +1,24 @@
+// Helpers for the end of the lesson, where an array is indexed with a number
+// that isn't known until the program runs.
+
+use std::env;
+
+/// What to use when no index was given.
+const ONE_PAST_THE_END: usize = 5;
+
+/// The index given on the command line, like the 2 in
+/// `cargo run -p data_types -- 2`. If there isn't one, like when club runs
+/// the lesson, or it isn't a number, it's 5, one past the end of the array.
+pub fn index_from_args() -> usize {
+    env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(ONE_PAST_THE_END)
+}
+
+/// Whether the lesson should index the array with that number, which panics
+/// if it's out of bounds. It's only run with CLUB_PATH=index, so that the
+/// rest of the time the lesson gets to the end.
+pub fn index_path() -> bool {
+    env::var("CLUB_PATH").as_deref() == Ok("index")
+}
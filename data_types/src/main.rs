@@ -1,3 +1,14 @@
+// Some of the variables below are only there to show off their types.
+#![allow(unused_variables)]
+
+// The safe way through the crash below is in utf8.rs, what a char is and
+// isn't is in unicode.rs, and arrays.rs helps with indexing arrays at the end.
+mod arrays;
+mod graphemes;
+mod normalise;
+mod unicode;
+mod utf8;
+
 fn main() {
     // Let's create a variable with the type i32, which is an int in Java.
     // Variables are given types in the following format: <name>: <type>.
@@ -7,10 +18,27 @@ fn main() {
     // Now let's try a char! Remember that a char is any one valid Unicode scalar
     // value. For example, the ß character.
     let esstzet: char = 'ß';
+    // Why does it matter that a char is a "Unicode scalar value", and not a
+    // letter? Because they're not always the same thing. ß has no capital of
+    // its own, so in upper case it becomes two chars:
+    unicode::changing_case(esstzet);
+    // And what you'd call one letter can be several chars. Splitting text into
+    // the letters a reader sees, which Unicode calls grapheme clusters, takes
+    // rules of its own:
+    unicode::chars_and_graphemes();
+    // The same text can even be written with different chars. é is one char,
+    // or an e and an accent, and Rust compares the bytes, so the two aren't
+    // equal until they're normalised to the same form:
+    unicode::normalisation();
+    // So anything that works one char at a time, like reversing a string, can
+    // go wrong. Reversing by grapheme cluster keeps each letter whole:
+    unicode::reversing();
 
     // Let's try to create a str. Your first instinct might be to create a str
     // by writing this:
+    // ```compile_fail(E0277, E0308): the size for values of type `str` cannot be known
     // let my_str: str = "Hello, World!";
+    // ```
     // But notice, the compiler gives you an error if you try this. Why is that?
     // In Rust, every value must have a known size at compile time. In Java, this
     // isn't a problem, since everything is a reference, and references have a
@@ -36,12 +64,48 @@ fn main() {
     // compile time, so we reference the indexing operation. This indexing syntax 
     // is called a range, and it denotes starting at the 0th byte inclusive and 
     // continuing until the 1st byte exclusive.
-    let _my_value: &str = &hello_str[0..1];
+    //
+    // `club run data_types` runs the lesson like this, and then with
+    // CLUB_PATH=safe, which skips the crash so that you can see what comes
+    // after it. (`CLUB_PATH=safe cargo run -p data_types` does the same.)
+    if !utf8::safe_path() {
+        let _my_value: &str = &hello_str[0..1];
+    }
     // The program crashes! This is because the 你 character is larger than one
     // byte, so storing only part of it into a string would give incorrect results.
-    // There are ways to fix this, namely changing the 1 to 3 (since the
-    // character is three bytes long), but also by using the Result enum, which
-    // will be talked about later.
+    // Let's look at the bytes to see why.
+    utf8::inspect(hello_str);
+    // 你 is bytes 0..3 and 好 is bytes 3..6, so a slice can only start or end
+    // at 0, 3 or 6. Here's every range we could have tried:
+    utf8::slices(hello_str);
+    // So one fix is changing the 1 to 3, since the character is three bytes
+    // long.
+    let first_char: &str = &hello_str[0..3];
+    println!("&hello_str[0..3] is {:?}", first_char);
+    // That only works if you know how long each char is, though. The other fix
+    // is `get`, which checks instead of crashing: it gives back `None` if the
+    // range doesn't start and end on char boundaries, and `Some` with the
+    // slice if it does. `Option`, like the `Result` enum, will be talked about
+    // later.
+    println!("hello_str.get(0..1) is {:?}", hello_str.get(0..1));
+    println!("hello_str.get(0..3) is {:?}", hello_str.get(0..3));
+    // And if what you wanted was the first *character*, count chars instead
+    // of bytes, like utf8::slice_chars does.
+    println!(
+        "utf8::slice_chars(hello_str, 0..1) is {:?}",
+        utf8::slice_chars(hello_str, 0..1)
+    );
+    // The same goes for ß, which is two bytes. A char can be turned into a
+    // &str by writing its UTF-8 into a buffer that's big enough for any char.
+    let mut buffer = [0; 4];
+    let esstzet_str: &str = esstzet.encode_utf8(&mut buffer);
+    utf8::inspect(esstzet_str);
+    utf8::slices(esstzet_str);
+    println!("esstzet_str.get(0..1) is {:?}", esstzet_str.get(0..1));
+    println!(
+        "utf8::slice_chars(esstzet_str, 0..1) is {:?}",
+        utf8::slice_chars(esstzet_str, 0..1)
+    );
 
     // Rust also has a few "compound" types, namely, the tuple type and the array.
     // A tuple is a compound type that allows each of its members to have a
@@ -54,12 +118,49 @@ fn main() {
 
     // Arrays in Rust are almost the same as in Java, namely that they can hold
     // a fixed number of items and that the items must be of the same type.
-    // However, trying to index an array out of bounds will be a compiler error
-    // by default. Since the array size is known at compile time, Rust will check
-    // to make sure any access to the array isn't out of bounds. (Technically,
-    // this can be disabled, but why would you?) The type signature of an array
-    // is [<type>; <length>].
+    // The type signature of an array is [<type>; <length>].
     let array: [i32; 5] = [16, 17, 5, 4, 2002];
     // Indexing into an array is the same as Java also.
     let first_item: i32 = array[0];
+    // Like in Java, every index is checked, so nothing is ever read from past
+    // the end of an array. Unlike Java, an index that the compiler can work out
+    // for itself is checked before the program even runs. Since the array size
+    // is known at compile time, indexing it out of bounds with a constant is a
+    // compiler error:
+    // ```compile_fail(unconditional_panic): this operation will panic at runtime
+    // let _sixth_item: i32 = array[5];
+    // ```
+    // That error comes from a lint called unconditional_panic, which is an
+    // error by default. (Technically, it can be turned off, but why would you?)
+    // It's only found when the code is compiled all the way, so `cargo check`
+    // won't show it, but `cargo build` will.
+    //
+    // Most indices aren't known until the program runs, though, like one that
+    // the user types in. Those are checked when the program runs, and one
+    // that's out of bounds makes it panic, like slicing "你好" in the wrong
+    // place did. Here the index comes from the command line, and it's 5 if
+    // there isn't one.
+    let index: usize = arrays::index_from_args();
+    // `club run data_types` runs the lesson a third time with CLUB_PATH=index
+    // to show that panic, which would otherwise stop the lesson here. (Or try
+    // `CLUB_PATH=index cargo run -p data_types -- 2`, which doesn't panic.)
+    if arrays::index_path() {
+        let _item: i32 = array[index];
+    }
+    // The way to handle an index that might be out of bounds is `get`, which,
+    // like it does for a str, gives back `None` instead of panicking.
+    println!("array.get({}) is {:?}", index, array.get(index));
+    println!("array.get(2) is {:?}", array.get(2));
+
+    // Checking every index sounds slow, but the compiler leaves the check out
+    // wherever it can prove the index is in bounds, like in
+    // `for i in 0..array.len()`, and an optimized build can usually move the
+    // check out of a loop altogether. Looping over the items with an iterator,
+    // `for item in array.iter()`, has no indices to check in the first place.
+    // To see what the checks cost on your machine, run:
+    //
+    //     cargo run --release -p bench -- table bounds
+    //
+    // and again without --release, where nothing is optimized and every check
+    // stays in.
 }
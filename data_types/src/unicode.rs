@@ -0,0 +1,107 @@
+// What "one Unicode scalar value" does and doesn't mean. A char is one
+// number from the Unicode table, but what a reader sees as one letter can be
+// several of them, and changing a letter's case can turn one into two.
+//
+// The splitting into letters is in graphemes.rs, and the normalising is in
+// normalise.rs.
+
+use crate::graphemes::graphemes;
+use crate::normalise::{nfc, nfd};
+
+/// `c` in upper case, which isn't always one char.
+pub fn uppercase(c: char) -> String {
+    c.to_uppercase().collect()
+}
+
+/// `c` in lower case, which isn't always one char either.
+pub fn lowercase(c: char) -> String {
+    c.to_lowercase().collect()
+}
+
+/// `text` backwards one char at a time, which takes accents off the letters
+/// they were on.
+pub fn reverse_chars(text: &str) -> String {
+    text.chars().rev().collect()
+}
+
+/// `text` backwards one grapheme cluster at a time, which keeps each letter
+/// whole.
+pub fn reverse_graphemes(text: &str) -> String {
+    graphemes(text).into_iter().rev().collect()
+}
+
+pub fn changing_case(c: char) {
+    let upper = uppercase(c);
+    println!(
+        "{:?} in upper case is \"{}\", which is {} chars",
+        c,
+        upper,
+        upper.chars().count()
+    );
+    // It goes the other way too: the Turkish capital İ is an i and a
+    // combining dot above in lower case.
+    let lower = lowercase('İ');
+    println!(
+        "'İ' in lower case is \"{}\", which is {} chars",
+        lower,
+        lower.chars().count()
+    );
+}
+
+pub fn chars_and_graphemes() {
+    let examples = [
+        ("e\u{301}", "an e and a combining acute accent"),
+        ("🇩🇪", "two regional indicators, D and E"),
+        ("👍🏽", "a thumbs up and a skin tone"),
+        (
+            "👩\u{200d}👩\u{200d}👧",
+            "three people and two zero width joiners",
+        ),
+        ("\u{1112}\u{1161}\u{11ab}", "the three Korean letters of 한"),
+    ];
+    for (text, what) in examples.iter() {
+        println!(
+            "{} is {} bytes, {} chars and {} grapheme: {}",
+            text,
+            text.len(),
+            text.chars().count(),
+            graphemes(text).len(),
+            what
+        );
+    }
+}
+
+pub fn normalisation() {
+    for text in ["café", "Việt Nam", "한국어"].iter() {
+        let composed = nfc(text);
+        let decomposed = nfd(text);
+        println!(
+            "{}: {} bytes and {} chars in NFC, {} bytes and {} chars in NFD",
+            text,
+            composed.len(),
+            composed.chars().count(),
+            decomposed.len(),
+            decomposed.chars().count()
+        );
+    }
+    let composed = "caf\u{e9}";
+    let decomposed = "cafe\u{301}";
+    println!(
+        "{} == {} is {}, but once they're both NFC it's {}",
+        composed,
+        decomposed,
+        composed == decomposed,
+        nfc(composed) == nfc(decomposed)
+    );
+}
+
+pub fn reversing() {
+    for text in ["noe\u{308}l", "🇩🇪🇫🇷", "👍🏽"].iter() {
+        println!(
+            "{} reversed by chars is {}, and by graphemes is {}",
+            text,
+            reverse_chars(text),
+            reverse_graphemes(text)
+        );
+    }
+}
@@ -0,0 +1,93 @@
+// A look inside a str: which bytes each char takes up, the number Unicode
+// gives it (its code point), and how UTF-8 spells that number out in bits.
+// Then every range you could slice the str with, and whether it would crash.
+//
+// The lesson only comes here on one of its other paths, like when it's run
+// with CLUB_PATH=safe. Otherwise it crashes before it gets this far.
+
+use std::env;
+use std::ops::Range;
+
+/// Whether the lesson should slice "你好" safely instead of crashing, which
+/// it does on every path but the first, so that they get past it.
+pub fn safe_path() -> bool {
+    env::var("CLUB_PATH").is_ok()
+}
+
+/// Prints each char of `text`, with its byte range, its code point, and its
+/// bytes in binary.
+pub fn inspect(text: &str) {
+    println!("{:?} is {} bytes long:", text, text.len());
+    for (start, c) in text.char_indices() {
+        let end = start + c.len_utf8();
+        let bits: Vec<String> = text.as_bytes()[start..end]
+            .iter()
+            .map(|byte| format!("{:08b}", byte))
+            .collect();
+        println!(
+            "    {:?} is bytes {}..{}, U+{:04X}, {}",
+            c,
+            start,
+            end,
+            c as u32,
+            bits.join(" ")
+        );
+    }
+    // The first byte of a char says how many bytes the char has: 0xxxxxxx is
+    // a char on its own, 110xxxxx starts two bytes, 1110xxxx three, and
+    // 11110xxx four. Every byte after the first is 10xxxxxx. The x's,
+    // put together, are the code point.
+}
+
+/// Tries every range `text` could be sliced with, and prints what each one
+/// gives, or why it would crash.
+pub fn slices(text: &str) {
+    println!("Slicing {:?}:", text);
+    for start in 0..=text.len() {
+        for end in start + 1..=text.len() {
+            match text.get(start..end) {
+                Some(slice) => println!("    [{}..{}] is {:?}", start, end, slice),
+                None => {
+                    let inside = if text.is_char_boundary(start) {
+                        end
+                    } else {
+                        start
+                    };
+                    println!(
+                        "    [{}..{}] would crash: {} is inside {:?}",
+                        start,
+                        end,
+                        inside,
+                        char_at(text, inside)
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Slices `text` by chars instead of by bytes, so `0..1` is the first char
+/// however many bytes it has. `None` if the range goes past the end.
+pub fn slice_chars(text: &str, chars: Range<usize>) -> Option<&str> {
+    // Where each char starts, and then where the last one ends.
+    let mut boundaries = text
+        .char_indices()
+        .map(|(start, _)| start)
+        .chain(Some(text.len()));
+    let start = boundaries.nth(chars.start)?;
+    let end = if chars.end > chars.start {
+        boundaries.nth(chars.end - chars.start - 1)?
+    } else {
+        start
+    };
+    Some(&text[start..end])
+}
+
+/// The char that the byte at `index` is part of.
+fn char_at(text: &str, index: usize) -> char {
+    let start = (0..=index)
+        .rev()
+        .find(|&start| text.is_char_boundary(start))
+        .unwrap_or(0);
+    text[start..].chars().next().unwrap_or('?')
+}
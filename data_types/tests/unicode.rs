@@ -0,0 +1,108 @@
+//! Checks the Unicode demos' workings. The lesson is a binary, so its
+//! modules are included here by path.
+
+#[path = "../src/graphemes.rs"]
+mod graphemes;
+#[path = "../src/normalise.rs"]
+mod normalise;
+// The demos themselves only print, and what they print is in the lesson's
+// snapshot.
+#[allow(dead_code)]
+#[path = "../src/unicode.rs"]
+mod unicode;
+
+use graphemes::graphemes;
+use normalise::{nfc, nfd};
+use unicode::{lowercase, reverse_chars, reverse_graphemes, uppercase};
+
+#[test]
+fn changing_case_can_change_how_many_chars_there_are() {
+    assert_eq!(uppercase('ß'), "SS");
+    assert_eq!(uppercase('a'), "A");
+    assert_eq!(lowercase('İ'), "i\u{307}");
+}
+
+#[test]
+fn combining_marks_stay_with_their_letter() {
+    assert_eq!(graphemes("e\u{301}"), ["e\u{301}"]);
+    assert_eq!(graphemes("noe\u{308}l"), ["n", "o", "e\u{308}", "l"]);
+    // Devanagari vowel signs join the consonant before them. Before Unicode
+    // 15.1, which this follows, a virama doesn't join the next consonant.
+    assert_eq!(graphemes("नमस्ते"), ["न", "म", "स्", "ते"]);
+}
+
+#[test]
+fn emoji_sequences_are_one_grapheme() {
+    assert_eq!(graphemes("👍🏽"), ["👍🏽"]);
+    let family = "👩\u{200d}👩\u{200d}👧";
+    assert_eq!(graphemes(family), [family]);
+    // A joiner only glues emoji to emoji.
+    assert_eq!(graphemes("a\u{200d}👍"), ["a\u{200d}", "👍"]);
+}
+
+#[test]
+fn flags_are_pairs_of_regional_indicators() {
+    assert_eq!(graphemes("🇩🇪🇫🇷"), ["🇩🇪", "🇫🇷"]);
+    assert_eq!(graphemes("🇩🇪🇫"), ["🇩🇪", "🇫"]);
+}
+
+#[test]
+fn korean_letters_make_syllables() {
+    assert_eq!(graphemes("한국어"), ["한", "국", "어"]);
+    assert_eq!(
+        graphemes("\u{1112}\u{1161}\u{11ab}"),
+        ["\u{1112}\u{1161}\u{11ab}"]
+    );
+}
+
+#[test]
+fn line_breaks_are_their_own_graphemes() {
+    assert_eq!(graphemes("a\r\nb\n"), ["a", "\r\n", "b", "\n"]);
+    assert_eq!(graphemes(""), Vec::<&str>::new());
+}
+
+#[test]
+fn graphemes_cover_the_whole_text() {
+    let text = "Việt Nam 🇻🇳 e\u{301}\u{323} 👩\u{200d}👩\u{200d}👧!";
+    assert_eq!(graphemes(text).concat(), text);
+}
+
+#[test]
+fn nfd_takes_letters_apart_and_nfc_puts_them_back() {
+    assert_eq!(nfd("café"), "cafe\u{301}");
+    assert_eq!(nfc("cafe\u{301}"), "café");
+    assert_eq!(nfd("café").len(), nfc("café").len() + 1);
+    assert_eq!(nfd("한"), "\u{1112}\u{1161}\u{11ab}");
+    assert_eq!(nfc("\u{1112}\u{1161}\u{11ab}"), "한");
+    assert_eq!(nfc("\u{1112}\u{1161}"), "하");
+}
+
+#[test]
+fn marks_are_put_in_a_standard_order() {
+    // ệ has a dot below and a circumflex above, which could be written in
+    // either order.
+    assert_eq!(nfd("ệ"), "e\u{323}\u{302}");
+    assert_eq!(nfd("e\u{302}\u{323}"), "e\u{323}\u{302}");
+    assert_eq!(nfc("e\u{302}\u{323}"), "ệ");
+    // Two marks above stay in the order they were written.
+    assert_eq!(nfc("a\u{301}\u{308}"), "á\u{308}");
+}
+
+#[test]
+fn every_letter_the_tables_know_round_trips() {
+    let known = (0xc0..0x250).chain(0x1e00..0x1f00).chain(0xac00..0xd7a4);
+    for c in known.filter_map(char::from_u32) {
+        let text = c.to_string();
+        assert_eq!(nfc(&nfd(&text)), text, "{:?}", c);
+        assert_eq!(nfc(&text), text, "{:?}", c);
+    }
+}
+
+#[test]
+fn reversing_by_graphemes_keeps_letters_whole() {
+    assert_eq!(reverse_chars("noe\u{308}l"), "l\u{308}eon");
+    assert_eq!(reverse_graphemes("noe\u{308}l"), "le\u{308}on");
+    assert_eq!(reverse_chars("🇩🇪🇫🇷"), "🇷🇫🇪🇩");
+    assert_eq!(reverse_graphemes("🇩🇪🇫🇷"), "🇫🇷🇩🇪");
+    assert_eq!(reverse_graphemes("👍🏽"), "👍🏽");
+}
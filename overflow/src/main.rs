@@ -0,0 +1,103 @@
+// In the last lesson, `add` took two i32s and gave back their sum. But an i32
+// only has 32 bits, so it can only hold the numbers from -2147483648 to
+// 2147483647. What's the sum of 2147483647 and 1, then? In Java, adding one to
+// Integer.MAX_VALUE quietly wraps around to Integer.MIN_VALUE, and the program
+// carries on with a number nobody wanted. In Rust, it depends on how the
+// program was built.
+
+use std::convert::TryFrom;
+
+// The property tester is in properties.rs.
+mod properties;
+
+fn main() {
+    // If the compiler can see that an addition will overflow, it won't build
+    // the program at all.
+    // ```compile_fail(arithmetic_overflow): this arithmetic operation will overflow
+    // let too_big: i32 = i32::MAX + 1;
+    // ```
+
+    // Usually it can't see, because the numbers come from somewhere else. So
+    // when overflow could happen, say what should happen instead. Every
+    // integer type has a method for each choice:
+    println!("checked_add(2, 3) is {:?}", checked_add(2, 3));
+    println!("checked_add(i32::MAX, 1) is {:?}", checked_add(i32::MAX, 1));
+    println!("wrapping_add(i32::MAX, 1) is {}", wrapping_add(i32::MAX, 1));
+    println!(
+        "saturating_add(i32::MAX, 1) is {}",
+        saturating_add(i32::MAX, 1)
+    );
+    println!(
+        "overflowing_add(i32::MAX, 1) is {:?}",
+        overflowing_add(i32::MAX, 1)
+    );
+
+    // Those are a few examples, but do the functions do what they say for
+    // every pair of numbers? Each property below is checked against the sum
+    // worked out as an i64, which is big enough that adding two i32s can
+    // never overflow it.
+    properties::report(
+        "checked_add is the sum if it fits in an i32, and None if it doesn't",
+        |first, second| {
+            let sum = i64::from(first) + i64::from(second);
+            checked_add(first, second) == i32::try_from(sum).ok()
+        },
+    );
+    properties::report(
+        "wrapping_add is the sum, give or take 2^32",
+        |first, second| {
+            let sum = i64::from(first) + i64::from(second);
+            (sum - i64::from(wrapping_add(first, second))) % (1 << 32) == 0
+        },
+    );
+    properties::report(
+        "saturating_add is the sum, but no further than i32::MIN or i32::MAX",
+        |first, second| {
+            let sum = i64::from(first) + i64::from(second);
+            let limits = (i64::from(i32::MIN), i64::from(i32::MAX));
+            i64::from(saturating_add(first, second)) == sum.max(limits.0).min(limits.1)
+        },
+    );
+    properties::report(
+        "overflowing_add is wrapping_add, and whether checked_add was None",
+        |first, second| {
+            let wrapped = wrapping_add(first, second);
+            let overflowed = checked_add(first, second).is_none();
+            overflowing_add(first, second) == (wrapped, overflowed)
+        },
+    );
+
+    // And now plain `add`, with nothing said about overflow. `cargo run`
+    // makes a debug build, which checks every + for overflow, so this panics
+    // with "attempt to add with overflow". `cargo run --release` leaves the
+    // checks out to go faster, and it wraps around just like Java, printing
+    // -2147483648. `club run overflow` runs both.
+    println!("add(i32::MAX, 1) is {}", add(i32::MAX, 1));
+}
+
+fn add(first: i32, second: i32) -> i32 {
+    first + second
+}
+
+// The sum, or None if it doesn't fit. Good for when overflow means the input
+// was wrong.
+fn checked_add(first: i32, second: i32) -> Option<i32> {
+    first.checked_add(second)
+}
+
+// The sum, wrapped around like Java would. Good for hashes and checksums,
+// where wrapping is what you want.
+fn wrapping_add(first: i32, second: i32) -> i32 {
+    first.wrapping_add(second)
+}
+
+// The sum, but stopping at i32::MAX or i32::MIN. Good for things like volume
+// or health points, which should stop at the limit.
+fn saturating_add(first: i32, second: i32) -> i32 {
+    first.saturating_add(second)
+}
+
+// The wrapped sum, and whether it wrapped.
+fn overflowing_add(first: i32, second: i32) -> (i32, bool) {
+    first.overflowing_add(second)
+}
@@ -0,0 +1,80 @@
+// A very small property tester, like the quickcheck and proptest crates, but
+// written out here so there's nothing to download. A property is something
+// that should be true for every pair of i32s. There are far too many pairs to
+// try them all, so it tries every pair of the numbers where overflow happens,
+// and then lots of random pairs, and stops at the first pair it's false for.
+
+/// The numbers at the edges of an i32, where adding goes wrong if it's going
+/// to.
+pub const EDGES: [i32; 7] = [i32::MIN, i32::MIN + 1, -1, 0, 1, i32::MAX - 1, i32::MAX];
+
+/// How many random pairs each property is tried with, after the edges.
+pub const CASES: usize = 10_000;
+
+/// The seed the lesson uses, so that it prints the same thing every time.
+pub const SEED: u64 = 0x5EED;
+
+/// Random numbers from a xorshift generator, which is nowhere near good
+/// enough for cryptography, but is plenty for picking numbers to test with.
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        // A xorshift generator whose state is 0 only ever gives back 0.
+        Random { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// A random i32. Most are anywhere in the range, but a quarter are near
+    /// one of the edges, since that's where the bugs are.
+    pub fn i32(&mut self) -> i32 {
+        let near = (self.next_u64() % 16) as i32;
+        match self.next_u64() % 8 {
+            0 => i32::MAX - near,
+            1 => i32::MIN + near,
+            _ => self.next_u64() as i32,
+        }
+    }
+}
+
+/// A pair of numbers that a property was false for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Counterexample {
+    pub first: i32,
+    pub second: i32,
+}
+
+/// Tries `property` with every pair of [`EDGES`], then with [`CASES`] random
+/// pairs, and gives back the first pair it's false for.
+pub fn check(seed: u64, property: impl Fn(i32, i32) -> bool) -> Result<(), Counterexample> {
+    let mut random = Random::new(seed);
+    let edges = EDGES
+        .iter()
+        .flat_map(|&first| EDGES.iter().map(move |&second| (first, second)));
+    let random = (0..CASES).map(|_| (random.i32(), random.i32()));
+    match edges
+        .chain(random)
+        .find(|&(first, second)| !property(first, second))
+    {
+        Some((first, second)) => Err(Counterexample { first, second }),
+        None => Ok(()),
+    }
+}
+
+/// [`check`]s `property` with the lesson's seed, and prints whether it held.
+pub fn report(name: &str, property: impl Fn(i32, i32) -> bool) {
+    match check(SEED, property) {
+        Ok(()) => println!("ok: {} ({} pairs)", name, EDGES.len() * EDGES.len() + CASES),
+        Err(Counterexample { first, second }) => {
+            println!("FAILED: {}, for {} and {}", name, first, second)
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Checks the lesson's property tester. The lesson is a binary, so the
+//! module is included here by path.
+
+#[allow(dead_code)]
+#[path = "../src/properties.rs"]
+mod properties;
+
+use properties::{check, Counterexample, Random, EDGES, SEED};
+
+#[test]
+fn a_property_that_holds_has_no_counterexample() {
+    assert_eq!(
+        check(SEED, |first, second| first ^ second == second ^ first),
+        Ok(())
+    );
+}
+
+#[test]
+fn a_false_property_is_caught_at_the_edges() {
+    // Adding a positive number always makes it bigger, unless it wraps.
+    let counterexample = check(SEED, |first, second| {
+        second <= 0 || first.wrapping_add(second) > first
+    })
+    .unwrap_err();
+    assert_eq!(
+        counterexample,
+        Counterexample {
+            first: 1,
+            second: i32::MAX,
+        }
+    );
+}
+
+#[test]
+fn a_false_property_away_from_the_edges_is_caught_by_the_random_pairs() {
+    let counterexample = check(SEED, |first, _| first % 1000 != 123).unwrap_err();
+    assert_eq!(counterexample.first % 1000, 123);
+    assert!(!EDGES.contains(&counterexample.first));
+}
+
+#[test]
+fn the_same_seed_gives_the_same_numbers() {
+    let numbers = |seed| {
+        let mut random = Random::new(seed);
+        (0..100).map(|_| random.i32()).collect::<Vec<_>>()
+    };
+    assert_eq!(numbers(SEED), numbers(SEED));
+    assert_ne!(numbers(SEED), numbers(SEED + 1));
+    // A seed of 0 would be stuck at 0 forever.
+    assert!(numbers(0).iter().any(|&number| number != 0));
+}
+
+#[test]
+fn the_random_numbers_are_often_near_the_edges() {
+    let mut random = Random::new(SEED);
+    let near_an_edge = (0..10_000)
+        .map(|_| random.i32())
+        .filter(|&number| !(i32::MIN + 16..=i32::MAX - 16).contains(&number))
+        .count();
+    assert!((2000..3000).contains(&near_an_edge), "{}", near_an_edge);
+}
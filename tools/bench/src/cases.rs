@@ -0,0 +1,251 @@
+//! What gets measured, in two groups.
+//!
+//! The `arguments` group passes arguments the ways `variables_and_functions`
+//! does:
+//!
+//! - `copy i32` is `add(x, y)`. Both integers are copied into `add`.
+//! - `move String` is `say_hello_to_name(name)`. Only the `String` itself is
+//!   moved, which is a pointer, a capacity and a length, whatever its size.
+//!   `say_hello_to_name` hands it back, so that the same one can be moved
+//!   again.
+//! - `clone String` is `say_hello_to_name(name.clone())`, which is what it
+//!   takes to keep using `name` afterwards. Every byte is copied into a new
+//!   allocation, which `say_hello_to_name` frees when it's done.
+//! - `move Vec<i32>` and `clone Vec<i32>` are the same with a `Vec`.
+//!
+//! The `bounds` group sums an array of `i32`s the ways `data_types` could
+//! index one:
+//!
+//! - `index [i32]` is `for i in 0..count { sum += array[i] }`, with a count
+//!   that the compiler can't tell is the array's length, like one the user
+//!   typed in. Every `array[i]` checks that `i` is in bounds.
+//! - `index to len [i32]` is the same with `0..array.len()`. The compiler can
+//!   see that every index is in bounds, and leaves the checks out.
+//! - `iterate [i32]` is `for number in array.iter()`, which has no indices to
+//!   check.
+
+use std::fmt;
+use std::hint::black_box;
+use std::mem;
+
+use crate::measure::{self, Settings, Summary};
+
+/// The sizes of `String`s and `Vec`s measured, in bytes on the heap.
+pub const SIZES: [usize; 5] = [16, 256, 4 * 1024, 64 * 1024, 1024 * 1024];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Group {
+    Arguments,
+    Bounds,
+}
+
+impl Group {
+    pub const ALL: [Group; 2] = [Group::Arguments, Group::Bounds];
+
+    /// How it's picked on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Group::Arguments => "arguments",
+            Group::Bounds => "bounds",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    CopyI32,
+    MoveString,
+    CloneString,
+    MoveVec,
+    CloneVec,
+    Index,
+    IndexToLen,
+    Iterate,
+}
+
+impl Kind {
+    pub fn group(self) -> Group {
+        match self {
+            Kind::CopyI32
+            | Kind::MoveString
+            | Kind::CloneString
+            | Kind::MoveVec
+            | Kind::CloneVec => Group::Arguments,
+            Kind::Index | Kind::IndexToLen | Kind::Iterate => Group::Bounds,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Case {
+    pub kind: Kind,
+    /// The bytes being passed: 4 for an `i32`, and what's on the heap for a
+    /// `String` or a `Vec`. For the `bounds` group, the size of the array.
+    pub bytes: usize,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Kind::CopyI32 => write!(f, "copy i32"),
+            Kind::MoveString => write!(f, "move String"),
+            Kind::CloneString => write!(f, "clone String"),
+            Kind::MoveVec => write!(f, "move Vec<i32>"),
+            Kind::CloneVec => write!(f, "clone Vec<i32>"),
+            Kind::Index => write!(f, "index [i32]"),
+            Kind::IndexToLen => write!(f, "index to len [i32]"),
+            Kind::Iterate => write!(f, "iterate [i32]"),
+        }
+    }
+}
+
+/// Every case, smallest first within each kind.
+pub fn all() -> Vec<Case> {
+    let mut cases = vec![Case {
+        kind: Kind::CopyI32,
+        bytes: mem::size_of::<i32>(),
+    }];
+    for kind in &[
+        Kind::MoveString,
+        Kind::CloneString,
+        Kind::MoveVec,
+        Kind::CloneVec,
+        Kind::Index,
+        Kind::IndexToLen,
+        Kind::Iterate,
+    ] {
+        cases.extend(SIZES.iter().map(|&bytes| Case { kind: *kind, bytes }));
+    }
+    cases
+}
+
+/// The cases in one group, in the same order as [`all`].
+pub fn of(group: Group) -> Vec<Case> {
+    all()
+        .into_iter()
+        .filter(|case| case.kind.group() == group)
+        .collect()
+}
+
+/// A case and how long it took.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    pub case: Case,
+    pub summary: Summary,
+}
+
+pub fn measure(case: Case, settings: &Settings) -> Measurement {
+    let summary = match case.kind {
+        Kind::CopyI32 => {
+            let (x, y) = (5, 6);
+            measure::measure(settings, || {
+                black_box(add(black_box(x), black_box(y)));
+            })
+        }
+        Kind::MoveString => {
+            let mut name = "a".repeat(case.bytes);
+            measure::measure(settings, || {
+                name = say_hello_to_name(black_box(mem::take(&mut name)));
+            })
+        }
+        Kind::CloneString => {
+            let name = "a".repeat(case.bytes);
+            measure::measure(settings, || {
+                black_box(say_hello_to_name(black_box(&name).clone()));
+            })
+        }
+        Kind::MoveVec => {
+            let mut numbers = numbers(case.bytes);
+            measure::measure(settings, || {
+                numbers = sum(black_box(mem::take(&mut numbers)));
+            })
+        }
+        Kind::CloneVec => {
+            let numbers = numbers(case.bytes);
+            measure::measure(settings, || {
+                black_box(sum(black_box(&numbers).clone()));
+            })
+        }
+        Kind::Index => {
+            let numbers = numbers(case.bytes);
+            measure::measure(settings, || {
+                black_box(sum_by_index(black_box(&numbers), black_box(numbers.len())));
+            })
+        }
+        Kind::IndexToLen => {
+            let numbers = numbers(case.bytes);
+            measure::measure(settings, || {
+                black_box(sum_to_len(black_box(&numbers)));
+            })
+        }
+        Kind::Iterate => {
+            let numbers = numbers(case.bytes);
+            measure::measure(settings, || {
+                black_box(sum_by_iterator(black_box(&numbers)));
+            })
+        }
+    };
+    Measurement { case, summary }
+}
+
+/// `add` from `variables_and_functions`.
+#[inline(never)]
+fn add(first: i32, second: i32) -> i32 {
+    first + second
+}
+
+/// `say_hello_to_name` from `variables_and_functions`, but without the
+/// printing, which would take longer than everything else put together.
+#[inline(never)]
+fn say_hello_to_name(name: String) -> String {
+    black_box(&name);
+    name
+}
+
+/// Takes a `Vec` the way `say_hello_to_name` takes a `String`.
+#[inline(never)]
+fn sum(numbers: Vec<i32>) -> Vec<i32> {
+    black_box(&numbers);
+    numbers
+}
+
+/// Sums the first `count` numbers, checking each index. Wrapping, since
+/// a megabyte of numbers adds up to more than an `i32` holds.
+// Indexing is what's being measured, so clippy's iterator suggestion is
+// beside the point.
+#[inline(never)]
+#[allow(clippy::needless_range_loop)]
+fn sum_by_index(numbers: &[i32], count: usize) -> i32 {
+    let mut total: i32 = 0;
+    for i in 0..count {
+        total = total.wrapping_add(numbers[i]);
+    }
+    total
+}
+
+/// The same, up to the length of `numbers`, so every index is in bounds.
+#[inline(never)]
+#[allow(clippy::needless_range_loop)]
+fn sum_to_len(numbers: &[i32]) -> i32 {
+    let mut total: i32 = 0;
+    for i in 0..numbers.len() {
+        total = total.wrapping_add(numbers[i]);
+    }
+    total
+}
+
+/// The same, without any indices.
+#[inline(never)]
+fn sum_by_iterator(numbers: &[i32]) -> i32 {
+    let mut total: i32 = 0;
+    for number in numbers.iter() {
+        total = total.wrapping_add(*number);
+    }
+    total
+}
+
+/// A `Vec` with `bytes` worth of numbers.
+fn numbers(bytes: usize) -> Vec<i32> {
+    let count = bytes / mem::size_of::<i32>();
+    (0..count as i32).collect()
+}
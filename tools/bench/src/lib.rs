@@ -0,0 +1,33 @@
+//! bench: what copying, moving and bounds checks actually cost.
+//!
+//! `variables_and_functions` says integers are copied because "copying an
+//! integer is a very inexpensive operation, versus copying a whole Vec or
+//! String into a function", and `data_types` says indexing an array checks
+//! the index. This measures both, with [`measure`] timing each of the
+//! [`cases`] with `std::time::Instant`, and [`report`] putting the results
+//! in a table or as CSV.
+//!
+//! The numbers only mean something in a release build.
+
+pub mod cases;
+pub mod measure;
+pub mod report;
+
+use crate::cases::{Case, Measurement};
+use crate::measure::Settings;
+
+/// Measures each case, calling `done` after each one.
+pub fn run(
+    cases: &[Case],
+    settings: &Settings,
+    mut done: impl FnMut(&Measurement),
+) -> Vec<Measurement> {
+    cases
+        .iter()
+        .map(|&case| {
+            let measurement = cases::measure(case, settings);
+            done(&measurement);
+            measurement
+        })
+        .collect()
+}
@@ -0,0 +1,114 @@
+//! `bench` measures copying an `i32` against moving and cloning `String`s
+//! and `Vec`s, and summing an array by index against with an iterator.
+
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::process;
+
+use bench::cases::{self, Case, Group};
+use bench::measure::Settings;
+
+const USAGE: &str = "\
+usage: bench <command> [<group>] [--quick]
+
+commands:
+    table [--csv <file>]
+                  measure the cases and print a table, and write the
+                  numbers to <file> as CSV too
+    csv           measure the cases and print the numbers as CSV
+    list          list the cases
+
+groups:
+    arguments     copying, moving and cloning arguments
+    bounds        summing an array by index and with an iterator
+
+Every group is measured if none is given. --quick takes fewer, shorter
+samples. Build with --release, or the numbers are for unoptimized code.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match bench(&args) {
+        Ok(()) => {}
+        Err(error) => {
+            eprintln!("bench: {}", error);
+            process::exit(2);
+        }
+    }
+}
+
+fn bench(args: &[&str]) -> Result<(), String> {
+    let quick = args.contains(&"--quick");
+    let group = |arg: &str| Group::ALL.iter().copied().find(|group| group.name() == arg);
+    let cases = match args.iter().find_map(|arg| group(arg)) {
+        Some(group) => cases::of(group),
+        None => cases::all(),
+    };
+    let args: Vec<&str> = args
+        .iter()
+        .copied()
+        .filter(|arg| *arg != "--quick" && group(arg).is_none())
+        .collect();
+    let settings = if quick {
+        Settings::quick()
+    } else {
+        Settings::default()
+    };
+
+    match args.as_slice() {
+        ["table"] => {
+            let measurements = run(&cases, &settings);
+            print!("{}", bench::report::table(&measurements));
+            Ok(())
+        }
+        ["table", "--csv", file] => {
+            let measurements = run(&cases, &settings);
+            print!("{}", bench::report::table(&measurements));
+            fs::write(file, bench::report::csv(&measurements))
+                .map_err(|error| format!("{}: {}", file, error))
+        }
+        ["csv"] => {
+            let measurements = run(&cases, &settings);
+            print!("{}", bench::report::csv(&measurements));
+            Ok(())
+        }
+        ["list"] => {
+            for case in &cases {
+                println!("{} ({} bytes)", case.kind, case.bytes);
+            }
+            Ok(())
+        }
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+/// Measures every case, with progress on stderr when it's a terminal, so
+/// that stdout only has the results.
+fn run(cases: &[Case], settings: &Settings) -> Vec<cases::Measurement> {
+    if cfg!(debug_assertions) {
+        eprintln!("bench: this is a debug build; run with --release for real numbers");
+    }
+    let progress = io::stderr().is_terminal();
+    let total = cases.len();
+    let mut finished = 0;
+    let measurements = bench::run(cases, settings, |measurement| {
+        finished += 1;
+        if progress {
+            eprint!(
+                "\rmeasured {}/{}: {} ({} bytes)\x1b[K",
+                finished, total, measurement.case.kind, measurement.case.bytes
+            );
+            let _ = io::stderr().flush();
+        }
+    });
+    if progress {
+        eprint!("\r\x1b[K");
+    }
+    measurements
+}
@@ -0,0 +1,153 @@
+//! Timing a piece of code with nothing but `Instant`, and summarising the
+//! times.
+//!
+//! A routine is run over and over in samples. Before sampling, it runs for a
+//! while to warm the caches up and to work out how many iterations make a
+//! sample last about `sample_time`, so that each sample is long enough for
+//! the clock to measure well. Each sample gives a time per iteration, and the
+//! samples are summarised by their median, mean and spread.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    pub samples: usize,
+    /// Roughly how long each sample runs for.
+    pub sample_time: Duration,
+    /// How long to run the routine before sampling.
+    pub warm_up: Duration,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            samples: 30,
+            sample_time: Duration::from_millis(5),
+            warm_up: Duration::from_millis(50),
+        }
+    }
+}
+
+impl Settings {
+    /// Fewer and shorter samples, for a rough idea in a hurry.
+    pub fn quick() -> Settings {
+        Settings {
+            samples: 10,
+            sample_time: Duration::from_millis(1),
+            warm_up: Duration::from_millis(10),
+        }
+    }
+}
+
+/// Times `routine`, one iteration per call. Whatever it computes should go
+/// through `std::hint::black_box`, so that the compiler can't skip it.
+pub fn measure(settings: &Settings, mut routine: impl FnMut()) -> Summary {
+    let mut run = |iterations: u64| {
+        let started = Instant::now();
+        for _ in 0..iterations {
+            routine();
+        }
+        started.elapsed()
+    };
+
+    // Doubling the iterations until a batch takes a whole sample time also
+    // warms up.
+    let mut iterations = 1;
+    let warm_up = Instant::now();
+    loop {
+        let elapsed = run(iterations);
+        if elapsed >= settings.sample_time {
+            if warm_up.elapsed() >= settings.warm_up {
+                break;
+            }
+        } else {
+            iterations *= 2;
+        }
+    }
+
+    let times: Vec<f64> = (0..settings.samples.max(1))
+        .map(|_| run(iterations).as_nanos() as f64 / iterations as f64)
+        .collect();
+    Summary::of(&times, iterations)
+}
+
+/// Nanoseconds per iteration, over every sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub samples: usize,
+    /// How many iterations each sample ran.
+    pub iterations: u64,
+    pub median: f64,
+    pub mean: f64,
+    /// The sample standard deviation.
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Samples more than one and a half interquartile ranges outside the
+    /// middle half, which usually means something else had the CPU.
+    pub outliers: usize,
+}
+
+impl Summary {
+    /// Summarises times, which mustn't be empty.
+    pub fn of(times: &[f64], iterations: u64) -> Summary {
+        let mut sorted = times.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let samples = sorted.len();
+        let mean = sorted.iter().sum::<f64>() / samples as f64;
+        let variance = if samples > 1 {
+            sorted.iter().map(|time| (time - mean).powi(2)).sum::<f64>() / (samples - 1) as f64
+        } else {
+            0.0
+        };
+
+        let (low, high) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+        let fence = 1.5 * (high - low);
+        let outliers = sorted
+            .iter()
+            .filter(|time| **time < low - fence || **time > high + fence)
+            .count();
+
+        Summary {
+            samples,
+            iterations,
+            median: quantile(&sorted, 0.5),
+            mean,
+            std_dev: variance.sqrt(),
+            min: sorted[0],
+            max: sorted[samples - 1],
+            outliers,
+        }
+    }
+}
+
+/// The value a fraction of the way through sorted values, interpolating
+/// between the two nearest.
+fn quantile(sorted: &[f64], fraction: f64) -> f64 {
+    let position = fraction * (sorted.len() - 1) as f64;
+    let below = position.floor() as usize;
+    let above = position.ceil() as usize;
+    let weight = position - below as f64;
+    sorted[below] * (1.0 - weight) + sorted[above] * weight
+}
+
+/// `1.23 ns`, `45.6 µs` or `7.89 ms`, with three significant figures.
+pub fn duration(nanos: f64) -> String {
+    let (value, unit) = if nanos < 1e3 {
+        (nanos, "ns")
+    } else if nanos < 1e6 {
+        (nanos / 1e3, "µs")
+    } else if nanos < 1e9 {
+        (nanos / 1e6, "ms")
+    } else {
+        (nanos / 1e9, "s")
+    };
+    let decimals = if value < 10.0 {
+        2
+    } else if value < 100.0 {
+        1
+    } else {
+        0
+    };
+    format!("{:.*} {}", decimals, value, unit)
+}
@@ -0,0 +1,100 @@
+//! Measurements as a table to read, or as CSV to plot.
+
+use crate::cases::{Kind, Measurement};
+use crate::measure::duration;
+
+/// One row per case. The last column compares each median with copying an
+/// `i32`.
+pub fn table(measurements: &[Measurement]) -> String {
+    let baseline = measurements
+        .iter()
+        .find(|measurement| measurement.case.kind == Kind::CopyI32)
+        .map(|measurement| measurement.summary.median);
+
+    let header = [
+        "case",
+        "bytes",
+        "median",
+        "mean",
+        "± std dev",
+        "min",
+        "max",
+        "outliers",
+        "× i32",
+    ];
+    let mut rows: Vec<Vec<String>> = vec![header.iter().map(|cell| cell.to_string()).collect()];
+    for Measurement { case, summary } in measurements {
+        rows.push(vec![
+            case.kind.to_string(),
+            case.bytes.to_string(),
+            duration(summary.median),
+            duration(summary.mean),
+            duration(summary.std_dev),
+            duration(summary.min),
+            duration(summary.max),
+            format!("{}/{}", summary.outliers, summary.samples),
+            baseline.map_or(String::new(), |baseline| {
+                format!("{:.1}", summary.median / baseline)
+            }),
+        ]);
+    }
+
+    let columns = header.len();
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let mut text = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                // The case is text, and everything else is a number.
+                let padding = " ".repeat(width - cell.chars().count());
+                if column == 0 {
+                    format!("{}{}", cell, padding)
+                } else {
+                    format!("{}{}", padding, cell)
+                }
+            })
+            .collect();
+        text.push_str(cells.join("  ").trim_end());
+        text.push('\n');
+        if index == 0 {
+            let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+            text.push_str(&rule.join("  "));
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// A header, then one line per case, with times in nanoseconds per
+/// iteration.
+pub fn csv(measurements: &[Measurement]) -> String {
+    let mut text = String::from(
+        "case,bytes,samples,iterations,median_ns,mean_ns,std_dev_ns,min_ns,max_ns,outliers\n",
+    );
+    for Measurement { case, summary } in measurements {
+        text.push_str(&format!(
+            "{},{},{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{}\n",
+            case.kind,
+            case.bytes,
+            summary.samples,
+            summary.iterations,
+            summary.median,
+            summary.mean,
+            summary.std_dev,
+            summary.min,
+            summary.max,
+            summary.outliers
+        ));
+    }
+    text
+}
@@ -0,0 +1,135 @@
+//! Summarises made-up times, and takes a few quick real measurements.
+
+use std::time::Duration;
+
+use bench::cases::{self, Case, Group, Kind, Measurement};
+use bench::measure::{duration, Settings, Summary};
+use bench::report;
+
+fn summary(times: &[f64]) -> Summary {
+    Summary::of(times, 1000)
+}
+
+#[test]
+fn times_are_summarised() {
+    let summary = summary(&[4.0, 1.0, 3.0, 2.0, 5.0]);
+    assert_eq!(summary.samples, 5);
+    assert_eq!(summary.median, 3.0);
+    assert_eq!(summary.mean, 3.0);
+    assert!((summary.std_dev - 2.5f64.sqrt()).abs() < 1e-9);
+    assert_eq!((summary.min, summary.max), (1.0, 5.0));
+    assert_eq!(summary.outliers, 0);
+
+    // The median of an even number of times is halfway between the middle
+    // two.
+    assert_eq!(self::summary(&[1.0, 2.0, 3.0, 10.0]).median, 2.5);
+}
+
+#[test]
+fn a_time_far_from_the_rest_is_an_outlier() {
+    let summary = summary(&[10.0, 11.0, 10.0, 12.0, 11.0, 10.0, 90.0]);
+    assert_eq!(summary.outliers, 1);
+    assert_eq!(summary.median, 11.0);
+    assert_eq!(self::summary(&[7.0]).std_dev, 0.0);
+}
+
+#[test]
+fn durations_have_three_significant_figures() {
+    assert_eq!(duration(1.234), "1.23 ns");
+    assert_eq!(duration(45.67), "45.7 ns");
+    assert_eq!(duration(2_280.0), "2.28 µs");
+    assert_eq!(duration(49_512.0), "49.5 µs");
+    assert_eq!(duration(123_456_789.0), "123 ms");
+}
+
+#[test]
+fn the_csv_has_a_line_per_case() {
+    let measurements = vec![
+        Measurement {
+            case: Case {
+                kind: Kind::CopyI32,
+                bytes: 4,
+            },
+            summary: summary(&[2.0, 2.0]),
+        },
+        Measurement {
+            case: Case {
+                kind: Kind::CloneVec,
+                bytes: 4096,
+            },
+            summary: summary(&[70.0, 72.0]),
+        },
+    ];
+    let csv = report::csv(&measurements);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "case,bytes,samples,iterations,median_ns,mean_ns,std_dev_ns,min_ns,max_ns,outliers",
+            "copy i32,4,2,1000,2.000,2.000,0.000,2.000,2.000,0",
+            "clone Vec<i32>,4096,2,1000,71.000,71.000,1.414,70.000,72.000,0",
+        ]
+    );
+
+    let table = report::table(&measurements);
+    assert!(table.lines().nth(3).unwrap().ends_with("35.5"), "{}", table);
+}
+
+#[test]
+fn every_kind_is_measured_at_every_size() {
+    let all = cases::all();
+    assert_eq!(all.len(), 1 + 7 * cases::SIZES.len());
+    assert_eq!(all[0].kind, Kind::CopyI32);
+
+    let arguments = cases::of(Group::Arguments);
+    assert_eq!(arguments.len(), 1 + 4 * cases::SIZES.len());
+    let bounds = cases::of(Group::Bounds);
+    assert_eq!(bounds.len(), 3 * cases::SIZES.len());
+    assert!(bounds.iter().all(|case| case.kind.group() == Group::Bounds));
+}
+
+#[test]
+fn every_way_of_summing_stays_in_bounds() {
+    let settings = Settings {
+        samples: 2,
+        sample_time: Duration::from_millis(1),
+        warm_up: Duration::from_millis(0),
+    };
+    // An index out of bounds would panic.
+    for case in cases::of(Group::Bounds) {
+        let measurement = cases::measure(case, &settings);
+        assert_eq!(measurement.summary.samples, 2);
+    }
+}
+
+#[test]
+fn cloning_a_megabyte_costs_more_than_moving_it() {
+    let settings = Settings {
+        samples: 5,
+        sample_time: Duration::from_millis(1),
+        warm_up: Duration::from_millis(0),
+    };
+    let megabyte = 1024 * 1024;
+    let moved = cases::measure(
+        Case {
+            kind: Kind::MoveString,
+            bytes: megabyte,
+        },
+        &settings,
+    );
+    let cloned = cases::measure(
+        Case {
+            kind: Kind::CloneString,
+            bytes: megabyte,
+        },
+        &settings,
+    );
+    // Even unoptimized, copying a megabyte takes far longer than copying
+    // the three words of a `String`.
+    assert!(
+        cloned.summary.median > 10.0 * moved.summary.median,
+        "cloning took {} and moving took {}",
+        duration(cloned.summary.median),
+        duration(moved.summary.median)
+    );
+}
@@ -0,0 +1,203 @@
+//! `club exercise`: work through the exercises, with the hidden tests re-run
+//! every time the file is saved.
+
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use lessons::exercise::{self, Attempt, Exercise, Progress};
+use lessons::{state, Error, Workspace};
+
+use crate::terminal::{self, BOLD, DIM, GREEN, RED, RESET, YELLOW};
+use crate::watcher::Watcher;
+
+/// `club exercises`: every exercise, and which ones are done.
+pub fn list(workspace: &Workspace) {
+    let exercises = exercise::all(workspace);
+    let progress = Progress::load(&workspace.root);
+    let next = progress.next(&exercises).map(Exercise::id);
+
+    for exercise in &exercises {
+        let id = exercise.id();
+        let marker = if progress.is_done(exercise) {
+            "✓"
+        } else if Some(&id) == next.as_ref() {
+            ">"
+        } else {
+            " "
+        };
+        println!(" {} {}  {}", marker, id, workspace.relative(&exercise.path));
+    }
+    println!();
+    println!(
+        "{}/{} done",
+        progress_count(&progress, &exercises),
+        exercises.len()
+    );
+}
+
+/// `club verify`: checks the exercises once, without watching. Returns whether
+/// they all passed.
+pub fn verify(workspace: &Workspace, names: &[&str]) -> Result<bool, Error> {
+    let exercises = exercise::all(workspace);
+    let mut progress = Progress::load(&workspace.root);
+    let mut all_passed = true;
+
+    for exercise in &exercises {
+        if !names.is_empty()
+            && !names
+                .iter()
+                .any(|name| *name == exercise.id() || *name == exercise.name)
+        {
+            continue;
+        }
+        let attempt = exercise::check(exercise, &scratch(&workspace.root, exercise))?;
+        println!("{}: {}", exercise.id(), attempt);
+        if attempt.passed() {
+            progress.complete(&workspace.root, exercise)?;
+        } else {
+            all_passed = false;
+        }
+    }
+    Ok(all_passed)
+}
+
+/// `club exercise [<name>]`: watches an exercise, re-running its tests on every
+/// save, and moves on to the next one once they pass.
+pub fn watch(workspace: &Workspace, name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let exercises = exercise::all(workspace);
+    let mut progress = Progress::load(&workspace.root);
+
+    let mut current = match name {
+        Some(name) => exercise::find(&exercises, name)
+            .ok_or_else(|| format!("there is no exercise called `{}`", name))?,
+        None => match progress.next(&exercises) {
+            Some(exercise) => exercise,
+            None => {
+                println!("You've done every exercise! Use `club exercise <name>` to do one again.");
+                return Ok(());
+            }
+        },
+    };
+    let mut passed: Option<String> = None;
+
+    loop {
+        // Watching from before the check, so that a save while it runs
+        // isn't missed.
+        let mut watcher = Watcher::new(exercises_dir(current));
+        let attempt = exercise::check(current, &scratch(&workspace.root, current))?;
+        if attempt.passed() {
+            progress.complete(&workspace.root, current)?;
+            let next = exercises
+                .iter()
+                .skip_while(|exercise| exercise.id() != current.id())
+                .skip(1)
+                .find(|exercise| !progress.is_done(exercise))
+                .or_else(|| progress.next(&exercises));
+            match next {
+                Some(next) => {
+                    passed = Some(current.id());
+                    current = next;
+                    continue;
+                }
+                None => {
+                    draw(workspace, &exercises, &progress, current, &attempt, None)?;
+                    println!();
+                    println!(
+                        "{}That was the last exercise left. Well done!{}",
+                        GREEN, RESET
+                    );
+                    return Ok(());
+                }
+            }
+        }
+
+        draw(
+            workspace,
+            &exercises,
+            &progress,
+            current,
+            &attempt,
+            passed.take().as_deref(),
+        )?;
+        watcher.wait()?;
+    }
+}
+
+fn draw(
+    workspace: &Workspace,
+    exercises: &[Exercise],
+    progress: &Progress,
+    exercise: &Exercise,
+    attempt: &Attempt,
+    passed: Option<&str>,
+) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    if stdout.is_terminal() {
+        write!(stdout, "{}", terminal::CLEAR)?;
+    }
+
+    if let Some(passed) = passed {
+        writeln!(stdout, "{}✓ {} passed!{}\n", GREEN, passed, RESET)?;
+    }
+
+    let done = progress_count(progress, exercises);
+    let width = 30;
+    let filled = width * done / exercises.len().max(1);
+    writeln!(
+        stdout,
+        "{}{}{} {}({}){}",
+        BOLD,
+        exercise.id(),
+        RESET,
+        DIM,
+        workspace.relative(&exercise.path),
+        RESET
+    )?;
+    writeln!(
+        stdout,
+        "[{}{}{}{}] {}/{} done\n",
+        GREEN,
+        "█".repeat(filled),
+        "░".repeat(width - filled),
+        RESET,
+        done,
+        exercises.len()
+    )?;
+
+    let color = match attempt {
+        Attempt::Passed => GREEN,
+        Attempt::Unfinished(_) => YELLOW,
+        _ => RED,
+    };
+    let output = attempt.output().trim_end();
+    if !output.is_empty() {
+        writeln!(stdout, "{}\n", output)?;
+    }
+    writeln!(stdout, "{}{}{}", color, attempt, RESET)?;
+    if attempt.passed() {
+        return stdout.flush();
+    }
+    writeln!(
+        stdout,
+        "{}Edit the file and save it to try again. Ctrl-C to stop.{}",
+        DIM, RESET
+    )?;
+    stdout.flush()
+}
+
+/// The directory the student's copy is in, which is what's watched.
+fn exercises_dir(exercise: &Exercise) -> &Path {
+    exercise.path.parent().unwrap_or_else(|| Path::new("."))
+}
+
+/// Where an exercise is compiled.
+fn scratch(root: &Path, exercise: &Exercise) -> PathBuf {
+    state::scratch(root, &format!("{}-{}", exercise.lesson, exercise.name))
+}
+
+fn progress_count(progress: &Progress, exercises: &[Exercise]) -> usize {
+    exercises
+        .iter()
+        .filter(|exercise| progress.is_done(exercise))
+        .count()
+}
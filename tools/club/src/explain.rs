@@ -0,0 +1,97 @@
+//! `club explain`: compiler errors, with the part of the lesson that explains
+//! them printed underneath.
+
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+use lessons::diagnostic::Diagnostic;
+use lessons::explain::{self, Explainer, Section};
+use lessons::{Lesson, Workspace};
+
+use crate::terminal::{BOLD, CYAN, RESET};
+
+/// `club explain <lesson>`: builds a lesson and prints its errors, annotated.
+/// Returns whether it built.
+pub fn lesson(workspace: &Workspace, lesson: &Lesson) -> Result<bool, lessons::Error> {
+    let explainer = Explainer::load(workspace)?;
+    let diagnostics = explain::build(&workspace.root, lesson)?;
+    let errors: Vec<&Diagnostic> = diagnostics.iter().filter(|d| d.is_error()).collect();
+
+    if errors.is_empty() {
+        println!("{} builds without errors", lesson.name);
+        return Ok(true);
+    }
+    let color = io::stdout().is_terminal();
+    for error in errors {
+        print!(
+            "{}",
+            annotate(error, &explainer, lesson, &workspace.root, color)
+        );
+    }
+    Ok(false)
+}
+
+/// `club explain E0382`: every section that explains an error code.
+pub fn code(workspace: &Workspace, code: &str) -> Result<bool, lessons::Error> {
+    let explainer = Explainer::load(workspace)?;
+    let color = io::stdout().is_terminal();
+    let mut found = false;
+    for section in explainer.all(code) {
+        found = true;
+        print!("{}", excerpt(section, &workspace.root, color));
+    }
+    if !found {
+        println!(
+            "None of the lessons explain {}. Try `rustc --explain {}`.",
+            code, code
+        );
+    }
+    Ok(found)
+}
+
+/// An error as rustc renders it, followed by the lesson's explanation if it
+/// has one. This is also what `club watch` shows.
+pub fn annotate(
+    error: &Diagnostic,
+    explainer: &Explainer,
+    lesson: &Lesson,
+    root: &Path,
+    color: bool,
+) -> String {
+    let mut text = match &error.rendered {
+        Some(rendered) => rendered.clone(),
+        None => format!("error: {}\n", error.message),
+    };
+    let section = error
+        .code
+        .as_deref()
+        .and_then(|code| explainer.explain(code, Some(&lesson.name)));
+    if let Some(section) = section {
+        text.push_str(&excerpt(section, root, color));
+    }
+    text
+}
+
+/// A section of a lesson, set off from the compiler's output.
+fn excerpt(section: &Section, root: &Path, color: bool) -> String {
+    let (bold, cyan, reset) = if color {
+        (BOLD, CYAN, RESET)
+    } else {
+        ("", "", "")
+    };
+    let path = section.path.strip_prefix(root).unwrap_or(&section.path);
+
+    let mut text = format!(
+        "{}{}  = the lesson explains this at {}:{}{}\n",
+        cyan,
+        bold,
+        path.display(),
+        section.line,
+        reset
+    );
+    for line in &section.lines {
+        text.push_str(&format!("{}  │{} {}\n", cyan, reset, line));
+    }
+    text.push('\n');
+    text
+}
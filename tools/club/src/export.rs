@@ -0,0 +1,372 @@
+//! `club export`: the lessons as HTML slide decks.
+//!
+//! Every deck is a single file with its styles and script inlined, so the
+//! decks work from a USB stick on a projector with no network. There is one
+//! deck per lesson, and an `index.html` deck that links them together.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use lessons::source::{self, Item, Kind, Slide};
+use lessons::{Lesson, Workspace};
+
+use crate::highlight::{self, escape};
+
+/// Writes every deck into `out`, returning the files it wrote.
+pub fn export(workspace: &Workspace, out: &Path) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(out)?;
+    let mut written = Vec::new();
+
+    let lessons = &workspace.lessons;
+    for (index, lesson) in lessons.iter().enumerate() {
+        let source = fs::read_to_string(lesson.main())?;
+        let mut slides = vec![title_slide(lesson, index, lessons.len())];
+        slides.extend(source::slides(&source).iter().map(slide));
+
+        let next = lessons.get(index + 1).map(|next| {
+            (
+                format!("{}.html", next.name),
+                format!("Next: {}", next.title),
+            )
+        });
+        let html = page(&lesson.title, &slides, next);
+
+        let path = out.join(format!("{}.html", lesson.name));
+        fs::write(&path, html)?;
+        written.push(path);
+    }
+
+    let path = out.join("index.html");
+    fs::write(&path, index(lessons))?;
+    written.push(path);
+
+    Ok(written)
+}
+
+/// The workspace deck: a title slide, then a slide per lesson.
+fn index(lessons: &[Lesson]) -> String {
+    let mut slides = Vec::new();
+
+    let mut contents = String::from("<h1>Rust Code Club</h1>\n<ol class=\"lessons\">\n");
+    for lesson in lessons {
+        contents.push_str(&format!(
+            "<li><a href=\"{}.html\">{}</a></li>\n",
+            escape(&lesson.name),
+            escape(&lesson.title)
+        ));
+    }
+    contents.push_str("</ol>\n<p class=\"hint\">Press a number to open that lesson.</p>\n");
+    slides.push(contents);
+
+    for (index, lesson) in lessons.iter().enumerate() {
+        let mut slide = format!(
+            "<p class=\"kicker\">Lesson {} of {}</p>\n<h1><a href=\"{}.html\">{}</a></h1>\n",
+            index + 1,
+            lessons.len(),
+            escape(&lesson.name),
+            escape(&lesson.title)
+        );
+        slide.push_str(&concepts(lesson));
+        if !lesson.prerequisites.is_empty() {
+            let after: Vec<String> = lesson
+                .prerequisites
+                .iter()
+                .map(|name| format!("<a href=\"{0}.html\">{0}</a>", escape(name)))
+                .collect();
+            slide.push_str(&format!("<p>Builds on {}.</p>\n", after.join(", ")));
+        }
+        slides.push(slide);
+    }
+
+    page("Rust Code Club", &slides, None)
+}
+
+fn title_slide(lesson: &Lesson, index: usize, count: usize) -> String {
+    format!(
+        "<p class=\"kicker\">Lesson {} of {}</p>\n<h1>{}</h1>\n{}",
+        index + 1,
+        count,
+        escape(&lesson.title),
+        concepts(lesson)
+    )
+}
+
+fn concepts(lesson: &Lesson) -> String {
+    if lesson.concepts.is_empty() {
+        return String::new();
+    }
+    let items: Vec<String> = lesson
+        .concepts
+        .iter()
+        .map(|concept| format!("<li>{}</li>", escape(concept)))
+        .collect();
+    format!("<ul class=\"concepts\">{}</ul>\n", items.join(""))
+}
+
+fn slide(slide: &Slide) -> String {
+    let mut html = String::new();
+    for block in &slide.blocks {
+        match block.kind {
+            Kind::Prose => html.push_str(&prose(&source::items(&block.lines))),
+            Kind::Code => html.push_str(&code(block.code())),
+            Kind::CommentedCode => {
+                let info = block.info().unwrap_or("");
+                let label = match info.strip_prefix("compile_fail") {
+                    Some(codes) => format!("✗ doesn't compile {}", codes),
+                    None => String::from("commented out"),
+                };
+                html.push_str(&format!(
+                    "<div class=\"fail\"><div class=\"label\">{}</div>\n{}</div>\n",
+                    inline(&label),
+                    code(block.code())
+                ));
+            }
+        }
+    }
+    html
+}
+
+/// Paragraphs, lists and definitions. List items can nest one level deep, like
+/// the `a)` and `b)` under rule 2 in `borrowing`.
+fn prose(items: &[Item]) -> String {
+    let mut html = String::new();
+    // How many `<ol>`s are open, and whether we're inside a `<dl>`.
+    let mut lists = 0;
+    let mut in_definitions = false;
+
+    for item in items {
+        let depth = match item {
+            Item::ListItem { depth, .. } => depth + 1,
+            _ => 0,
+        };
+        while lists > depth {
+            html.push_str("</li></ol>\n");
+            lists -= 1;
+        }
+        if in_definitions && !matches!(item, Item::Definition { .. }) {
+            html.push_str("</dl>\n");
+            in_definitions = false;
+        }
+
+        match item {
+            Item::Paragraph(text) => html.push_str(&format!("<p>{}</p>\n", inline(text))),
+            Item::ListItem { marker, text, .. } => {
+                if lists == depth {
+                    html.push_str("</li>\n");
+                }
+                while lists < depth {
+                    html.push_str("<ol class=\"list\">\n");
+                    lists += 1;
+                }
+                html.push_str(&format!(
+                    "<li><span class=\"marker\">{}</span> {}",
+                    escape(marker),
+                    inline(text)
+                ));
+            }
+            Item::Definition { term, text } => {
+                if !in_definitions {
+                    html.push_str("<dl>\n");
+                    in_definitions = true;
+                }
+                html.push_str(&format!(
+                    "<dt><code>{}</code></dt><dd>{}</dd>\n",
+                    escape(term),
+                    inline(text)
+                ));
+            }
+        }
+    }
+
+    for _ in 0..lists {
+        html.push_str("</li></ol>\n");
+    }
+    if in_definitions {
+        html.push_str("</dl>\n");
+    }
+    html
+}
+
+/// A highlighted block of code, with its common indentation taken off.
+pub fn code(lines: &[String]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let highlighted: Vec<String> = lines
+        .iter()
+        .map(|line| highlight::line(line.get(indent..).unwrap_or("")))
+        .collect();
+    format!(
+        "<pre class=\"code\"><code>{}</code></pre>\n",
+        highlighted.join("\n")
+    )
+}
+
+/// Escapes prose, turning `backticks` into code and _underscores_ into
+/// emphasis, the way the comments use them.
+fn inline(text: &str) -> String {
+    let mut html = String::new();
+    for (index, part) in text.split('`').enumerate() {
+        if index % 2 == 1 {
+            html.push_str(&format!("<code>{}</code>", escape(part)));
+            continue;
+        }
+
+        let words: Vec<String> = part
+            .split(' ')
+            .map(|word| {
+                let end = word.trim_end_matches(|c: char| c.is_ascii_punctuation() && c != '_');
+                let emphasis = end.len() > 2 && end.starts_with('_') && end.ends_with('_');
+                if emphasis {
+                    format!(
+                        "<em>{}</em>{}",
+                        escape(&end[1..end.len() - 1]),
+                        escape(&word[end.len()..])
+                    )
+                } else {
+                    escape(word)
+                }
+            })
+            .collect();
+        html.push_str(&words.join(" "));
+    }
+    html
+}
+
+/// A whole deck. `next` is a link to show on the last slide.
+pub fn page(title: &str, slides: &[String], next: Option<(String, String)>) -> String {
+    let mut sections = String::new();
+    for (index, slide) in slides.iter().enumerate() {
+        sections.push_str(&format!(
+            "<section class=\"slide\" id=\"slide-{}\">\n{}",
+            index + 1,
+            slide
+        ));
+        if index + 1 == slides.len() {
+            if let Some((href, label)) = &next {
+                sections.push_str(&format!(
+                    "<p class=\"next\"><a href=\"{}\">{} →</a></p>\n",
+                    escape(href),
+                    escape(label)
+                ));
+            }
+        }
+        sections.push_str("</section>\n");
+    }
+
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{title}</title>
+<style>{style}</style>
+</head>
+<body>
+<main>
+{sections}</main>
+<footer>
+<a href=\"index.html\" title=\"All lessons (i)\">☰</a>
+<div class=\"progress\"><div id=\"bar\"></div></div>
+<span id=\"counter\"></span>
+</footer>
+<script>{script}</script>
+</body>
+</html>
+",
+        title = escape(title),
+        style = STYLE,
+        sections = sections,
+        script = SCRIPT
+    )
+}
+
+const STYLE: &str = r#"
+* { box-sizing: border-box; }
+html, body { margin: 0; height: 100%; }
+body {
+    background: #1d1f21; color: #e6e6e6;
+    font: 24px/1.5 "Segoe UI", "Helvetica Neue", Arial, sans-serif;
+    display: flex; flex-direction: column;
+}
+main { flex: 1; overflow: hidden; }
+.slide { height: 100%; overflow-y: auto; padding: 1.5em 3em; max-width: 60em; margin: 0 auto; }
+.slide[hidden] { display: none; }
+h1 { font-size: 2.2em; margin: 1.5em 0 0.5em; }
+a { color: #81a2be; }
+.kicker { color: #969896; text-transform: uppercase; letter-spacing: 0.1em; margin: 3em 0 0; }
+.concepts { color: #b5bd68; }
+.hint, .next { color: #969896; }
+p { margin: 0 0 0.8em; }
+code { font-family: "Fira Code", Consolas, "DejaVu Sans Mono", monospace; font-size: 0.9em; }
+p code, li code, dd code { background: #282a2e; padding: 0.1em 0.3em; border-radius: 3px; }
+.list { list-style: none; padding-left: 0; margin: 0 0 0.8em; }
+.list .list { padding-left: 2em; margin: 0.4em 0 0; }
+.list li { margin-bottom: 0.4em; padding-left: 2em; text-indent: -2em; }
+.marker { color: #f0c674; display: inline-block; width: 2em; text-indent: 0; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 0.4em 1em; margin: 0 0 0.8em; }
+dt code { color: #f0c674; font-size: 1em; }
+dd { margin: 0; }
+pre.code { background: #282a2e; padding: 0.8em 1em; border-radius: 6px; overflow-x: auto; margin: 0 0 0.8em; }
+.fail { border-left: 4px solid #cc6666; padding-left: 0.8em; margin: 0 0 0.8em; }
+.fail .label { color: #cc6666; margin-bottom: 0.3em; }
+.keyword { color: #b294bb; }
+.string { color: #b5bd68; }
+.number { color: #de935f; }
+.comment { color: #969896; font-style: italic; }
+.macro, .function { color: #81a2be; }
+.type { color: #f0c674; }
+.lifetime { color: #8abeb7; }
+footer { display: flex; align-items: center; gap: 1em; padding: 0.4em 1em; color: #969896; font-size: 0.7em; }
+footer a { text-decoration: none; font-size: 1.3em; }
+.progress { flex: 1; height: 6px; background: #373b41; border-radius: 3px; }
+#bar { height: 100%; width: 0; background: #81a2be; border-radius: 3px; }
+.graph svg { max-width: 100%; height: auto; background: #fff; border-radius: 6px; padding: 0.5em; }
+"#;
+
+const SCRIPT: &str = r##"
+const slides = Array.from(document.querySelectorAll(".slide"));
+const counter = document.getElementById("counter");
+const bar = document.getElementById("bar");
+let at = 0;
+
+function show(n) {
+    at = Math.max(0, Math.min(slides.length - 1, n));
+    slides.forEach((slide, i) => { slide.hidden = i !== at; });
+    slides[at].scrollTop = 0;
+    counter.textContent = (at + 1) + " / " + slides.length;
+    bar.style.width = (100 * (at + 1) / slides.length) + "%";
+    history.replaceState(null, "", "#" + (at + 1));
+}
+
+document.addEventListener("keydown", (event) => {
+    if (event.ctrlKey || event.altKey || event.metaKey) return;
+    switch (event.key) {
+        case "ArrowRight": case "ArrowDown": case "PageDown": case " ": case "Enter": case "l": case "j":
+            show(at + 1); break;
+        case "ArrowLeft": case "ArrowUp": case "PageUp": case "Backspace": case "h": case "k":
+            show(at - 1); break;
+        case "Home": show(0); break;
+        case "End": show(slides.length - 1); break;
+        case "i": case "Escape": location.href = "index.html"; return;
+        default:
+            const link = document.querySelectorAll(".lessons a")[parseInt(event.key, 10) - 1];
+            if (link) location.href = link.href;
+            return;
+    }
+    event.preventDefault();
+});
+
+document.querySelector("main").addEventListener("click", (event) => {
+    if (event.target.closest("a, pre, code")) return;
+    show(event.clientX > window.innerWidth / 2 ? at + 1 : at - 1);
+});
+
+show((parseInt(location.hash.slice(1), 10) || 1) - 1);
+"##;
@@ -0,0 +1,132 @@
+//! `club graph`: "who owns what" diagrams of the lessons' ownership traces,
+//! one per step.
+//!
+//! Every step is written as a Graphviz `.dot` file. If Graphviz's `dot` is
+//! installed, each one is also rendered to SVG, and the SVGs go into a deck per
+//! lesson to flip through like the slides. Without it, the decks show the DOT
+//! source instead, which can be pasted into any Graphviz viewer.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use lessons::ownership::{self, Step};
+use lessons::{Lesson, Workspace};
+
+use crate::export;
+use crate::highlight::escape;
+
+/// Writes the graphs and decks of every lesson with a trace into `out`,
+/// returning the files it wrote.
+pub fn graphs(workspace: &Workspace, out: &Path) -> Result<Vec<PathBuf>, lessons::Error> {
+    let io = |error| lessons::Error::Io {
+        path: out.to_path_buf(),
+        error,
+    };
+    fs::create_dir_all(out).map_err(io)?;
+    let mut written = Vec::new();
+    let mut traced = Vec::new();
+
+    for lesson in &workspace.lessons {
+        let (trace, states) = match ownership::load(lesson)? {
+            Some(loaded) => loaded,
+            None => continue,
+        };
+        let source = fs::read_to_string(lesson.main()).map_err(|error| lessons::Error::Io {
+            path: lesson.main(),
+            error,
+        })?;
+        let dir = out.join(&lesson.name);
+        fs::create_dir_all(&dir).map_err(io)?;
+
+        let mut slides = vec![format!(
+            "<p class=\"kicker\">Who owns what</p>\n<h1>{}</h1>\n<p>{} steps. Owners are boxes and values are circles. A solid arrow owns, a dashed one is a <code>&amp;</code>, and a bold one is a <code>&amp;mut</code>.</p>\n",
+            escape(&lesson.title),
+            trace.steps.len()
+        )];
+        for (index, (step, state)) in trace.steps.iter().zip(&states).enumerate() {
+            let caption = format!("Step {}, line {}: {}", index + 1, step.line, step.event);
+            let dot = state.dot(&caption);
+            let path = dir.join(format!("step-{:02}.dot", index + 1));
+            fs::write(&path, &dot).map_err(io)?;
+            written.push(path);
+
+            let svg = render(&dot);
+            if let Some(svg) = &svg {
+                let path = dir.join(format!("step-{:02}.svg", index + 1));
+                fs::write(&path, svg).map_err(io)?;
+                written.push(path);
+            }
+            slides.push(slide(index, step, &source, svg.as_deref(), &dot));
+        }
+
+        let html = export::page(&format!("{}: who owns what", lesson.title), &slides, None);
+        let path = out.join(format!("{}.html", lesson.name));
+        fs::write(&path, html).map_err(io)?;
+        written.push(path);
+        traced.push(lesson);
+    }
+
+    let path = out.join("index.html");
+    fs::write(&path, index(&traced)).map_err(io)?;
+    written.push(path);
+    Ok(written)
+}
+
+fn slide(index: usize, step: &Step, source: &str, svg: Option<&str>, dot: &str) -> String {
+    let mut html = format!(
+        "<p class=\"kicker\">Step {}, line {}</p>\n<p>{}</p>\n",
+        index + 1,
+        step.line,
+        escape(&step.event.to_string())
+    );
+    if let Some(line) = source.lines().nth(step.line.saturating_sub(1)) {
+        html.push_str(&export::code(&[line.to_string()]));
+    }
+    match svg {
+        Some(svg) => {
+            // Inline, so the deck is still a single file. Everything before
+            // the <svg> is an XML prologue that doesn't belong in HTML.
+            let start = svg.find("<svg").unwrap_or(0);
+            html.push_str(&format!("<div class=\"graph\">{}</div>\n", &svg[start..]));
+        }
+        None => {
+            html.push_str(&format!(
+                "<pre class=\"code\"><code>{}</code></pre>\n<p class=\"hint\">Install Graphviz and run <code>club graph</code> again to see this drawn.</p>\n",
+                escape(dot)
+            ));
+        }
+    }
+    html
+}
+
+fn index(lessons: &[&Lesson]) -> String {
+    let mut contents = String::from("<h1>Who owns what</h1>\n<ol class=\"lessons\">\n");
+    for lesson in lessons {
+        contents.push_str(&format!(
+            "<li><a href=\"{}.html\">{}</a></li>\n",
+            escape(&lesson.name),
+            escape(&lesson.title)
+        ));
+    }
+    contents.push_str("</ol>\n<p class=\"hint\">Press a number to open that lesson.</p>\n");
+    export::page("Who owns what", &[contents], None)
+}
+
+/// Renders a graph to SVG with Graphviz, if it's installed.
+fn render(dot: &str) -> Option<String> {
+    let mut child = Command::new("dot")
+        .arg("-Tsvg")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(dot.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
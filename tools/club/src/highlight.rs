@@ -0,0 +1,125 @@
+//! Rust syntax highlighting for the HTML slides.
+//!
+//! This only needs to be good enough for the lessons, so it works a line at a
+//! time and knows about comments, strings, chars, lifetimes, numbers, keywords,
+//! macros and types, which covers everything they use.
+
+const KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "async", "await", "dyn",
+];
+
+const PRIMITIVES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32",
+    "f64", "bool", "char", "str",
+];
+
+/// Turns a line of Rust into HTML, with each token wrapped in a `<span>` whose
+/// class says what it is.
+pub fn line(code: &str) -> String {
+    let chars: Vec<char> = code.chars().collect();
+    let mut html = String::new();
+    let mut at = 0;
+
+    while at < chars.len() {
+        let c = chars[at];
+        let start = at;
+
+        let class = if c == '/' && chars.get(at + 1) == Some(&'/') {
+            at = chars.len();
+            Some("comment")
+        } else if c == '"' {
+            at += 1;
+            while at < chars.len() && chars[at] != '"' {
+                at += if chars[at] == '\\' { 2 } else { 1 };
+            }
+            at = (at + 1).min(chars.len());
+            Some("string")
+        } else if c == '\'' {
+            // 'a' and '\n' are chars, but 'static is a lifetime.
+            if chars.get(at + 1) == Some(&'\\') {
+                at += 2;
+                while at < chars.len() && chars[at] != '\'' {
+                    at += 1;
+                }
+                at = (at + 1).min(chars.len());
+                Some("string")
+            } else if chars.get(at + 2) == Some(&'\'') {
+                at += 3;
+                Some("string")
+            } else {
+                at += 1;
+                while at < chars.len() && is_ident(chars[at]) {
+                    at += 1;
+                }
+                Some("lifetime")
+            }
+        } else if c.is_ascii_digit() {
+            while at < chars.len() && (chars[at].is_ascii_alphanumeric() || chars[at] == '_') {
+                at += 1;
+            }
+            // A decimal point, as long as it isn't a range like `0..1`.
+            if chars.get(at) == Some(&'.') && chars.get(at + 1).is_some_and(char::is_ascii_digit) {
+                at += 1;
+                while at < chars.len() && chars[at].is_ascii_alphanumeric() {
+                    at += 1;
+                }
+            }
+            Some("number")
+        } else if is_ident(c) {
+            while at < chars.len() && is_ident(chars[at]) {
+                at += 1;
+            }
+            let word: String = chars[start..at].iter().collect();
+            if chars.get(at) == Some(&'!') {
+                at += 1;
+                Some("macro")
+            } else if KEYWORDS.contains(&word.as_str()) {
+                Some("keyword")
+            } else if PRIMITIVES.contains(&word.as_str()) || c.is_uppercase() {
+                Some("type")
+            } else if chars.get(at) == Some(&'(') {
+                Some("function")
+            } else {
+                None
+            }
+        } else {
+            at += 1;
+            None
+        };
+
+        let text: String = chars[start..at].iter().collect();
+        match class {
+            Some(class) => html.push_str(&format!(
+                "<span class=\"{}\">{}</span>",
+                class,
+                escape(&text)
+            )),
+            None => html.push_str(&escape(&text)),
+        }
+    }
+
+    html
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Escapes text for use in HTML, both between tags and in attributes.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
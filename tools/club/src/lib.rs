@@ -0,0 +1,30 @@
+//! The parts of `club`: presenting, exporting and watching lessons, the
+//! playground and the exercises. `main.rs` picks one by its command.
+
+pub mod exercises;
+pub mod explain;
+pub mod export;
+pub mod graph;
+pub mod highlight;
+pub mod playground;
+pub mod present;
+#[cfg(unix)]
+mod sys;
+pub mod terminal;
+pub mod watch;
+pub mod watcher;
+
+use std::fs;
+use std::path::Path;
+
+use lessons::state;
+
+/// `club next` picks up from the last lesson that ran.
+pub fn last_run(root: &Path) -> Option<String> {
+    let last = fs::read_to_string(state::last_run(root)).ok()?;
+    Some(last.trim().to_string())
+}
+
+pub fn remember_last_run(root: &Path, name: &str) -> std::io::Result<()> {
+    state::save(&state::last_run(root), name)
+}
@@ -0,0 +1,334 @@
+//! `club` runs the lessons of the presentation, so nobody has to remember
+//! `cargo run -p <name>` or which lesson comes after which.
+
+use std::env;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use lessons::runner::{self, Verdict};
+use lessons::snapshot::{self, Comparison, Snapshot, Variant};
+use lessons::solution;
+use lessons::Workspace;
+
+use club::{exercises, explain, export, graph, playground, present, watch};
+use club::{last_run, remember_last_run};
+
+const USAGE: &str = "\
+usage: club <command>
+
+commands:
+    list          list the lessons in presentation order
+    check         check the lesson manifests
+    run <lesson>  run one lesson, then any other paths through it, and
+                  then its release build if it has one
+    next          run the lesson after the one that ran last
+    all           run every lesson in order
+    watch [<lesson>]
+                  rebuild and re-run a lesson every time it's saved, the one
+                  that ran last by default
+    explain <lesson>|<code>
+                  build a lesson and show the part of the lessons that
+                  explains each error, or what explains an error code
+    present [<lesson>]
+                  step through the lessons' comments and code as slides
+    snapshot [--update] [<lesson>]
+                  compare what the lessons print with their snapshots, or
+                  update the snapshots with --update
+    exercises     list the exercises, and which ones are done
+    exercise [<name>]
+                  work on an exercise, re-running its tests on every save
+    verify [<name>...]
+                  run the exercises' tests once
+    student [--check] [<dir>]
+                  write the student's copy of the lessons, with the solutions
+                  taken out, into target/student by default. --check also
+                  makes sure both copies build, and that the lessons and the
+                  exercises' solutions pass their tests
+    export [<dir>]
+                  write the slides as HTML decks, into target/slides by
+                  default
+    graph [<dir>]
+                  draw the lessons' ownership traces, one graph per step,
+                  into target/graphs by default
+    playground [<address>]
+                  serve an offline playground with an editor, on
+                  127.0.0.1:8080 by default";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match club(&args) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("club: {}", error);
+            process::exit(2);
+        }
+    }
+}
+
+/// Runs a command, returning whether every lesson it ran did what it should.
+fn club(args: &[&str]) -> Result<bool, Box<dyn Error>> {
+    let workspace = Workspace::find()?;
+
+    match args {
+        [] | ["list"] => {
+            for lesson in &workspace.lessons {
+                println!("{}. {} ({})", lesson.position, lesson.title, lesson.name);
+                if !lesson.concepts.is_empty() {
+                    println!("   {}", lesson.concepts.join(", "));
+                }
+            }
+            Ok(true)
+        }
+        ["check"] => {
+            // Loading the workspace already validated it.
+            println!("{} lessons, no problems", workspace.lessons.len());
+            Ok(true)
+        }
+        ["run", name] => {
+            let index = workspace
+                .index_of(name)
+                .ok_or_else(|| format!("there is no lesson called `{}`", name))?;
+            run(&workspace, index)
+        }
+        ["next"] => {
+            let index = last_run(&workspace.root).and_then(|last| workspace.index_of(&last));
+            let next = index.map_or(0, |index| index + 1);
+            if next == workspace.lessons.len() {
+                println!("That was the last lesson! Use `club run <lesson>` to go back.");
+                return Ok(true);
+            }
+            run(&workspace, next)
+        }
+        ["all"] => {
+            let mut all_ok = true;
+            for index in 0..workspace.lessons.len() {
+                all_ok &= run(&workspace, index)?;
+                println!();
+            }
+            Ok(all_ok)
+        }
+        ["watch"] => {
+            let index = last_run(&workspace.root)
+                .and_then(|last| workspace.index_of(&last))
+                .unwrap_or(0);
+            watch::watch(&workspace, index)?;
+            Ok(true)
+        }
+        ["watch", name] => {
+            let index = workspace
+                .index_of(name)
+                .ok_or_else(|| format!("there is no lesson called `{}`", name))?;
+            watch::watch(&workspace, index)?;
+            Ok(true)
+        }
+        ["explain", name] => match workspace.get(name) {
+            Some(lesson) => Ok(explain::lesson(&workspace, lesson)?),
+            None if is_error_code(name) => Ok(explain::code(&workspace, name)?),
+            None => Err(format!("there is no lesson called `{}`", name).into()),
+        },
+        ["present"] => {
+            present::present(&workspace, 0)?;
+            Ok(true)
+        }
+        ["present", name] => {
+            let index = workspace
+                .index_of(name)
+                .ok_or_else(|| format!("there is no lesson called `{}`", name))?;
+            present::present(&workspace, index)?;
+            Ok(true)
+        }
+        ["exercises"] => {
+            exercises::list(&workspace);
+            Ok(true)
+        }
+        ["exercise"] => {
+            exercises::watch(&workspace, None)?;
+            Ok(true)
+        }
+        ["exercise", name] => {
+            exercises::watch(&workspace, Some(name))?;
+            Ok(true)
+        }
+        ["verify", names @ ..] => Ok(exercises::verify(&workspace, names)?),
+        ["student", rest @ ..] => {
+            let check = rest.contains(&"--check");
+            let dirs: Vec<&str> = rest
+                .iter()
+                .copied()
+                .filter(|arg| *arg != "--check")
+                .collect();
+            let out = match dirs.as_slice() {
+                [] => workspace.root.join("target").join("student"),
+                [dir] => PathBuf::from(dir),
+                _ => return Err(USAGE.into()),
+            };
+            student(&workspace, &out, check)
+        }
+        ["export"] | ["export", _] => {
+            let out = match args.get(1) {
+                Some(dir) => PathBuf::from(dir),
+                None => workspace.root.join("target").join("slides"),
+            };
+            for path in export::export(&workspace, &out)? {
+                println!("wrote {}", path.display());
+            }
+            Ok(true)
+        }
+        ["graph"] | ["graph", _] => {
+            let out = match args.get(1) {
+                Some(dir) => PathBuf::from(dir),
+                None => workspace.root.join("target").join("graphs"),
+            };
+            for path in graph::graphs(&workspace, &out)? {
+                println!("wrote {}", path.display());
+            }
+            Ok(true)
+        }
+        ["playground"] => {
+            playground::serve(workspace, playground::DEFAULT_ADDRESS)?;
+            Ok(true)
+        }
+        ["playground", address] => {
+            playground::serve(workspace, address)?;
+            Ok(true)
+        }
+        ["snapshot", rest @ ..] => {
+            let update = rest.contains(&"--update");
+            let names: Vec<&str> = rest
+                .iter()
+                .copied()
+                .filter(|arg| *arg != "--update")
+                .collect();
+            snapshots(&workspace, &names, update)
+        }
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{}", USAGE);
+            Ok(true)
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn run(workspace: &Workspace, index: usize) -> Result<bool, Box<dyn Error>> {
+    let lesson = &workspace.lessons[index];
+    println!(
+        "=== [{}/{}] {} ({}) ===",
+        index + 1,
+        workspace.lessons.len(),
+        lesson.title,
+        lesson.name
+    );
+
+    let run = runner::run(&workspace.root, lesson)?;
+    let verdict = Verdict::judge(lesson, &run);
+    runner::print(lesson, &run, verdict);
+    let mut all_ok = verdict.is_ok();
+
+    // Like the safe way around data_types' panic, right after the panic.
+    for path in &lesson.paths {
+        println!();
+        println!(
+            "=== {} ({}), with {}={} ===",
+            lesson.title,
+            lesson.name,
+            runner::PATH_VARIABLE,
+            path
+        );
+        let run = runner::run_path(&workspace.root, lesson, path)?;
+        let verdict = Verdict::judge_path(lesson, path, &run);
+        runner::print_variant(lesson, path, &run, verdict);
+        all_ok &= verdict.is_ok();
+    }
+
+    // Like overflow, which panics in debug and wraps around in release.
+    if lesson.release.is_some() {
+        println!();
+        println!(
+            "=== {} ({}), built with --release ===",
+            lesson.title, lesson.name
+        );
+        let run = runner::run_release(&workspace.root, lesson)?;
+        let verdict = Verdict::judge_release(lesson, &run);
+        runner::print_variant(lesson, "release", &run, verdict);
+        all_ok &= verdict.is_ok();
+    }
+
+    remember_last_run(&workspace.root, &lesson.name)?;
+    Ok(all_ok)
+}
+
+fn snapshots(workspace: &Workspace, names: &[&str], update: bool) -> Result<bool, Box<dyn Error>> {
+    for name in names {
+        if workspace.get(name).is_none() {
+            return Err(format!("there is no lesson called `{}`", name).into());
+        }
+    }
+
+    let mut all_same = true;
+    for lesson in &workspace.lessons {
+        if !names.is_empty() && !names.contains(&lesson.name.as_str()) {
+            continue;
+        }
+
+        for variant in Variant::all(lesson) {
+            let run = variant.run(&workspace.root, lesson)?;
+            let snapshot = Snapshot::of(&run);
+            let path = snapshot::path(&workspace.root, lesson, variant);
+            let label = variant.label(lesson);
+
+            if update {
+                snapshot::update(&workspace.root, lesson, variant, &snapshot)?;
+                println!("updated {}", path.display());
+                continue;
+            }
+
+            match snapshot::compare(&workspace.root, lesson, variant, &snapshot)? {
+                Comparison::Same => println!("{} matches its snapshot", label),
+                Comparison::Missing => {
+                    all_same = false;
+                    println!("{} has no snapshot yet, expected {}", label, path.display());
+                }
+                Comparison::Changed(diff) => {
+                    all_same = false;
+                    println!("{} changed from its snapshot:", label);
+                    print!("{}", diff);
+                }
+            }
+        }
+    }
+
+    if !all_same {
+        println!();
+        println!("If the new output is right, run `club snapshot --update` to keep it.");
+    }
+    Ok(all_same)
+}
+
+fn student(workspace: &Workspace, out: &Path, check: bool) -> Result<bool, Box<dyn Error>> {
+    if !check {
+        let written = solution::generate(workspace, out)?;
+        println!("wrote {} files to {}", written.len(), out.display());
+        return Ok(true);
+    }
+
+    let problems = solution::check(workspace, out)?;
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    if problems.is_empty() {
+        println!(
+            "the lessons pass their tests, and the student's copy in {} builds",
+            out.display()
+        );
+    }
+    Ok(problems.is_empty())
+}
+
+/// Like `E0382`.
+fn is_error_code(name: &str) -> bool {
+    name.len() == 5 && name.starts_with('E') && name[1..].chars().all(|c| c.is_ascii_digit())
+}
@@ -0,0 +1,512 @@
+//! `club playground`: an offline stand-in for play.rust-lang.org.
+//!
+//! The lab has no internet, so this serves a small editor that can load any of
+//! the lessons, and an `/execute` endpoint that takes the same JSON as the real
+//! playground's and runs the code with the local toolchain (see
+//! [`lessons::execute`]). It is plain HTTP/1.1 over `std::net`.
+//!
+//! It only listens on this machine unless it's given another address, since
+//! anyone who can reach it can run code on it. Connections are handled by a
+//! fixed pool of workers, one per CPU, so no more compiles run at once than
+//! there are CPUs, and a connection that arrives while the queue in front of
+//! them is full is turned away with a 503. A request has to arrive whole
+//! within [`TIMEOUT`], and its lines are capped, so a client can't hold a
+//! worker or grow its memory by sending slowly or sending too much.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lessons::execute::{self, Request};
+use lessons::json::Json;
+use lessons::Workspace;
+
+/// Where the playground listens by default: this machine only.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+/// The most we'll read of a request body. The lessons are a few kilobytes.
+pub const MAX_BODY: usize = 1 << 20;
+
+/// The longest request line or header line we'll read.
+pub const MAX_LINE: usize = 8 << 10;
+
+/// The most headers a request can have, and the most bytes they can add up to.
+pub const MAX_HEADERS: usize = 100;
+pub const MAX_HEAD: usize = 64 << 10;
+
+/// How long a client gets to send its whole request, and then to take the
+/// whole response, before we give up on it.
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the accept loop waits to turn a connection away. It can't wait
+/// long: nothing else is accepted meanwhile.
+const BUSY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How many connections can wait for a worker, per worker.
+const QUEUE_PER_WORKER: usize = 4;
+
+pub fn serve(workspace: Workspace, address: &str) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    println!("playground listening on http://{}/", listener.local_addr()?);
+
+    let workers = thread::available_parallelism().map_or(2, |count| count.get());
+    let (queue, connections) = mpsc::sync_channel::<TcpStream>(workers * QUEUE_PER_WORKER);
+    let connections = Arc::new(Mutex::new(connections));
+    let workspace = Arc::new(workspace);
+    let requests = Arc::new(AtomicUsize::new(0));
+    for _ in 0..workers {
+        let connections = Arc::clone(&connections);
+        let workspace = Arc::clone(&workspace);
+        let requests = Arc::clone(&requests);
+        thread::spawn(move || loop {
+            // The lock is only held while waiting for the next connection.
+            let stream = match connections.lock().map(|receiver| receiver.recv()) {
+                Ok(Ok(stream)) => stream,
+                _ => return,
+            };
+            let number = requests.fetch_add(1, Ordering::SeqCst);
+            // A bug that panics on one request mustn't take the worker with it,
+            // or enough bad requests would leave nobody to answer.
+            match panic::catch_unwind(AssertUnwindSafe(|| handle(stream, &workspace, number))) {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => eprintln!("playground: {}", error),
+                Err(_) => eprintln!("playground: request {} panicked", number),
+            }
+        });
+    }
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                eprintln!("playground: {}", error);
+                continue;
+            }
+        };
+        match queue.try_send(stream) {
+            Ok(()) => {}
+            Err(TrySendError::Full(stream)) => {
+                let busy = Response::error(
+                    "503 Service Unavailable",
+                    "the playground is busy, try again in a moment",
+                );
+                println!("turned a connection away: {}", busy.status);
+                let _ = stream
+                    .set_write_timeout(Some(BUSY_TIMEOUT))
+                    .and_then(|()| respond(stream, &busy));
+            }
+            Err(TrySendError::Disconnected(_)) => break,
+        }
+    }
+    Ok(())
+}
+
+/// What the playground looks at of an HTTP request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: &'static str,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    fn json(status: &'static str, json: Json) -> Response {
+        Response {
+            status,
+            content_type: "application/json",
+            body: json.to_string(),
+        }
+    }
+
+    /// An error in the playground's shape, which is `{"error": "..."}`.
+    fn error(status: &'static str, message: &str) -> Response {
+        let mut object = BTreeMap::new();
+        object.insert(String::from("error"), Json::from(message));
+        Response::json(status, Json::Object(object))
+    }
+}
+
+fn handle(stream: TcpStream, workspace: &Workspace, number: usize) -> io::Result<()> {
+    let mut reader = BufReader::new(Deadline::new(stream.try_clone()?));
+    let (label, response) = match read_request(&mut reader)? {
+        Ok(request) => (
+            format!("{} {}", request.method, request.path),
+            route(&request, workspace, number),
+        ),
+        Err(response) => (String::from("-"), response),
+    };
+    println!("{} {}", label, response.status);
+    respond(Deadline::new(stream), &response)
+}
+
+/// A connection that gives up once [`TIMEOUT`] has passed since it was made,
+/// however the time was spent. A timeout on each read alone would let a client
+/// that sends a byte every few seconds keep going forever.
+struct Deadline {
+    stream: TcpStream,
+    at: Instant,
+}
+
+impl Deadline {
+    fn new(stream: TcpStream) -> Deadline {
+        Deadline {
+            stream,
+            at: Instant::now() + TIMEOUT,
+        }
+    }
+
+    /// What's left of the time, or an error once it's gone.
+    fn left(&self) -> io::Result<Duration> {
+        let left = self.at.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the client took too long",
+            ))
+        } else {
+            Ok(left)
+        }
+    }
+}
+
+impl Read for Deadline {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        self.stream.set_read_timeout(Some(self.left()?))?;
+        self.stream.read(buffer)
+    }
+}
+
+impl Write for Deadline {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        self.stream.set_write_timeout(Some(self.left()?))?;
+        self.stream.write(buffer)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+/// Reads a request's line, headers and body. A request that's malformed or
+/// too big gives back the response to send instead.
+pub fn read_request(reader: &mut impl BufRead) -> io::Result<Result<HttpRequest, Response>> {
+    let request_line = match read_line(reader)? {
+        Some(line) => line,
+        None => {
+            return Ok(Err(Response::error(
+                "400 Bad Request",
+                "the request line is too long",
+            )))
+        }
+    };
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let path = parts.next().unwrap_or("").to_string();
+    if method.is_empty() || path.is_empty() {
+        return Ok(Err(Response::error(
+            "400 Bad Request",
+            "expected a request line like `GET / HTTP/1.1`",
+        )));
+    }
+
+    let too_large = || {
+        Ok(Err(Response::error(
+            "431 Request Header Fields Too Large",
+            "the request's headers are too large",
+        )))
+    };
+    let mut length = 0;
+    let mut headers = 0;
+    let mut head = 0;
+    loop {
+        let header = match read_line(reader)? {
+            Some(header) => header,
+            None => return too_large(),
+        };
+        if header.trim().is_empty() {
+            break;
+        }
+        headers += 1;
+        head += header.len();
+        if headers > MAX_HEADERS || head > MAX_HEAD {
+            return too_large();
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = match value.trim().parse() {
+                    Ok(length) => length,
+                    Err(_) => {
+                        return Ok(Err(Response::error(
+                            "400 Bad Request",
+                            "Content-Length isn't a number",
+                        )))
+                    }
+                };
+            }
+        }
+    }
+
+    if length > MAX_BODY {
+        return Ok(Err(Response::error(
+            "413 Payload Too Large",
+            "the request is too large",
+        )));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Ok(HttpRequest {
+        method,
+        path,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    }))
+}
+
+/// Reads a line of at most [`MAX_LINE`] bytes, or nothing if it's longer.
+/// At the end of the input the line is empty.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    let read = reader.take(MAX_LINE as u64).read_line(&mut line)?;
+    if read == MAX_LINE && !line.ends_with('\n') {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+fn respond(mut stream: impl Write, response: &Response) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    )?;
+    stream.write_all(response.body.as_bytes())?;
+    stream.flush()
+}
+
+/// The response to a request. `number` is unique to the request, and keeps
+/// the directories of two runs at once apart.
+pub fn route(request: &HttpRequest, workspace: &Workspace, number: usize) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/") | ("GET", "/index.html") => Response {
+            status: "200 OK",
+            content_type: "text/html",
+            body: String::from(EDITOR),
+        },
+        ("GET", "/lessons") => {
+            let lessons = workspace
+                .lessons
+                .iter()
+                .map(|lesson| {
+                    let mut object = BTreeMap::new();
+                    object.insert(String::from("name"), Json::from(lesson.name.as_str()));
+                    object.insert(String::from("title"), Json::from(lesson.title.as_str()));
+                    object.insert(String::from("edition"), Json::from(lesson.edition.as_str()));
+                    Json::Object(object)
+                })
+                .collect();
+            Response::json("200 OK", Json::Array(lessons))
+        }
+        ("GET", path) if path.starts_with("/lessons/") => {
+            let name = &path["/lessons/".len()..];
+            match workspace
+                .get(name)
+                .map(|lesson| fs::read_to_string(lesson.main()))
+            {
+                Some(Ok(source)) => Response {
+                    status: "200 OK",
+                    content_type: "text/plain",
+                    body: source,
+                },
+                Some(Err(error)) => {
+                    Response::error("500 Internal Server Error", &error.to_string())
+                }
+                None => Response::error(
+                    "404 Not Found",
+                    &format!("there is no lesson called `{}`", name),
+                ),
+            }
+        }
+        ("POST", "/execute") => {
+            let request = Json::parse(&request.body).and_then(|json| Request::from_json(&json));
+            match request {
+                Ok(request) => run(&request, number),
+                Err(message) => Response::error("400 Bad Request", &message),
+            }
+        }
+        (_, "/execute") => Response::error("405 Method Not Allowed", "use POST"),
+        _ => Response::error("404 Not Found", "not found"),
+    }
+}
+
+fn run(request: &Request, number: usize) -> Response {
+    // Every request gets a directory of its own, so two people pressing Run at
+    // once don't trip over each other's binaries.
+    let scratch: PathBuf =
+        env::temp_dir().join(format!("club-playground-{}-{}", std::process::id(), number));
+    let response = match execute::execute(request, &scratch) {
+        Ok(response) => Response::json("200 OK", response.to_json()),
+        Err(error) => Response::error("500 Internal Server Error", &error.to_string()),
+    };
+    let _ = fs::remove_dir_all(&scratch);
+    response
+}
+
+const EDITOR: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Rust Code Club Playground</title>
+<style>
+* { box-sizing: border-box; }
+html, body { margin: 0; height: 100%; }
+body {
+    background: #1d1f21; color: #e6e6e6;
+    font: 15px/1.4 "Segoe UI", "Helvetica Neue", Arial, sans-serif;
+    display: flex; flex-direction: column;
+}
+header { display: flex; gap: 0.6em; align-items: center; padding: 0.5em 1em; background: #282a2e; }
+header h1 { font-size: 1em; margin: 0 1em 0 0; }
+select, button { font: inherit; background: #373b41; color: inherit; border: 1px solid #4d5057; border-radius: 4px; padding: 0.2em 0.6em; }
+button { background: #81a2be; color: #1d1f21; font-weight: bold; cursor: pointer; }
+button:disabled { opacity: 0.6; cursor: wait; }
+.hint { color: #969896; margin-left: auto; }
+main { flex: 1; display: flex; flex-direction: column; min-height: 0; }
+textarea, pre { font-family: "Fira Code", Consolas, "DejaVu Sans Mono", monospace; font-size: 14px; margin: 0; }
+textarea {
+    flex: 3; width: 100%; resize: none; border: 0; outline: 0; padding: 1em;
+    background: #1d1f21; color: inherit; tab-size: 4;
+}
+#output { flex: 2; overflow: auto; padding: 1em; background: #151618; border-top: 2px solid #373b41; white-space: pre-wrap; }
+#output .stderr { color: #969896; }
+#output .stdout { color: #e6e6e6; }
+#output .failed { color: #cc6666; }
+#output h2 { font-size: 0.8em; text-transform: uppercase; letter-spacing: 0.1em; color: #969896; margin: 0.6em 0 0.2em; }
+</style>
+</head>
+<body>
+<header>
+<h1>Rust Code Club Playground</h1>
+<select id="lesson"><option value="">Load a lesson…</option></select>
+<select id="edition">
+<option>2015</option><option selected>2018</option><option>2021</option><option>2024</option>
+</select>
+<select id="mode"><option value="debug">Debug</option><option value="release">Release</option></select>
+<button id="run">Run</button>
+<span class="hint">Ctrl+Enter runs the code</span>
+</header>
+<main>
+<textarea id="code" spellcheck="false">fn main() {
+    println!("Hello, world!");
+}
+</textarea>
+<div id="output"></div>
+</main>
+<script>
+const code = document.getElementById("code");
+const output = document.getElementById("output");
+const lesson = document.getElementById("lesson");
+const edition = document.getElementById("edition");
+const mode = document.getElementById("mode");
+const button = document.getElementById("run");
+const editions = {};
+
+fetch("lessons").then((response) => response.json()).then((lessons) => {
+    for (const { name, title, edition } of lessons) {
+        editions[name] = edition;
+        lesson.add(new Option(title + " (" + name + ")", name));
+    }
+    const wanted = location.hash.slice(1);
+    if (wanted in editions) {
+        lesson.value = wanted;
+        load(wanted);
+    }
+});
+
+function load(name) {
+    fetch("lessons/" + encodeURIComponent(name)).then((response) => response.text()).then((source) => {
+        code.value = source;
+        edition.value = editions[name];
+        output.textContent = "";
+        history.replaceState(null, "", "#" + name);
+    });
+}
+
+lesson.addEventListener("change", () => { if (lesson.value) load(lesson.value); });
+
+function section(title, text, className) {
+    if (!text) return;
+    const heading = document.createElement("h2");
+    heading.textContent = title;
+    const body = document.createElement("div");
+    body.className = className;
+    body.textContent = text;
+    output.append(heading, body);
+}
+
+async function run() {
+    button.disabled = true;
+    output.textContent = "Running…";
+    try {
+        const response = await fetch("execute", {
+            method: "POST",
+            headers: { "Content-Type": "application/json" },
+            body: JSON.stringify({
+                channel: "stable",
+                mode: mode.value,
+                edition: edition.value,
+                crateType: "bin",
+                tests: false,
+                code: code.value,
+                backtrace: false,
+            }),
+        });
+        const result = await response.json();
+        output.textContent = "";
+        if (result.error) {
+            section("Error", result.error, "failed");
+        } else {
+            section("Standard Error", result.stderr, result.success ? "stderr" : "failed");
+            section("Standard Output", result.stdout, "stdout");
+            if (!result.stdout && !result.stderr) section("Result", "(no output)", "stderr");
+        }
+    } catch (error) {
+        output.textContent = "";
+        section("Error", String(error), "failed");
+    } finally {
+        button.disabled = false;
+    }
+}
+
+button.addEventListener("click", run);
+code.addEventListener("keydown", (event) => {
+    if (event.key === "Enter" && (event.ctrlKey || event.metaKey)) {
+        event.preventDefault();
+        run();
+    } else if (event.key === "Tab") {
+        event.preventDefault();
+        code.setRangeText("    ", code.selectionStart, code.selectionEnd, "end");
+    }
+});
+</script>
+</body>
+</html>
+"##;
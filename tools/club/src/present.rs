@@ -0,0 +1,489 @@
+//! `club present`: the lessons' comments as slides in the terminal.
+//!
+//! Each slide is a block of prose and the code that follows it. Slides that
+//! don't fit on the screen are split into pages between paragraphs or list
+//! items, so the long lecture at the top of `borrowing` reads a screen at a
+//! time.
+//!
+//! If `club watch` is running in another terminal, the footer shows whether
+//! the lesson it last ran passed, in green or red, and is redrawn whenever
+//! that changes.
+
+use std::fs::{self, File};
+use std::io::{self, IsTerminal, Write};
+use std::time::Duration;
+
+use lessons::source::{self, Block, Item, Kind, Slide};
+use lessons::{Lesson, Workspace};
+
+use crate::terminal::{self, Key, Keys, RawMode};
+use crate::terminal::{BOLD, CYAN, DIM, GREEN, RED, RESET, YELLOW};
+use crate::watch::Status;
+
+/// Lines taken up by the header and footer on every page.
+const CHROME: usize = 4;
+
+/// How often to look for a new result from `club watch` while waiting for a
+/// key.
+const STATUS_POLL: Duration = Duration::from_millis(250);
+
+struct Deck {
+    titles: Vec<String>,
+    slides: Vec<Vec<Slide>>,
+}
+
+/// Where we are in the deck. Pages depend on the screen size, so they are
+/// worked out again every time the screen is drawn.
+#[derive(Clone, Copy)]
+struct Position {
+    lesson: usize,
+    slide: usize,
+    page: usize,
+}
+
+pub fn present(workspace: &Workspace, start: usize) -> io::Result<()> {
+    let deck = Deck::load(&workspace.lessons)?;
+    let mut at = Position {
+        lesson: start,
+        slide: 0,
+        page: 0,
+    };
+
+    // Without a terminal to drive, just print every page, one after another.
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return match deck.print(start) {
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+            result => result,
+        };
+    }
+
+    let _raw = RawMode::enable()?;
+    // Not stdin, whose buffer would hide the rest of an escape sequence from
+    // `read_key`.
+    let mut keys = File::open("/dev/tty")?;
+    let mut stdout = io::stdout();
+    let style = Style::colored();
+    let mut menu = false;
+    write!(stdout, "{}", terminal::HIDE_CURSOR)?;
+
+    let result = loop {
+        let (rows, columns) = terminal::size();
+        let pages = deck.pages(at.lesson, at.slide, rows, columns, &style);
+        at.page = at.page.min(pages.len() - 1);
+        let status = Status::load(&workspace.root);
+
+        let screen = if menu {
+            deck.menu(at, &style)
+        } else {
+            deck.screen(at, &pages, rows, columns, status.as_ref(), &style)
+        };
+        write!(stdout, "{}{}", terminal::CLEAR, screen)?;
+        stdout.flush()?;
+
+        // Redraw if `club watch` has a new result before a key comes.
+        let mut changed = false;
+        while !keys.ready(STATUS_POLL) {
+            if Status::load(&workspace.root) != status {
+                changed = true;
+                break;
+            }
+        }
+        if changed {
+            continue;
+        }
+
+        let key = match terminal::read_key(&mut keys) {
+            Ok(key) => key,
+            Err(error) => break Err(error),
+        };
+        match key {
+            // Ctrl-C arrives as a key, since raw mode turns its signal off.
+            Key::Char('q') | Key::Char('\x03') => break Ok(()),
+            Key::Char('g') | Key::Char('m') => menu = !menu,
+            Key::Escape => menu = false,
+            Key::Char(digit @ '1'..='9') => {
+                let lesson = digit as usize - '1' as usize;
+                if lesson < deck.slides.len() {
+                    at = Position {
+                        lesson,
+                        slide: 0,
+                        page: 0,
+                    };
+                    menu = false;
+                }
+            }
+            Key::Right | Key::Down | Key::Enter | Key::PageDown => at = deck.next(at, pages.len()),
+            Key::Char(' ') | Key::Char('n') | Key::Char('l') | Key::Char('j') => {
+                at = deck.next(at, pages.len())
+            }
+            Key::Left | Key::Up | Key::Backspace | Key::PageUp => at = deck.previous(at),
+            Key::Char('p') | Key::Char('b') | Key::Char('h') | Key::Char('k') => {
+                at = deck.previous(at)
+            }
+            Key::Home => {
+                at = Position {
+                    slide: 0,
+                    page: 0,
+                    ..at
+                }
+            }
+            Key::End => {
+                at = Position {
+                    slide: deck.slides[at.lesson].len().saturating_sub(1),
+                    page: 0,
+                    ..at
+                }
+            }
+            _ => {}
+        }
+    };
+
+    write!(stdout, "{}{}", terminal::CLEAR, terminal::SHOW_CURSOR)?;
+    result
+}
+
+impl Deck {
+    fn load(lessons: &[Lesson]) -> io::Result<Deck> {
+        let mut titles = Vec::new();
+        let mut slides = Vec::new();
+        for lesson in lessons {
+            let source = fs::read_to_string(lesson.main())?;
+            titles.push(lesson.title.clone());
+            // A lesson with nothing in it still gets a slide, with just its
+            // title, so there's always somewhere to be.
+            let mut lesson_slides = source::slides(&source);
+            if lesson_slides.is_empty() {
+                lesson_slides.push(Slide { blocks: Vec::new() });
+            }
+            slides.push(lesson_slides);
+        }
+        Ok(Deck { titles, slides })
+    }
+
+    /// Prints every page from `start` on, without colors.
+    fn print(&self, start: usize) -> io::Result<()> {
+        let (rows, columns) = terminal::size();
+        let style = Style::plain();
+        let mut stdout = io::stdout().lock();
+
+        for (lesson, slides) in self.slides.iter().enumerate().skip(start) {
+            for slide in 0..slides.len() {
+                let pages = self.pages(lesson, slide, rows, columns, &style);
+                for page in 0..pages.len() {
+                    let here = Position {
+                        lesson,
+                        slide,
+                        page,
+                    };
+                    writeln!(
+                        stdout,
+                        "{}",
+                        self.screen(here, &pages, rows, columns, None, &style)
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn next(&self, at: Position, pages: usize) -> Position {
+        if at.page + 1 < pages {
+            Position {
+                page: at.page + 1,
+                ..at
+            }
+        } else if at.slide + 1 < self.slides[at.lesson].len() {
+            Position {
+                slide: at.slide + 1,
+                page: 0,
+                ..at
+            }
+        } else if at.lesson + 1 < self.slides.len() {
+            Position {
+                lesson: at.lesson + 1,
+                slide: 0,
+                page: 0,
+            }
+        } else {
+            at
+        }
+    }
+
+    fn previous(&self, at: Position) -> Position {
+        if at.page > 0 {
+            Position {
+                page: at.page - 1,
+                ..at
+            }
+        } else if at.slide > 0 {
+            // The last page of the slide before. `screen` clamps this.
+            Position {
+                slide: at.slide - 1,
+                page: usize::MAX,
+                ..at
+            }
+        } else if at.lesson > 0 {
+            Position {
+                lesson: at.lesson - 1,
+                slide: self.slides[at.lesson - 1].len().saturating_sub(1),
+                page: usize::MAX,
+            }
+        } else {
+            at
+        }
+    }
+
+    /// Lays a slide out into pages that fit on the screen.
+    fn pages(
+        &self,
+        lesson: usize,
+        slide: usize,
+        rows: usize,
+        columns: usize,
+        style: &Style,
+    ) -> Vec<Vec<String>> {
+        let height = rows.saturating_sub(CHROME).max(4);
+        let width = columns.saturating_sub(4).max(20);
+
+        // Lay the slide out as chunks that we'd rather not split up.
+        let mut chunks: Vec<Vec<String>> = Vec::new();
+        for block in &self.slides[lesson][slide].blocks {
+            match block.kind {
+                Kind::Prose => {
+                    for item in source::items(&block.lines) {
+                        chunks.push(render_item(&item, width, style));
+                    }
+                }
+                Kind::Code | Kind::CommentedCode => chunks.push(render_code(block, style)),
+            }
+        }
+
+        paginate(chunks, height)
+    }
+
+    /// One full screen: a header, the page, and a footer with a progress bar
+    /// and the result of the last run from `club watch`, if there is one.
+    fn screen(
+        &self,
+        at: Position,
+        pages: &[Vec<String>],
+        rows: usize,
+        columns: usize,
+        status: Option<&Status>,
+        style: &Style,
+    ) -> String {
+        let page = at.page.min(pages.len() - 1);
+        let mut screen = String::new();
+
+        let title = format!(
+            "{} ({}/{})",
+            self.titles[at.lesson],
+            at.lesson + 1,
+            self.titles.len()
+        );
+        let counter = if pages.len() > 1 {
+            format!(
+                "slide {}/{}, page {}/{}",
+                at.slide + 1,
+                self.slides[at.lesson].len(),
+                page + 1,
+                pages.len()
+            )
+        } else {
+            format!("slide {}/{}", at.slide + 1, self.slides[at.lesson].len())
+        };
+        let gap = columns.saturating_sub(title.chars().count() + counter.chars().count() + 2);
+        screen.push_str(&format!(
+            " {}{}{}{}{}\n",
+            style.bold,
+            title,
+            style.reset,
+            " ".repeat(gap),
+            counter
+        ));
+        screen.push_str(&format!(" {}\n", "─".repeat(columns.saturating_sub(2))));
+
+        let body = &pages[page];
+        for line in body {
+            screen.push_str(&format!("  {}\n", line));
+        }
+        for _ in body.len()..rows.saturating_sub(CHROME) {
+            screen.push('\n');
+        }
+
+        // How far through the whole deck we are, counting slides.
+        let total: usize = self.slides.iter().map(Vec::len).sum();
+        let done: usize =
+            self.slides[..at.lesson].iter().map(Vec::len).sum::<usize>() + at.slide + 1;
+        let help = "←/→ move  1-9 lesson  g lessons  q quit";
+        let badge = status.map_or_else(String::new, |status| {
+            format!("  {}", badge(status, style.is_colored()))
+        });
+        let bar_width = columns
+            .saturating_sub(help.len() + terminal::width(&badge) + 6)
+            .clamp(10, 40);
+        let filled = bar_width * done / total.max(1);
+        screen.push_str(&format!(" {}\n", "─".repeat(columns.saturating_sub(2))));
+        screen.push_str(&format!(
+            " [{}{}{}{}] {}{}{}{}",
+            style.cyan,
+            "█".repeat(filled),
+            "░".repeat(bar_width - filled),
+            style.reset,
+            style.dim,
+            help,
+            style.reset,
+            badge
+        ));
+        screen
+    }
+
+    /// The list of lessons to jump to.
+    fn menu(&self, at: Position, style: &Style) -> String {
+        let mut menu = format!(" {}Jump to a lesson{}\n\n", style.bold, style.reset);
+        for (index, title) in self.titles.iter().enumerate() {
+            let marker = if index == at.lesson { ">" } else { " " };
+            menu.push_str(&format!(
+                " {} {}. {} {}({} slides){}\n",
+                marker,
+                index + 1,
+                title,
+                style.dim,
+                self.slides[index].len(),
+                style.reset
+            ));
+        }
+        menu.push_str(&format!(
+            "\n {}press a number, or g to go back{}",
+            style.dim, style.reset
+        ));
+        menu
+    }
+}
+
+/// The result of the last run from `club watch`, green if it passed and red if
+/// it didn't, like `✓ borrowing`.
+pub fn badge(status: &Status, color: bool) -> String {
+    let (mark, paint) = if status.passed {
+        ("✓", GREEN)
+    } else {
+        ("✗", RED)
+    };
+    if color {
+        format!("{}{}{} {}{}", paint, BOLD, mark, status.lesson, RESET)
+    } else {
+        format!("{} {}", mark, status.lesson)
+    }
+}
+
+/// Puts chunks of lines onto pages of `height` lines, with a blank line
+/// between chunks. A chunk only starts a new page if it won't fit on this one,
+/// and a chunk taller than a page is split wherever it must be.
+pub fn paginate(chunks: Vec<Vec<String>>, height: usize) -> Vec<Vec<String>> {
+    let mut pages: Vec<Vec<String>> = vec![Vec::new()];
+    for chunk in chunks {
+        let page = pages.last_mut().unwrap();
+        if !page.is_empty() && page.len() + 1 + chunk.len() > height {
+            pages.push(Vec::new());
+        } else if !page.is_empty() {
+            page.push(String::new());
+        }
+
+        for line in chunk {
+            if pages.last().unwrap().len() >= height {
+                pages.push(Vec::new());
+            }
+            pages.last_mut().unwrap().push(line);
+        }
+    }
+    pages
+}
+
+fn render_item(item: &Item, width: usize, style: &Style) -> Vec<String> {
+    match item {
+        Item::Paragraph(text) => terminal::wrap(text, width, "", ""),
+        Item::ListItem {
+            marker,
+            depth,
+            text,
+        } => {
+            let indent = "    ".repeat(*depth);
+            let first = format!("{}{}{}{} ", indent, style.yellow, marker, style.reset);
+            let rest = format!("{}{}", indent, " ".repeat(marker.chars().count() + 1));
+            terminal::wrap(text, width, &first, &rest)
+        }
+        Item::Definition { term, text } => {
+            // Line the definitions up, the way the comments do.
+            let first = format!("{}{:<6}{} ", style.yellow, term, style.reset);
+            let rest = " ".repeat(term.chars().count().max(6) + 1);
+            terminal::wrap(text, width, &first, &rest)
+        }
+    }
+}
+
+fn render_code(block: &Block, style: &Style) -> Vec<String> {
+    let code = block.code();
+    let indent = code
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut lines = Vec::new();
+    let color = match block.kind {
+        Kind::CommentedCode => {
+            let info = block.info().unwrap_or("");
+            let label = match info.strip_prefix("compile_fail") {
+                Some(codes) => format!("✗ doesn't compile {}", codes),
+                None => String::from("commented out"),
+            };
+            lines.push(format!("{}{}{}", style.red, label, style.reset));
+            style.red
+        }
+        _ => style.cyan,
+    };
+    for line in code {
+        let line = line.get(indent..).unwrap_or("");
+        lines.push(format!("{}│ {}{}", color, line, style.reset));
+    }
+    lines
+}
+
+/// The colors to draw with, which are all empty when printing to a file.
+struct Style {
+    reset: &'static str,
+    bold: &'static str,
+    dim: &'static str,
+    red: &'static str,
+    yellow: &'static str,
+    cyan: &'static str,
+}
+
+impl Style {
+    fn is_colored(&self) -> bool {
+        !self.reset.is_empty()
+    }
+
+    fn colored() -> Style {
+        Style {
+            reset: RESET,
+            bold: BOLD,
+            dim: DIM,
+            red: RED,
+            yellow: YELLOW,
+            cyan: CYAN,
+        }
+    }
+
+    fn plain() -> Style {
+        Style {
+            reset: "",
+            bold: "",
+            dim: "",
+            red: "",
+            yellow: "",
+            cyan: "",
+        }
+    }
+}
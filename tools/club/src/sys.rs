@@ -0,0 +1,48 @@
+//! `poll(2)`, which both the presenter and the watcher wait on.
+
+use std::io;
+use std::os::raw::{c_int, c_short};
+use std::os::unix::io::RawFd;
+use std::time::Duration;
+
+#[repr(C)]
+struct PollFd {
+    fd: c_int,
+    events: c_short,
+    revents: c_short,
+}
+
+#[cfg(target_os = "linux")]
+type Count = std::os::raw::c_ulong;
+#[cfg(not(target_os = "linux"))]
+type Count = std::os::raw::c_uint;
+
+const POLLIN: c_short = 0x1;
+
+extern "C" {
+    fn poll(fds: *mut PollFd, count: Count, timeout: c_int) -> c_int;
+}
+
+/// Waits up to `timeout`, or forever, for `fd` to have something to read.
+/// Returns whether it does.
+pub fn readable(fd: RawFd, timeout: Option<Duration>) -> io::Result<bool> {
+    let millis = timeout.map_or(-1, |timeout| {
+        timeout.as_millis().min(c_int::MAX as u128) as c_int
+    });
+    loop {
+        let mut fds = PollFd {
+            fd,
+            events: POLLIN,
+            revents: 0,
+        };
+        // Safety: `fds` is one valid pollfd for the duration of the call.
+        let ready = unsafe { poll(&mut fds, 1, millis) };
+        if ready >= 0 {
+            return Ok(ready > 0);
+        }
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! The bits of terminal handling `club` needs, without pulling in a crate:
+//! reading single keys, the terminal's size, and a few ANSI escapes.
+//!
+//! Single keys come from switching the terminal out of line mode with `stty`,
+//! which is on every Unix-like system the presentation runs on.
+
+use std::env;
+use std::fs::File;
+use std::io::{self, Read};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+pub const CLEAR: &str = "\x1b[2J\x1b[H";
+pub const HIDE_CURSOR: &str = "\x1b[?25l";
+pub const SHOW_CURSOR: &str = "\x1b[?25h";
+
+pub const RESET: &str = "\x1b[0m";
+pub const BOLD: &str = "\x1b[1m";
+pub const DIM: &str = "\x1b[2m";
+pub const RED: &str = "\x1b[31m";
+pub const GREEN: &str = "\x1b[32m";
+pub const YELLOW: &str = "\x1b[33m";
+pub const CYAN: &str = "\x1b[36m";
+
+/// Puts the terminal in a mode where keys arrive as soon as they are pressed
+/// and aren't echoed, until this is dropped. Ctrl-C arrives as a key too,
+/// rather than killing us before the terminal is put back.
+pub struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    pub fn enable() -> io::Result<RawMode> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+        Ok(RawMode {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(File::open("/dev/tty")?)
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The terminal's size as `(rows, columns)`, or 24 by 80 if we can't tell.
+pub fn size() -> (usize, usize) {
+    let from_stty = stty(&["size"]).ok().and_then(|size| {
+        let mut parts = size.split_whitespace().map(str::parse);
+        match (parts.next(), parts.next()) {
+            (Some(Ok(rows)), Some(Ok(columns))) => Some((rows, columns)),
+            _ => None,
+        }
+    });
+
+    from_stty
+        .filter(|&(rows, columns)| rows > 0 && columns > 0)
+        .unwrap_or_else(|| {
+            let var = |name: &str, default| {
+                env::var(name)
+                    .ok()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(default)
+            };
+            (var("LINES", 24), var("COLUMNS", 80))
+        })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Enter,
+    Backspace,
+    Escape,
+    Char(char),
+}
+
+/// How long to wait for the rest of an escape sequence before deciding that
+/// Esc was pressed on its own. Terminals send a whole sequence at once, so this
+/// only has to cover a slow connection.
+const ESCAPE_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Somewhere to read keys from, which can say whether another byte is coming.
+/// It has to be unbuffered, like `/dev/tty` opened as a [`File`], since bytes
+/// already sitting in a buffer wouldn't count.
+pub trait Keys: Read {
+    /// Whether there's a byte to read within `timeout`.
+    fn ready(&mut self, timeout: Duration) -> bool;
+}
+
+impl Keys for File {
+    #[cfg(unix)]
+    fn ready(&mut self, timeout: Duration) -> bool {
+        use std::os::unix::io::AsRawFd;
+        crate::sys::readable(self.as_raw_fd(), Some(timeout)).unwrap_or(false)
+    }
+
+    /// Without `poll`, assume the rest of the sequence is on its way.
+    #[cfg(not(unix))]
+    fn ready(&mut self, _timeout: Duration) -> bool {
+        true
+    }
+}
+
+impl Keys for &[u8] {
+    fn ready(&mut self, _timeout: Duration) -> bool {
+        !self.is_empty()
+    }
+}
+
+/// Waits for a key press. Arrow keys and friends arrive as escape sequences,
+/// like `ESC [ C` for the right arrow, and an Esc with nothing after it is the
+/// Esc key.
+pub fn read_key(input: &mut impl Keys) -> io::Result<Key> {
+    let byte = read_byte(input)?;
+    let key = match byte {
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        0x1b if !input.ready(ESCAPE_TIMEOUT) => Key::Escape,
+        0x1b => match read_byte(input)? {
+            b'[' | b'O' => match read_byte(input)? {
+                b'A' => Key::Up,
+                b'B' => Key::Down,
+                b'C' => Key::Right,
+                b'D' => Key::Left,
+                b'H' => Key::Home,
+                b'F' => Key::End,
+                digit @ b'0'..=b'9' => {
+                    // `ESC [ 5 ~` and the like.
+                    let _tilde = read_byte(input)?;
+                    match digit {
+                        b'1' | b'7' => Key::Home,
+                        b'4' | b'8' => Key::End,
+                        b'5' => Key::PageUp,
+                        b'6' => Key::PageDown,
+                        _ => Key::Escape,
+                    }
+                }
+                _ => Key::Escape,
+            },
+            _ => Key::Escape,
+        },
+        byte if byte.is_ascii() => Key::Char(byte as char),
+        _ => Key::Escape,
+    };
+    Ok(key)
+}
+
+fn read_byte(input: &mut impl Read) -> io::Result<u8> {
+    let mut byte = [0];
+    input.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+/// How many columns a line takes up on screen, ignoring ANSI escapes.
+pub fn width(line: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for c in line.chars() {
+        match c {
+            '\x1b' => in_escape = true,
+            'm' if in_escape => in_escape = false,
+            _ if in_escape => {}
+            _ => width += 1,
+        }
+    }
+    width
+}
+
+/// Wraps text to a width, starting the first line with `first` and the rest
+/// with `rest`, which is how list items get their hanging indent. Escapes in
+/// `first` and `rest` don't count towards the width.
+pub fn wrap(text: &str, width: usize, first: &str, rest: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::from(first);
+    let mut empty = true;
+
+    for word in text.split_whitespace() {
+        if !empty && self::width(&line) + 1 + word.chars().count() > width {
+            lines.push(line);
+            line = String::from(rest);
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+
+    if !empty || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,182 @@
+//! `club watch`: rebuilds and re-runs a lesson every time it's saved, for live
+//! coding without switching away from the editor.
+//!
+//! The result of the last run is also written to `.club/status`, as `pass` or
+//! `fail` and the lesson's name, for anything else that wants to show it, like
+//! a shell prompt, a tmux status line, or `club present`, which shows it in
+//! its footer.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+use lessons::diagnostic::Diagnostic;
+use lessons::explain::{self, Explainer};
+use lessons::runner::{self, Run, Verdict};
+use lessons::{state, Error, Lesson, Workspace};
+
+use crate::terminal::{self, BOLD, DIM, GREEN, RED, RESET};
+use crate::watcher::Watcher;
+
+/// How a build and run of the lesson went.
+enum Outcome {
+    BuildFailed(Vec<Diagnostic>),
+    Ran(Run, Verdict),
+}
+
+impl Outcome {
+    fn passed(&self) -> bool {
+        match self {
+            Outcome::BuildFailed(_) => false,
+            Outcome::Ran(_, verdict) => verdict.is_ok(),
+        }
+    }
+}
+
+/// Watches a lesson's `src/` until interrupted.
+pub fn watch(workspace: &Workspace, index: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let lesson = &workspace.lessons[index];
+    let mut watcher = Watcher::new(&lesson.dir.join("src"));
+    let mut previous: Option<bool> = None;
+
+    loop {
+        let outcome = build_and_run(workspace, lesson)?;
+        let status = Status {
+            passed: outcome.passed(),
+            lesson: lesson.name.clone(),
+        };
+        state::save(&state::status(&workspace.root), &status.to_string())?;
+        crate::remember_last_run(&workspace.root, &lesson.name)?;
+        draw(workspace, index, &outcome, previous, watcher.is_polling())?;
+        previous = Some(outcome.passed());
+        watcher.wait()?;
+    }
+}
+
+fn build_and_run(workspace: &Workspace, lesson: &Lesson) -> Result<Outcome, Error> {
+    let diagnostics = explain::build(&workspace.root, lesson)?;
+    let errors: Vec<Diagnostic> = diagnostics
+        .into_iter()
+        .filter(Diagnostic::is_error)
+        .collect();
+    if !errors.is_empty() {
+        return Ok(Outcome::BuildFailed(errors));
+    }
+    match runner::run(&workspace.root, lesson) {
+        Ok(run) => {
+            let verdict = Verdict::judge(lesson, &run);
+            Ok(Outcome::Ran(run, verdict))
+        }
+        // Something cargo didn't report as a diagnostic, like a broken
+        // manifest. Cargo has already printed it.
+        Err(Error::Build(_)) => Ok(Outcome::BuildFailed(Vec::new())),
+        Err(error) => Err(error),
+    }
+}
+
+fn draw(
+    workspace: &Workspace,
+    index: usize,
+    outcome: &Outcome,
+    previous: Option<bool>,
+    polling: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let lesson = &workspace.lessons[index];
+    let mut stdout = io::stdout().lock();
+    let color = stdout.is_terminal();
+    if color {
+        write!(stdout, "{}", terminal::CLEAR)?;
+    }
+
+    let (mark, status, status_color) = match outcome {
+        Outcome::BuildFailed(_) => ("✗", String::from("doesn't build"), RED),
+        Outcome::Ran(_, verdict) if verdict.is_ok() => ("✓", verdict.to_string(), GREEN),
+        Outcome::Ran(_, verdict) => ("✗", verdict.to_string(), RED),
+    };
+    let was = match previous {
+        Some(true) => " (passed last time)",
+        Some(false) => " (failed last time)",
+        None => "",
+    };
+    writeln!(
+        stdout,
+        "{}{}{} [{}/{}] {} ({}) {}{}{}{}{}\n",
+        status_color,
+        BOLD,
+        mark,
+        index + 1,
+        workspace.lessons.len(),
+        lesson.title,
+        lesson.name,
+        status,
+        RESET,
+        DIM,
+        was,
+        RESET
+    )?;
+
+    match outcome {
+        Outcome::BuildFailed(errors) => {
+            let explainer = Explainer::load(workspace)?;
+            for error in errors {
+                write!(
+                    stdout,
+                    "{}",
+                    crate::explain::annotate(error, &explainer, lesson, &workspace.root, color)
+                )?;
+            }
+        }
+        Outcome::Ran(run, verdict) => {
+            // runner::print writes to stdout itself.
+            drop(stdout);
+            runner::print(lesson, run, *verdict);
+            stdout = io::stdout().lock();
+        }
+    }
+
+    let how = if polling { " (polling)" } else { "" };
+    writeln!(
+        stdout,
+        "\n{}Watching {}{}. Save to rebuild, Ctrl-C to stop.{}",
+        DIM,
+        workspace.relative(&lesson.dir.join("src")),
+        how,
+        RESET
+    )?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// The result of the last run, as kept in `.club/status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    pub passed: bool,
+    pub lesson: String,
+}
+
+impl Status {
+    /// Reads the status file, if `club watch` has written one.
+    pub fn load(root: &Path) -> Option<Status> {
+        Status::parse(&fs::read_to_string(state::status(root)).ok()?)
+    }
+
+    /// Parses a status line, like `pass borrowing`.
+    pub fn parse(text: &str) -> Option<Status> {
+        let mut words = text.split_whitespace();
+        let passed = match words.next()? {
+            "pass" => true,
+            "fail" => false,
+            _ => return None,
+        };
+        let lesson = words.next()?.to_string();
+        Some(Status { passed, lesson })
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let result = if self.passed { "pass" } else { "fail" };
+        writeln!(f, "{} {}", result, self.lesson)
+    }
+}
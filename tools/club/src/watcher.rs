@@ -0,0 +1,293 @@
+//! Waiting for source files to change, for `club watch` and `club exercise`.
+//!
+//! On Linux this uses inotify, so a save shows up straight away without
+//! burning any CPU in between. Anywhere else, or if inotify isn't available
+//! (some container runtimes and network filesystems don't have it), it falls
+//! back to looking at the files' modification times a few times a second.
+//!
+//! Only `.rs` files count, so editors' swap and backup files don't set it off.
+//! Directories made while watching, like a new `src/bin/`, are watched too.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often to look at the files when polling.
+const POLL: Duration = Duration::from_millis(250);
+
+/// Editors often save in more than one write, and some save by writing a new
+/// file and renaming it over the old one, so wait for things to go quiet for
+/// this long before reporting a change.
+const SETTLE: Duration = Duration::from_millis(150);
+
+pub struct Watcher {
+    dir: PathBuf,
+    inner: Inner,
+}
+
+enum Inner {
+    #[cfg(target_os = "linux")]
+    Inotify(inotify::Inotify),
+    Poll(Vec<(PathBuf, Option<SystemTime>, u64)>),
+}
+
+impl Watcher {
+    /// Watches every directory under `dir`.
+    pub fn new(dir: &Path) -> Watcher {
+        #[cfg(target_os = "linux")]
+        {
+            if let Ok(inotify) = inotify::Inotify::new(&directories(dir)) {
+                return Watcher {
+                    dir: dir.to_path_buf(),
+                    inner: Inner::Inotify(inotify),
+                };
+            }
+        }
+        Watcher::polling(dir)
+    }
+
+    /// Watches every directory under `dir` by polling, even where inotify
+    /// would work.
+    pub fn polling(dir: &Path) -> Watcher {
+        Watcher {
+            dir: dir.to_path_buf(),
+            inner: Inner::Poll(fingerprint(dir)),
+        }
+    }
+
+    /// Whether this fell back to polling.
+    pub fn is_polling(&self) -> bool {
+        matches!(self.inner, Inner::Poll(_))
+    }
+
+    /// Blocks until a source file has changed and the changes have settled.
+    pub fn wait(&mut self) -> io::Result<()> {
+        while !self.changed(None)? {}
+        while self.changed(Some(SETTLE))? {}
+        Ok(())
+    }
+
+    /// Waits up to `timeout`, or forever, for a change. Returns whether there
+    /// was one.
+    pub fn changed(&mut self, timeout: Option<Duration>) -> io::Result<bool> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        match &mut self.inner {
+            #[cfg(target_os = "linux")]
+            Inner::Inotify(inotify) => inotify.changed(deadline),
+            Inner::Poll(seen) => loop {
+                let left =
+                    deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+                if left == Some(Duration::ZERO) {
+                    return Ok(false);
+                }
+                thread::sleep(left.map_or(POLL, |left| left.min(POLL)));
+                let now = fingerprint(&self.dir);
+                if now != *seen {
+                    *seen = now;
+                    return Ok(true);
+                }
+            },
+        }
+    }
+}
+
+fn is_source(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "rs")
+}
+
+/// `dir` and every directory under it.
+fn directories(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![dir.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                dirs.extend(directories(&entry.path()));
+            }
+        }
+    }
+    dirs
+}
+
+/// `struct inotify_event`, without the name that follows it.
+const EVENT_SIZE: usize = 16;
+
+/// One of inotify's events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    /// Which watch, and so which directory, it's from.
+    pub watch: i32,
+    /// What happened, as inotify's `IN_*` bits.
+    pub mask: u32,
+    /// The file in the directory that it happened to. Events for the
+    /// directory itself have an empty name.
+    pub name: String,
+}
+
+/// `IN_ISDIR`, set in an event's mask when it's about a directory.
+pub const IS_DIR: u32 = 0x4000_0000;
+
+/// The events in a buffer read from inotify.
+pub fn events(mut buffer: &[u8]) -> impl Iterator<Item = Event> + '_ {
+    std::iter::from_fn(move || {
+        if buffer.len() < EVENT_SIZE {
+            return None;
+        }
+        let field = |at: usize| [buffer[at], buffer[at + 1], buffer[at + 2], buffer[at + 3]];
+        let watch = i32::from_ne_bytes(field(0));
+        let mask = u32::from_ne_bytes(field(4));
+        let len = u32::from_ne_bytes(field(12)) as usize;
+        let end = EVENT_SIZE.saturating_add(len).min(buffer.len());
+        // The name is padded with NULs.
+        let name = &buffer[EVENT_SIZE..end];
+        let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+        let name = String::from_utf8_lossy(name).into_owned();
+        buffer = &buffer[end..];
+        Some(Event { watch, mask, name })
+    })
+}
+
+/// The modification time and size of every source file under `dir`.
+fn fingerprint(dir: &Path) -> Vec<(PathBuf, Option<SystemTime>, u64)> {
+    let mut files = Vec::new();
+    for dir in directories(dir) {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !is_source(&path) {
+                continue;
+            }
+            if let Ok(metadata) = entry.metadata() {
+                files.push((path, metadata.modified().ok(), metadata.len()));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::collections::HashMap;
+    use std::ffi::CString;
+    use std::fs::File;
+    use std::io::{self, Read};
+    use std::os::raw::{c_char, c_int};
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+    use std::path::{Path, PathBuf};
+    use std::time::Instant;
+
+    const IN_CLOEXEC: c_int = 0o2000000;
+
+    const IN_MODIFY: u32 = 0x2;
+    const IN_CLOSE_WRITE: u32 = 0x8;
+    const IN_MOVED_FROM: u32 = 0x40;
+    const IN_MOVED_TO: u32 = 0x80;
+    const IN_CREATE: u32 = 0x100;
+    const IN_DELETE: u32 = 0x200;
+
+    extern "C" {
+        fn inotify_init1(flags: c_int) -> c_int;
+        fn inotify_add_watch(fd: c_int, path: *const c_char, mask: u32) -> c_int;
+    }
+
+    const MASK: u32 =
+        IN_MODIFY | IN_CLOSE_WRITE | IN_MOVED_FROM | IN_MOVED_TO | IN_CREATE | IN_DELETE;
+
+    pub struct Inotify {
+        /// Closes the inotify instance when dropped.
+        file: File,
+        /// The directory each watch is on.
+        dirs: HashMap<c_int, PathBuf>,
+    }
+
+    impl Inotify {
+        pub fn new(dirs: &[PathBuf]) -> io::Result<Inotify> {
+            // Safety: inotify_init1 takes no pointers.
+            let fd = unsafe { inotify_init1(IN_CLOEXEC) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // Safety: `fd` was just opened, and nothing else owns it.
+            let file = unsafe { File::from_raw_fd(fd) };
+
+            let mut inotify = Inotify {
+                file,
+                dirs: HashMap::new(),
+            };
+            for dir in dirs {
+                inotify.watch(dir)?;
+            }
+            Ok(inotify)
+        }
+
+        fn watch(&mut self, dir: &Path) -> io::Result<()> {
+            let path = c_path(dir)?;
+            // Safety: `path` is a valid C string for the duration of the call.
+            let watch = unsafe { inotify_add_watch(self.file.as_raw_fd(), path.as_ptr(), MASK) };
+            if watch < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            self.dirs.insert(watch, dir.to_path_buf());
+            Ok(())
+        }
+
+        /// Watches a directory made since we started, and everything under
+        /// it. Returns whether there are already source files in it, which
+        /// could have been written before it was watched.
+        fn watch_new(&mut self, dir: &Path) -> bool {
+            for dir in super::directories(dir) {
+                // It may have gone again already.
+                let _ = self.watch(&dir);
+            }
+            !super::fingerprint(dir).is_empty()
+        }
+
+        /// Waits until `deadline`, or forever, for a source file to change.
+        pub fn changed(&mut self, deadline: Option<Instant>) -> io::Result<bool> {
+            loop {
+                let timeout = match deadline {
+                    Some(deadline) => {
+                        let left = deadline.saturating_duration_since(Instant::now());
+                        if left.is_zero() {
+                            return Ok(false);
+                        }
+                        Some(left)
+                    }
+                    None => None,
+                };
+                if !crate::sys::readable(self.file.as_raw_fd(), timeout)? {
+                    return Ok(false);
+                }
+
+                let mut buffer = [0; 4096];
+                let read = self.file.read(&mut buffer)?;
+                let mut changed = false;
+                for event in super::events(&buffer[..read]) {
+                    let made = IN_CREATE | IN_MOVED_TO;
+                    if event.mask & super::IS_DIR != 0 && event.mask & made != 0 {
+                        if let Some(parent) = self.dirs.get(&event.watch) {
+                            let dir = parent.join(&event.name);
+                            changed |= self.watch_new(&dir);
+                        }
+                    } else if super::is_source(Path::new(&event.name)) {
+                        changed = true;
+                    }
+                }
+                if changed {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    fn c_path(path: &Path) -> io::Result<CString> {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains a NUL"))
+    }
+}
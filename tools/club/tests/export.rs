@@ -0,0 +1,106 @@
+//! Checks the HTML that `club export` writes: the decks, the code blocks and
+//! the highlighting inside them.
+
+use club::export::{code, page};
+use club::highlight::{escape, line};
+
+fn lines(code: &[&str]) -> Vec<String> {
+    code.iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn a_page_has_a_section_per_slide_and_links_on_from_the_last() {
+    let slides = [String::from("<h1>One</h1>\n"), String::from("<p>Two</p>\n")];
+    let html = page(
+        "Data <Types>",
+        &slides,
+        Some((
+            String::from("next.html"),
+            String::from("Next: \"Borrowing\""),
+        )),
+    );
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>Data &lt;Types&gt;</title>"));
+    assert!(html.contains("<section class=\"slide\" id=\"slide-1\">\n<h1>One</h1>\n</section>"));
+    assert!(html.contains(
+        "<section class=\"slide\" id=\"slide-2\">\n<p>Two</p>\n\
+         <p class=\"next\"><a href=\"next.html\">Next: &quot;Borrowing&quot; →</a></p>\n\
+         </section>"
+    ));
+    // Everything the deck needs is in the one file.
+    assert!(!html.contains("<link"));
+    assert!(!html.contains("src=\""));
+
+    let last = page("Overflow", &slides, None);
+    assert!(!last.contains("class=\"next\""));
+}
+
+#[test]
+fn code_loses_its_common_indentation() {
+    let html = code(&lines(&["    let x = 5;", "", "        x"]));
+    assert_eq!(
+        html,
+        "<pre class=\"code\"><code>\
+         <span class=\"keyword\">let</span> x = <span class=\"number\">5</span>;\n\
+         \n    x\
+         </code></pre>\n"
+    );
+}
+
+#[test]
+fn html_in_code_is_escaped() {
+    assert_eq!(
+        escape("<a href='x'>&</a>"),
+        "&lt;a href=&#39;x&#39;&gt;&amp;&lt;/a&gt;"
+    );
+    assert_eq!(
+        line("a < b && c > \"<d>\""),
+        "a &lt; b &amp;&amp; c &gt; <span class=\"string\">&quot;&lt;d&gt;&quot;</span>"
+    );
+}
+
+#[test]
+fn tokens_get_the_class_for_what_they_are() {
+    assert_eq!(
+        line("let s: String = format!(\"{}\", add(1, 2)); // sum"),
+        "<span class=\"keyword\">let</span> s: <span class=\"type\">String</span> = \
+         <span class=\"macro\">format!</span>(<span class=\"string\">&quot;{}&quot;</span>, \
+         <span class=\"function\">add</span>(<span class=\"number\">1</span>, \
+         <span class=\"number\">2</span>)); <span class=\"comment\">// sum</span>"
+    );
+    // A range isn't a decimal point.
+    assert_eq!(
+        line("0..1"),
+        "<span class=\"number\">0</span>..<span class=\"number\">1</span>"
+    );
+}
+
+#[test]
+fn lifetimes_are_told_apart_from_chars() {
+    assert_eq!(
+        line("fn f<'a>(c: &'a char) -> char { 'x' }"),
+        "<span class=\"keyword\">fn</span> f&lt;\
+         <span class=\"lifetime\">&#39;a</span>&gt;(c: &amp;<span class=\"lifetime\">&#39;a</span> \
+         <span class=\"type\">char</span>) -&gt; <span class=\"type\">char</span> { \
+         <span class=\"string\">&#39;x&#39;</span> }"
+    );
+    assert_eq!(
+        line("'\\n' 'static"),
+        "<span class=\"string\">&#39;\\n&#39;</span> <span class=\"lifetime\">&#39;static</span>"
+    );
+}
+
+#[test]
+fn an_unterminated_string_runs_to_the_end_of_the_line() {
+    assert_eq!(
+        line("let s = \"never closed"),
+        "<span class=\"keyword\">let</span> s = <span class=\"string\">&quot;never closed</span>"
+    );
+    // The backslash escapes a character that isn't there.
+    assert_eq!(
+        line("\"ends in \\"),
+        "<span class=\"string\">&quot;ends in \\</span>"
+    );
+    assert_eq!(line("'\\"), "<span class=\"string\">&#39;\\</span>");
+}
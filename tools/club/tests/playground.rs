@@ -0,0 +1,152 @@
+//! Reads made-up HTTP requests, and routes them the way the playground would,
+//! without a server.
+
+use club::playground::{
+    read_request, route, HttpRequest, Response, MAX_BODY, MAX_HEAD, MAX_HEADERS, MAX_LINE,
+};
+
+#[path = "../../lessons/tests/common/mod.rs"]
+mod common;
+
+use common::workspace;
+
+fn read(request: &str) -> Result<HttpRequest, Response> {
+    read_request(&mut request.as_bytes()).expect("reading from bytes can't fail")
+}
+
+fn get(path: &str) -> Response {
+    let request = HttpRequest {
+        method: String::from("GET"),
+        path: path.to_string(),
+        body: String::new(),
+    };
+    route(&request, &workspace(), 0)
+}
+
+#[test]
+fn a_request_is_read_up_to_its_content_length() {
+    let request = read(
+        "POST /execute HTTP/1.1\r\nHost: localhost\r\ncontent-LENGTH: 5\r\n\r\nhello, and more",
+    )
+    .unwrap();
+    assert_eq!(
+        request,
+        HttpRequest {
+            method: String::from("POST"),
+            path: String::from("/execute"),
+            body: String::from("hello"),
+        }
+    );
+
+    let request = read("GET /lessons HTTP/1.1\r\n\r\n").unwrap();
+    assert_eq!(request.body, "");
+}
+
+#[test]
+fn malformed_requests_are_bad_requests() {
+    assert_eq!(read("").unwrap_err().status, "400 Bad Request");
+    assert_eq!(read("GET\r\n\r\n").unwrap_err().status, "400 Bad Request");
+    let response = read("POST /execute HTTP/1.1\r\nContent-Length: lots\r\n\r\n").unwrap_err();
+    assert_eq!(response.status, "400 Bad Request");
+    assert!(response.body.contains("Content-Length"));
+}
+
+#[test]
+fn a_body_over_the_limit_isnt_read() {
+    let at_the_limit = format!(
+        "POST /execute HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+        MAX_BODY,
+        "x".repeat(MAX_BODY)
+    );
+    assert_eq!(read(&at_the_limit).unwrap().body.len(), MAX_BODY);
+
+    // Only the headers are there: the body would be refused before reading it.
+    let over = format!(
+        "POST /execute HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+        MAX_BODY + 1
+    );
+    assert_eq!(read(&over).unwrap_err().status, "413 Payload Too Large");
+}
+
+#[test]
+fn lines_and_headers_over_their_limits_arent_read() {
+    let path = format!("/{}", "x".repeat(MAX_LINE));
+    let long_line = format!("GET {} HTTP/1.1\r\n\r\n", path);
+    assert_eq!(read(&long_line).unwrap_err().status, "400 Bad Request");
+
+    let endless_header = format!("GET / HTTP/1.1\r\nX-Long: {}", "x".repeat(4 * MAX_LINE));
+    assert_eq!(
+        read(&endless_header).unwrap_err().status,
+        "431 Request Header Fields Too Large"
+    );
+
+    let many_headers = format!(
+        "GET / HTTP/1.1\r\n{}\r\n",
+        "X-A: b\r\n".repeat(MAX_HEADERS + 1)
+    );
+    assert_eq!(
+        read(&many_headers).unwrap_err().status,
+        "431 Request Header Fields Too Large"
+    );
+    let just_enough = format!("GET / HTTP/1.1\r\n{}\r\n", "X-A: b\r\n".repeat(MAX_HEADERS));
+    assert!(read(&just_enough).is_ok());
+
+    let header = format!("X-Big: {}\r\n", "x".repeat(MAX_LINE - 100));
+    let big_headers = format!(
+        "GET / HTTP/1.1\r\n{}\r\n",
+        header.repeat(MAX_HEAD / header.len() + 1)
+    );
+    assert_eq!(
+        read(&big_headers).unwrap_err().status,
+        "431 Request Header Fields Too Large"
+    );
+}
+
+#[test]
+fn the_editor_and_lessons_are_served() {
+    let editor = get("/");
+    assert_eq!(editor.status, "200 OK");
+    assert_eq!(editor.content_type, "text/html");
+    assert_eq!(get("/index.html"), editor);
+
+    let lessons = get("/lessons");
+    assert_eq!(lessons.content_type, "application/json");
+    assert!(lessons
+        .body
+        .contains("{\"edition\":\"2018\",\"name\":\"hello_world\",\"title\":\"Hello, World!\"}"));
+
+    let lesson = get("/lessons/hello_world");
+    assert_eq!(lesson.status, "200 OK");
+    assert!(lesson.body.contains("fn main()"));
+}
+
+#[test]
+fn anything_else_is_an_error() {
+    assert_eq!(get("/lessons/nope").status, "404 Not Found");
+    assert_eq!(get("/nope").status, "404 Not Found");
+    assert_eq!(get("/execute").status, "405 Method Not Allowed");
+
+    let request = HttpRequest {
+        method: String::from("POST"),
+        path: String::from("/execute"),
+        body: String::from("{\"code\": "),
+    };
+    let response = route(&request, &workspace(), 0);
+    assert_eq!(response.status, "400 Bad Request");
+    assert!(response.body.starts_with("{\"error\":"));
+}
+
+#[test]
+fn hostile_json_is_a_bad_request() {
+    for body in [
+        String::from("{\"code\": \"\\uD800\\u0041\"}"),
+        "[".repeat(MAX_BODY),
+    ] {
+        let request = HttpRequest {
+            method: String::from("POST"),
+            path: String::from("/execute"),
+            body,
+        };
+        assert_eq!(route(&request, &workspace(), 0).status, "400 Bad Request");
+    }
+}
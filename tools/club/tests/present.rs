@@ -0,0 +1,60 @@
+//! The result `club watch` leaves in `.club/status`, and how `club present`
+//! shows it in its footer.
+
+use std::env;
+use std::fs;
+
+use club::present::badge;
+use club::terminal::{self, GREEN, RED};
+use club::watch::Status;
+use lessons::state;
+
+fn status(passed: bool, lesson: &str) -> Status {
+    Status {
+        passed,
+        lesson: lesson.to_string(),
+    }
+}
+
+#[test]
+fn a_pass_is_green_and_a_fail_is_red() {
+    let pass = badge(&status(true, "borrowing"), true);
+    assert!(pass.starts_with(GREEN), "{:?}", pass);
+    assert!(pass.contains("✓ borrowing"), "{:?}", pass);
+    assert_eq!(terminal::width(&pass), "✓ borrowing".chars().count());
+
+    let fail = badge(&status(false, "overflow"), true);
+    assert!(fail.starts_with(RED), "{:?}", fail);
+    assert!(fail.contains("✗ overflow"), "{:?}", fail);
+}
+
+#[test]
+fn without_color_the_mark_says_it() {
+    assert_eq!(badge(&status(true, "borrowing"), false), "✓ borrowing");
+    assert_eq!(badge(&status(false, "overflow"), false), "✗ overflow");
+}
+
+#[test]
+fn a_status_reads_back_the_way_it_was_written() {
+    for written in [status(true, "data_types"), status(false, "borrowing")] {
+        assert_eq!(Status::parse(&written.to_string()), Some(written));
+    }
+    assert_eq!(
+        Status::parse("pass borrowing\n"),
+        Some(status(true, "borrowing"))
+    );
+    assert_eq!(Status::parse("maybe borrowing"), None);
+    assert_eq!(Status::parse("pass"), None);
+    assert_eq!(Status::parse(""), None);
+}
+
+#[test]
+fn the_status_is_loaded_from_the_workspace() {
+    let root = env::temp_dir().join(format!("club-present-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    assert_eq!(Status::load(&root), None);
+
+    state::save(&state::status(&root), "fail overflow\n").unwrap();
+    assert_eq!(Status::load(&root), Some(status(false, "overflow")));
+    let _ = fs::remove_dir_all(&root);
+}
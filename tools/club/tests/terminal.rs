@@ -0,0 +1,151 @@
+//! Reads keys out of made-up terminal input, and wraps and pages text the way
+//! `club present` lays out its slides.
+
+use club::present::paginate;
+use club::terminal::{read_key, width, wrap, Key};
+
+/// Every key in `input`, in order.
+fn keys(mut input: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    while !input.is_empty() {
+        keys.push(read_key(&mut input).unwrap());
+    }
+    keys
+}
+
+fn lines(count: usize, text: &str) -> Vec<String> {
+    (0..count).map(|n| format!("{} {}", text, n)).collect()
+}
+
+#[test]
+fn plain_keys_are_characters() {
+    assert_eq!(
+        keys(b"q \r\n\x7f\x08\x03"),
+        [
+            Key::Char('q'),
+            Key::Char(' '),
+            Key::Enter,
+            Key::Enter,
+            Key::Backspace,
+            Key::Backspace,
+            Key::Char('\x03'),
+        ]
+    );
+}
+
+#[test]
+fn escape_sequences_are_the_keys_they_stand_for() {
+    assert_eq!(
+        keys(b"\x1b[A\x1b[B\x1b[C\x1b[D\x1bOH\x1bOF"),
+        [
+            Key::Up,
+            Key::Down,
+            Key::Right,
+            Key::Left,
+            Key::Home,
+            Key::End
+        ]
+    );
+    assert_eq!(
+        keys(b"\x1b[5~\x1b[6~\x1b[1~\x1b[4~\x1b[7~\x1b[8~"),
+        [
+            Key::PageUp,
+            Key::PageDown,
+            Key::Home,
+            Key::End,
+            Key::Home,
+            Key::End
+        ]
+    );
+}
+
+#[test]
+fn an_escape_with_nothing_after_it_is_the_esc_key() {
+    assert_eq!(keys(b"\x1b"), [Key::Escape]);
+    assert_eq!(keys(b"j\x1b"), [Key::Char('j'), Key::Escape]);
+}
+
+#[test]
+fn sequences_we_dont_know_are_swallowed_whole() {
+    // One key, not an Esc followed by a stray `Z`.
+    assert_eq!(keys(b"\x1b[Z"), [Key::Escape]);
+    assert_eq!(keys(b"\x1b[2~q"), [Key::Escape, Key::Char('q')]);
+}
+
+#[test]
+fn a_cut_off_sequence_is_an_error() {
+    let mut input: &[u8] = b"\x1b[";
+    assert!(read_key(&mut input).is_err());
+}
+
+#[test]
+fn escapes_take_up_no_width() {
+    assert_eq!(width("plain"), 5);
+    assert_eq!(width("\x1b[33m-\x1b[0m item"), 6);
+    assert_eq!(width("ß→"), 2);
+}
+
+#[test]
+fn text_wraps_at_word_boundaries_with_a_hanging_indent() {
+    assert_eq!(
+        wrap("one two three four five", 10, "- ", "  "),
+        ["- one two", "  three", "  four", "  five"]
+    );
+    assert_eq!(wrap("short", 80, "", ""), ["short"]);
+    assert_eq!(wrap("", 80, "> ", ""), ["> "]);
+}
+
+#[test]
+fn a_word_longer_than_the_width_gets_a_line_of_its_own() {
+    assert_eq!(
+        wrap("a supercalifragilistic b", 8, "", ""),
+        ["a", "supercalifragilistic", "b"]
+    );
+}
+
+#[test]
+fn the_width_ignores_escapes_in_the_indent() {
+    let first = "\x1b[33m-\x1b[0m ";
+    let wrapped = wrap("aaa bbb ccc", 9, first, "  ");
+    assert_eq!(
+        wrapped,
+        [format!("{}aaa bbb", first), String::from("  ccc")]
+    );
+}
+
+#[test]
+fn chunks_share_a_page_with_a_blank_line_between_them() {
+    let pages = paginate(vec![lines(2, "a"), lines(2, "b")], 10);
+    assert_eq!(pages, [vec!["a 0", "a 1", "", "b 0", "b 1"]]);
+}
+
+#[test]
+fn a_chunk_that_doesnt_fit_starts_the_next_page() {
+    let pages = paginate(vec![lines(3, "a"), lines(3, "b"), lines(1, "c")], 6);
+    assert_eq!(
+        pages,
+        [
+            vec!["a 0", "a 1", "a 2"],
+            vec!["b 0", "b 1", "b 2", "", "c 0"]
+        ]
+    );
+}
+
+#[test]
+fn a_chunk_taller_than_a_page_is_split() {
+    let pages = paginate(vec![lines(1, "a"), lines(5, "b")], 2);
+    assert_eq!(
+        pages,
+        [
+            vec!["a 0"],
+            vec!["b 0", "b 1"],
+            vec!["b 2", "b 3"],
+            vec!["b 4"]
+        ]
+    );
+}
+
+#[test]
+fn nothing_is_still_one_page() {
+    assert_eq!(paginate(Vec::new(), 10), [Vec::<String>::new()]);
+}
@@ -0,0 +1,194 @@
+//! Tells the watcher about saves the way an editor makes them, and checks
+//! which ones it notices and when it reports them.
+
+use std::env;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use club::watcher::{self, Watcher};
+
+/// Longer than the watcher takes to notice a change, either way.
+const NOTICE: Duration = Duration::from_secs(2);
+
+/// Shorter than that, for changes that shouldn't be noticed at all.
+const QUIET: Duration = Duration::from_millis(600);
+
+/// Both ways of watching, to make one of each as it's needed.
+const WATCHERS: [fn(&Path) -> Watcher; 2] = [Watcher::new, Watcher::polling];
+
+/// A fresh directory with a `main.rs` and a `lib/` under it.
+fn scratch() -> PathBuf {
+    static TESTS: AtomicUsize = AtomicUsize::new(0);
+    let test = TESTS.fetch_add(1, Ordering::SeqCst);
+    let dir = env::temp_dir().join(format!("club-watcher-test-{}-{}", std::process::id(), test));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("lib")).unwrap();
+    fs::write(dir.join("main.rs"), "fn main() {}\n").unwrap();
+    dir
+}
+
+/// An `inotify_event` as the kernel lays it out: wd, mask, cookie and the
+/// name's length, then the name padded with NULs.
+fn event(name: &str, padded: usize) -> Vec<u8> {
+    event_on(1, 0x8, name, padded)
+}
+
+fn event_on(watch: i32, mask: u32, name: &str, padded: usize) -> Vec<u8> {
+    let mut event = Vec::new();
+    event.extend_from_slice(&watch.to_ne_bytes());
+    event.extend_from_slice(&mask.to_ne_bytes());
+    event.extend_from_slice(&0u32.to_ne_bytes());
+    event.extend_from_slice(&(padded as u32).to_ne_bytes());
+    event.extend_from_slice(name.as_bytes());
+    event.resize(16 + padded, 0);
+    event
+}
+
+#[test]
+fn events_reads_every_event_in_the_buffer() {
+    let mut buffer = event("main.rs", 16);
+    buffer.extend(event(".main.rs.swp", 16));
+    buffer.extend(event("", 0));
+    buffer.extend(event("a_much_longer_name.rs", 32));
+    let names: Vec<String> = watcher::events(&buffer).map(|event| event.name).collect();
+    assert_eq!(
+        names,
+        ["main.rs", ".main.rs.swp", "", "a_much_longer_name.rs"]
+    );
+}
+
+#[test]
+fn events_say_which_watch_and_what_happened() {
+    // IN_CREATE, on a directory.
+    let buffer = event_on(7, 0x100 | watcher::IS_DIR, "bin", 16);
+    let events: Vec<_> = watcher::events(&buffer).collect();
+    assert_eq!(
+        events,
+        [watcher::Event {
+            watch: 7,
+            mask: 0x100 | watcher::IS_DIR,
+            name: String::from("bin"),
+        }]
+    );
+}
+
+#[test]
+fn events_stops_at_a_truncated_event() {
+    let mut buffer = event("main.rs", 16);
+    buffer.extend_from_slice(&event("lib.rs", 16)[..10]);
+    let names: Vec<String> = watcher::events(&buffer).map(|event| event.name).collect();
+    assert_eq!(names, ["main.rs"]);
+
+    // A length running past the end of the buffer takes what's there.
+    let mut buffer = event("main.rs", 16);
+    buffer.truncate(20);
+    let names: Vec<String> = watcher::events(&buffer).map(|event| event.name).collect();
+    assert_eq!(names, ["main"]);
+
+    assert_eq!(watcher::events(&[]).count(), 0);
+}
+
+#[test]
+fn polling_notices_a_newer_modification_time() {
+    let dir = scratch();
+    let mut watcher = Watcher::polling(&dir);
+    assert!(watcher.is_polling());
+    assert!(!watcher.changed(Some(QUIET)).unwrap());
+
+    // Same contents and size, only the time moves.
+    let later = SystemTime::now() + Duration::from_secs(60);
+    File::options()
+        .write(true)
+        .open(dir.join("main.rs"))
+        .unwrap()
+        .set_modified(later)
+        .unwrap();
+    assert!(watcher.changed(Some(NOTICE)).unwrap());
+    assert!(!watcher.changed(Some(QUIET)).unwrap());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn polling_notices_new_and_removed_files_in_subdirectories() {
+    let dir = scratch();
+    let mut watcher = Watcher::polling(&dir);
+
+    fs::write(dir.join("lib").join("mod.rs"), "").unwrap();
+    assert!(watcher.changed(Some(NOTICE)).unwrap());
+    fs::remove_file(dir.join("lib").join("mod.rs")).unwrap();
+    assert!(watcher.changed(Some(NOTICE)).unwrap());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn files_that_arent_source_are_ignored() {
+    let dir = scratch();
+    for watch in &WATCHERS {
+        let mut watcher = watch(&dir);
+        fs::write(dir.join(".main.rs.swp"), "swap").unwrap();
+        fs::write(dir.join("main.rs~"), "backup").unwrap();
+        assert!(!watcher.changed(Some(QUIET)).unwrap());
+    }
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn new_notices_a_save() {
+    let dir = scratch();
+    let mut watcher = Watcher::new(&dir);
+    fs::write(dir.join("main.rs"), "fn main() { println!(); }\n").unwrap();
+    assert!(watcher.changed(Some(NOTICE)).unwrap());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn wait_returns_once_a_save_in_several_writes_has_settled() {
+    let dir = scratch();
+    for watch in &WATCHERS {
+        let mut watcher = watch(&dir);
+        let path = dir.join("main.rs");
+        let start = Instant::now();
+        let editor = thread::spawn(move || {
+            for contents in &["fn", "fn main()", "fn main() {}"] {
+                thread::sleep(Duration::from_millis(100));
+                fs::write(&path, contents).unwrap();
+            }
+        });
+        watcher.wait().unwrap();
+        let waited = start.elapsed();
+        editor.join().unwrap();
+
+        // The last write was 300ms in, and then it has to go quiet.
+        assert!(waited >= Duration::from_millis(400), "{:?}", waited);
+        // Every write was taken up by that one wait.
+        assert!(!watcher.changed(Some(QUIET)).unwrap());
+    }
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn directories_made_while_watching_are_watched() {
+    let dir = scratch();
+    for (made, watch) in WATCHERS.iter().enumerate() {
+        let mut watcher = watch(&dir);
+        let bin = dir.join(format!("bin{}", made));
+        fs::create_dir(&bin).unwrap();
+        // A directory on its own isn't a change.
+        assert!(!watcher.changed(Some(QUIET)).unwrap());
+        fs::write(bin.join("main.rs"), "fn main() {}\n").unwrap();
+        assert!(watcher.changed(Some(NOTICE)).unwrap());
+
+        // A file written before its new directory could be watched still
+        // counts, and so do later saves to it.
+        let nested = dir.join(format!("nested{}", made)).join("module");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("mod.rs"), "").unwrap();
+        assert!(watcher.changed(Some(NOTICE)).unwrap());
+        fs::write(nested.join("mod.rs"), "fn f() {}\n").unwrap();
+        assert!(watcher.changed(Some(NOTICE)).unwrap());
+    }
+    let _ = fs::remove_dir_all(&dir);
+}
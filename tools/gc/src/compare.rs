@@ -0,0 +1,162 @@
+//! The same script on both heaps, side by side.
+
+use crate::simulate::{Object, Report, Sample, Strategy, Why};
+
+/// How much of each line of the script the timeline shows.
+const CODE_WIDTH: usize = 32;
+
+/// A timeline of both heaps, step by step, then when each object was freed
+/// and a summary.
+pub fn compare(source: &str, ownership: &Report, collected: &Report) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut text = String::new();
+
+    let threshold = match collected.strategy {
+        Strategy::MarkSweep { threshold } => threshold,
+        Strategy::Ownership => 0,
+    };
+    text.push_str(&format!(
+        "{:>4}  {:<width$}  {:<24}  mark-and-sweep, every {} bytes\n",
+        "line",
+        "script",
+        "ownership",
+        threshold,
+        width = CODE_WIDTH
+    ));
+    for (owned, swept) in ownership.samples.iter().zip(&collected.samples) {
+        let code = lines.get(owned.line - 1).map_or("", |line| line.trim());
+        text.push_str(&format!(
+            "{:>4}  {:<width$}  {:<24}  {}\n",
+            owned.line,
+            cut(code, CODE_WIDTH),
+            cell(owned, None),
+            cell(swept, Some(collected)),
+            width = CODE_WIDTH
+        ));
+    }
+
+    text.push_str("\nobjects\n");
+    for (index, object) in ownership.objects.iter().enumerate() {
+        text.push_str(&format!("  {}\n", name(index, object)));
+        text.push_str(&format!(
+            "    ownership:       {}\n",
+            freed(ownership, index)
+        ));
+        text.push_str(&format!(
+            "    mark-and-sweep:  {}\n",
+            freed(collected, index)
+        ));
+    }
+
+    let pauses = |report: &Report| match report.pauses.len() {
+        0 => String::from("none"),
+        count => format!(
+            "{}, {} ticks in all, the longest {}",
+            count,
+            report.paused(),
+            report.longest_pause()
+        ),
+    };
+    let leaked = |report: &Report| -> usize {
+        report
+            .objects
+            .iter()
+            .filter(|object| object.freed.is_none())
+            .map(|object| object.bytes)
+            .sum()
+    };
+    text.push('\n');
+    let rows = [
+        (
+            "peak memory",
+            format!("{} bytes", ownership.peak),
+            format!("{} bytes", collected.peak),
+        ),
+        ("pauses", pauses(ownership), pauses(collected)),
+        (
+            "run time",
+            format!("{} ticks", ownership.ticks),
+            format!("{} ticks", collected.ticks),
+        ),
+        (
+            "never freed",
+            format!("{} bytes", leaked(ownership)),
+            format!("{} bytes", leaked(collected)),
+        ),
+    ];
+    text.push_str(&format!(
+        "{:<14}  {:<24}  mark-and-sweep\n",
+        "", "ownership"
+    ));
+    for (what, owned, swept) in &rows {
+        text.push_str(&format!("{:<14}  {:<24}  {}\n", what, owned, swept));
+    }
+    text
+}
+
+/// The heap after a step, and what the step freed.
+fn cell(sample: &Sample, collected: Option<&Report>) -> String {
+    let mut cell = format!("{} B", sample.bytes);
+    let freed: Vec<String> = sample
+        .freed
+        .iter()
+        .map(|index| format!("#{}", index + 1))
+        .collect();
+    match (sample.pause, collected) {
+        (Some(pause), Some(report)) => {
+            let length = report.pauses[pause].length;
+            if freed.is_empty() {
+                cell.push_str(&format!(", paused {} ticks, freed nothing", length));
+            } else {
+                cell.push_str(&format!(
+                    ", paused {} ticks, freed {}",
+                    length,
+                    freed.join(" ")
+                ));
+            }
+        }
+        _ if !freed.is_empty() => cell.push_str(&format!(", freed {}", freed.join(" "))),
+        _ => {}
+    }
+    cell
+}
+
+fn name(index: usize, object: &Object) -> String {
+    let label = if object.label.is_empty() {
+        String::new()
+    } else {
+        format!(" {:?}", object.label)
+    };
+    format!(
+        "#{}{}, {} bytes, allocated on line {}",
+        index + 1,
+        label,
+        object.bytes,
+        object.allocated
+    )
+}
+
+/// When and why an object was freed.
+fn freed(report: &Report, index: usize) -> String {
+    let freed = match &report.objects[index].freed {
+        Some(freed) => freed,
+        None => return String::from("never; it was still in the heap when the program ended"),
+    };
+    let why = match &freed.why {
+        Why::OutOfScope(owner) => format!("when `{}` went out of scope", owner),
+        Why::Replaced(owner) => format!("when `{}` was given a new value", owner),
+        Why::Grew(owner) => format!("when `{}` grew", owner),
+        Why::Owner(owner) => format!("along with #{}, which owned it", owner + 1),
+        Why::Collected(pause) => format!("by collection {}", pause + 1),
+    };
+    format!("line {}, tick {}, {}", freed.line, freed.tick, why)
+}
+
+fn cut(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width - 3).collect();
+    cut.push_str("...");
+    cut
+}
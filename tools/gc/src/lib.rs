@@ -0,0 +1,28 @@
+//! gc: Rust's drop-at-the-end-of-scope against a garbage collector, on the
+//! same program.
+//!
+//! The `borrowing` lesson says Rust doesn't need a garbage collector,
+//! because it knows a value can be freed when its owner goes out of scope,
+//! where Java has to periodically search for values that have become
+//! garbage. This runs an allocation [`script`] on two simulated heaps, one
+//! that frees by ownership and one that's mark-and-sweep, and [`compare`]s
+//! their peak memory, their pauses, and the moment each object is freed.
+//! Scripts are in `scripts/`.
+
+pub mod compare;
+pub mod script;
+pub mod simulate;
+
+use crate::script::Script;
+use crate::simulate::Strategy;
+
+/// Collections happen every this many bytes, unless told otherwise.
+pub const THRESHOLD: usize = 16;
+
+/// Runs a script on both heaps, and compares them.
+pub fn compare(source: &str, threshold: usize) -> Result<String, String> {
+    let script = Script::parse(source)?;
+    let ownership = simulate::run(&script, Strategy::Ownership)?;
+    let collected = simulate::run(&script, Strategy::MarkSweep { threshold })?;
+    Ok(compare::compare(source, &ownership, &collected))
+}
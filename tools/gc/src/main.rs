@@ -0,0 +1,54 @@
+//! `gc` runs a program's allocations on an ownership heap and on a
+//! mark-and-sweep heap, and compares them.
+
+use std::env;
+use std::fs;
+use std::process;
+
+const USAGE: &str = "\
+usage: gc <command>
+
+commands:
+    compare <script> [--threshold <bytes>]
+                  run the script on both heaps, and show when each frees
+                  what, side by side; the collector runs every 16 bytes
+                  unless told otherwise";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match gc(&args) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("gc: {}", error);
+            process::exit(2);
+        }
+    }
+}
+
+fn gc(args: &[&str]) -> Result<bool, String> {
+    match args {
+        ["compare", file] => compare(file, gc::THRESHOLD),
+        ["compare", file, "--threshold", bytes] => {
+            let threshold = bytes
+                .parse()
+                .map_err(|_| format!("`{}` isn't a number of bytes", bytes))?;
+            compare(file, threshold)
+        }
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{}", USAGE);
+            Ok(true)
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn compare(file: &str, threshold: usize) -> Result<bool, String> {
+    let source = fs::read_to_string(file).map_err(|error| format!("{}: {}", file, error))?;
+    let comparison =
+        gc::compare(&source, threshold).map_err(|error| format!("{}: {}", file, error))?;
+    print!("{}", comparison);
+    Ok(true)
+}
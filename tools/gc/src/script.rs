@@ -0,0 +1,226 @@
+//! Allocation scripts: what a program does with its heap, one step per
+//! line.
+//!
+//! ```text
+//! # `name` in borrowing::main, which grows as it's changed.
+//! fn main {
+//!     let name = new 4 "Gabe"
+//!     fn say_hello {
+//!         let name_ref = &name
+//!     }
+//!     grow name 9 "Gabe Love"
+//! }
+//! ```
+//!
+//! The steps are:
+//!
+//! - `fn <name> {` or `{`, and `}`: a scope, which ends at the `}`.
+//! - `let <var> = new <bytes> ["label"]`: `var` owns a new object.
+//! - `let <var> = &<other>` or `let <var> = &mut <other>`: `var` refers to
+//!   `other`'s object without owning it.
+//! - `let <var> = <other>`: `other`'s object moves to `var`.
+//! - `<var> = new <bytes> ["label"]`: `var` owns a new object instead.
+//! - `grow <var> <bytes> ["label"]`: the object `var` owns or refers to is
+//!   reallocated, like a `String` that outgrew its buffer.
+//! - `move <var> into <other>`: `var`'s object moves into the one `other`
+//!   owns, like pushing it onto a `Vec`.
+//! - `work <ticks>`: time passes without touching the heap.
+
+/// A script, as a list of steps with scopes nested inside.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Script {
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// The line of the script it's on.
+    pub line: usize,
+    pub kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    Scope {
+        name: Option<String>,
+        steps: Vec<Step>,
+        /// The line of the `}`.
+        end: usize,
+    },
+    New {
+        var: String,
+        bytes: usize,
+        label: String,
+    },
+    Borrow {
+        var: String,
+        of: String,
+        mutable: bool,
+    },
+    Move {
+        var: String,
+        from: String,
+    },
+    Assign {
+        var: String,
+        bytes: usize,
+        label: String,
+    },
+    Grow {
+        var: String,
+        bytes: usize,
+        label: String,
+    },
+    MoveInto {
+        var: String,
+        owner: String,
+    },
+    Work(usize),
+}
+
+impl Script {
+    /// Reads a script. Errors say which line of it is wrong.
+    pub fn parse(text: &str) -> Result<Script, String> {
+        // The scopes that are open, with the steps so far in each.
+        let mut open: Vec<(usize, Option<String>, Vec<Step>)> = vec![(0, None, Vec::new())];
+
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let at = |error: String| format!("line {}: {}", number, error);
+            let words = words(line).map_err(at)?;
+            let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+            let kind = match words.as_slice() {
+                ["fn", name, "{"] => {
+                    open.push((number, Some(name.to_string()), Vec::new()));
+                    continue;
+                }
+                ["{"] => {
+                    open.push((number, None, Vec::new()));
+                    continue;
+                }
+                ["}"] => {
+                    if open.len() == 1 {
+                        return Err(at(String::from("`}` without a `{`")));
+                    }
+                    let (start, name, steps) = open.pop().unwrap();
+                    open.last_mut().unwrap().2.push(Step {
+                        line: start,
+                        kind: Kind::Scope {
+                            name,
+                            steps,
+                            end: number,
+                        },
+                    });
+                    continue;
+                }
+                ["let", var, "=", "new", rest @ ..] => {
+                    let (bytes, label) = allocation(rest).map_err(at)?;
+                    Kind::New {
+                        var: var.to_string(),
+                        bytes,
+                        label,
+                    }
+                }
+                ["let", var, "=", "&mut", of] => Kind::Borrow {
+                    var: var.to_string(),
+                    of: of.to_string(),
+                    mutable: true,
+                },
+                ["let", var, "=", of] if of.starts_with('&') => Kind::Borrow {
+                    var: var.to_string(),
+                    of: of[1..].to_string(),
+                    mutable: false,
+                },
+                ["let", var, "=", from] => Kind::Move {
+                    var: var.to_string(),
+                    from: from.to_string(),
+                },
+                [var, "=", "new", rest @ ..] => {
+                    let (bytes, label) = allocation(rest).map_err(at)?;
+                    Kind::Assign {
+                        var: var.to_string(),
+                        bytes,
+                        label,
+                    }
+                }
+                ["grow", var, rest @ ..] => {
+                    let (bytes, label) = allocation(rest).map_err(at)?;
+                    Kind::Grow {
+                        var: var.to_string(),
+                        bytes,
+                        label,
+                    }
+                }
+                ["move", var, "into", owner] => Kind::MoveInto {
+                    var: var.to_string(),
+                    owner: owner.to_string(),
+                },
+                ["work", ticks] => Kind::Work(number_of(ticks).map_err(at)?),
+                _ => return Err(at(format!("don't know what `{}` means", line))),
+            };
+            open.last_mut().unwrap().2.push(Step { line: number, kind });
+        }
+
+        if open.len() > 1 {
+            let (start, _, _) = open.last().unwrap();
+            return Err(format!("line {}: this `{{` is never closed", start));
+        }
+        Ok(Script {
+            steps: open.pop().unwrap().2,
+        })
+    }
+}
+
+/// `<bytes> ["label"]`.
+fn allocation(words: &[&str]) -> Result<(usize, String), String> {
+    match words {
+        [bytes] => Ok((number_of(bytes)?, String::new())),
+        [bytes, label] => Ok((number_of(bytes)?, label.to_string())),
+        _ => Err(String::from("expected a size in bytes, and maybe a label")),
+    }
+}
+
+fn number_of(word: &str) -> Result<usize, String> {
+    word.parse()
+        .map_err(|_| format!("`{}` isn't a number", word))
+}
+
+/// Splits on whitespace, keeping `"quoted strings"` together.
+fn words(text: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut chars = text.trim().chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut word = String::new();
+        if c == '"' {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => word.extend(chars.next()),
+                    Some(c) => word.push(c),
+                    None => return Err(String::from("unterminated string")),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+        }
+        words.push(word);
+    }
+    Ok(words)
+}
@@ -0,0 +1,392 @@
+//! Running a script on a heap.
+//!
+//! Both heaps see the same allocations. They differ in when objects are
+//! freed:
+//!
+//! - With ownership, an object is freed the moment its owner is done with
+//!   it: when the owner goes out of scope, is given a new object, or grows
+//!   into a bigger one. Whatever the object owns is freed with it.
+//! - With mark-and-sweep, nothing is freed until a collection. One runs
+//!   before an allocation that would take the bytes allocated since the last
+//!   one over the threshold. It marks every object that can be reached from
+//!   a variable in scope, then sweeps the heap, freeing the rest. The program
+//!   is paused while it does: a tick for each object marked, and a tick for
+//!   each object swept. Whatever is left when the program ends is never
+//!   freed.
+//!
+//! Every step takes a tick, and `work` takes as many as it says. A variable
+//! that's been moved out of still counts as a reference for the collector,
+//! like a Java variable would, until it goes out of scope.
+
+use crate::script::{Kind, Script, Step};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Ownership,
+    MarkSweep {
+        /// How many bytes can be allocated between collections.
+        threshold: usize,
+    },
+}
+
+/// What happened to the heap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub strategy: Strategy,
+    /// Every object allocated, numbered from 1 in the order they were.
+    pub objects: Vec<Object>,
+    pub pauses: Vec<Pause>,
+    /// The heap after each step, in the order they ran.
+    pub samples: Vec<Sample>,
+    /// The most bytes the heap ever held at once.
+    pub peak: usize,
+    /// How long the program took, pauses included.
+    pub ticks: usize,
+}
+
+impl Report {
+    pub fn longest_pause(&self) -> usize {
+        self.pauses
+            .iter()
+            .map(|pause| pause.length)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn paused(&self) -> usize {
+        self.pauses.iter().map(|pause| pause.length).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object {
+    pub label: String,
+    pub bytes: usize,
+    /// The line of the script that allocated it.
+    pub allocated: usize,
+    /// `None` if it was still in the heap when the program ended.
+    pub freed: Option<Freed>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Freed {
+    pub line: usize,
+    pub tick: usize,
+    pub why: Why,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Why {
+    /// Its owner went out of scope.
+    OutOfScope(String),
+    /// Its owner was given a new object.
+    Replaced(String),
+    /// Its owner grew into a bigger one.
+    Grew(String),
+    /// The object that owned it was freed.
+    Owner(usize),
+    /// A collection found it unreachable. The index is into the pauses.
+    Collected(usize),
+}
+
+/// A collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pause {
+    pub line: usize,
+    /// When it started.
+    pub tick: usize,
+    pub length: usize,
+    pub marked: usize,
+    pub swept: usize,
+    pub freed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub line: usize,
+    /// Bytes in the heap once the step was done.
+    pub bytes: usize,
+    /// The objects freed by the step, as indices into the objects.
+    pub freed: Vec<usize>,
+    /// The collection the step set off, if it set one off.
+    pub pause: Option<usize>,
+}
+
+/// Runs a script. Errors are for scripts Rust wouldn't compile, like using
+/// a variable that's been moved out of.
+pub fn run(script: &Script, strategy: Strategy) -> Result<Report, String> {
+    let mut heap = Heap {
+        strategy,
+        objects: Vec::new(),
+        children: Vec::new(),
+        scopes: vec![Vec::new()],
+        freed: Vec::new(),
+        bytes: 0,
+        since_collection: 0,
+        line: 0,
+        report: Report {
+            strategy,
+            objects: Vec::new(),
+            pauses: Vec::new(),
+            samples: Vec::new(),
+            peak: 0,
+            ticks: 0,
+        },
+    };
+    heap.steps(&script.steps)?;
+    heap.report.objects = heap.objects;
+    Ok(heap.report)
+}
+
+struct Var {
+    name: String,
+    object: usize,
+    owns: bool,
+    moved: bool,
+}
+
+struct Heap {
+    strategy: Strategy,
+    objects: Vec<Object>,
+    /// What each object owns.
+    children: Vec<Vec<usize>>,
+    scopes: Vec<Vec<Var>>,
+    /// The objects freed so far, in order.
+    freed: Vec<usize>,
+    bytes: usize,
+    since_collection: usize,
+    /// The line of the step running.
+    line: usize,
+    report: Report,
+}
+
+impl Heap {
+    fn steps(&mut self, steps: &[Step]) -> Result<(), String> {
+        for step in steps {
+            match step.kind {
+                // Errors in a scope already say which line they're on.
+                Kind::Scope { .. } => self.step(step)?,
+                _ => self
+                    .step(step)
+                    .map_err(|error| format!("line {}: {}", step.line, error))?,
+            }
+        }
+        Ok(())
+    }
+
+    fn step(&mut self, step: &Step) -> Result<(), String> {
+        self.line = step.line;
+        let freed = self.freed.len();
+        let pauses = self.report.pauses.len();
+        self.report.ticks += 1;
+
+        match &step.kind {
+            Kind::Scope { steps, end, .. } => {
+                self.sample(freed, pauses);
+                self.scopes.push(Vec::new());
+                self.steps(steps)?;
+                self.line = *end;
+                let freed = self.freed.len();
+                let pauses = self.report.pauses.len();
+                self.report.ticks += 1;
+                let scope = self.scopes.pop().unwrap();
+                for var in scope.iter().rev() {
+                    if var.owns && !var.moved {
+                        self.free(var.object, Why::OutOfScope(var.name.clone()));
+                    }
+                }
+                self.sample(freed, pauses);
+                return Ok(());
+            }
+            Kind::New { var, bytes, label } => {
+                let object = self.alloc(*bytes, label);
+                self.declare(var, object, true);
+            }
+            Kind::Borrow { var, of, .. } => {
+                let object = self.lookup(of)?.object;
+                self.declare(var, object, false);
+            }
+            Kind::Move { var, from } => {
+                let from = self.lookup_mut(from)?;
+                let (object, owns) = (from.object, from.owns);
+                if owns {
+                    from.moved = true;
+                }
+                self.declare(var, object, owns);
+            }
+            Kind::Assign { var, bytes, label } => {
+                // Checked before allocating, so a typo doesn't allocate.
+                self.lookup_any(var)?;
+                let object = self.alloc(*bytes, label);
+                let var = self.lookup_any(var)?;
+                let old = (var.owns && !var.moved).then_some(var.object);
+                var.object = object;
+                var.owns = true;
+                var.moved = false;
+                let name = var.name.clone();
+                if let Some(old) = old {
+                    self.free(old, Why::Replaced(name));
+                }
+            }
+            Kind::Grow { var, bytes, label } => {
+                let old = self.lookup(var)?.object;
+                let object = self.alloc(*bytes, label);
+                self.children[object] = std::mem::take(&mut self.children[old]);
+                // Everything that referred to the old object refers to the
+                // new one.
+                for var in self.scopes.iter_mut().flatten() {
+                    if var.object == old {
+                        var.object = object;
+                    }
+                }
+                for children in &mut self.children {
+                    for child in children.iter_mut() {
+                        if *child == old {
+                            *child = object;
+                        }
+                    }
+                }
+                self.free(old, Why::Grew(var.clone()));
+            }
+            Kind::MoveInto { var, owner } => {
+                let owner = self.lookup(owner)?.object;
+                let moved = self.lookup_mut(var)?;
+                if !moved.owns {
+                    return Err(format!("`{}` doesn't own anything to move", moved.name));
+                }
+                moved.moved = true;
+                let object = moved.object;
+                self.children[owner].push(object);
+            }
+            // The step's own tick counts as one of them.
+            Kind::Work(ticks) => self.report.ticks += ticks.saturating_sub(1),
+        }
+        self.sample(freed, pauses);
+        Ok(())
+    }
+
+    /// Records the heap after a step, which started with `freed` objects
+    /// freed and `pauses` collections done.
+    fn sample(&mut self, freed: usize, pauses: usize) {
+        self.report.samples.push(Sample {
+            line: self.line,
+            bytes: self.bytes,
+            freed: self.freed[freed..].to_vec(),
+            pause: (self.report.pauses.len() > pauses).then_some(pauses),
+        });
+    }
+
+    fn declare(&mut self, name: &str, object: usize, owns: bool) {
+        self.scopes.last_mut().unwrap().push(Var {
+            name: name.to_string(),
+            object,
+            owns,
+            moved: false,
+        });
+    }
+
+    /// A variable in scope, including one that's been moved out of.
+    fn lookup_any(&mut self, name: &str) -> Result<&mut Var, String> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|var| var.name == name)
+            .ok_or_else(|| format!("there's no `{}` in scope", name))
+    }
+
+    /// A variable that can be used.
+    fn lookup_mut(&mut self, name: &str) -> Result<&mut Var, String> {
+        let var = self.lookup_any(name)?;
+        if var.moved {
+            return Err(format!("`{}` has been moved out of", name));
+        }
+        Ok(var)
+    }
+
+    fn lookup(&mut self, name: &str) -> Result<&Var, String> {
+        self.lookup_mut(name).map(|var| &*var)
+    }
+
+    fn alloc(&mut self, bytes: usize, label: &str) -> usize {
+        if let Strategy::MarkSweep { threshold } = self.strategy {
+            if self.since_collection > 0 && self.since_collection + bytes > threshold {
+                self.collect();
+            }
+        }
+        self.objects.push(Object {
+            label: label.to_string(),
+            bytes,
+            allocated: self.line,
+            freed: None,
+        });
+        self.children.push(Vec::new());
+        self.bytes += bytes;
+        self.since_collection += bytes;
+        self.report.peak = self.report.peak.max(self.bytes);
+        self.objects.len() - 1
+    }
+
+    /// Frees an object when its owner is done with it, along with everything
+    /// it owns. The collector doesn't free anything this way.
+    fn free(&mut self, object: usize, why: Why) {
+        if self.strategy != Strategy::Ownership || self.objects[object].freed.is_some() {
+            return;
+        }
+        self.release(object, why);
+        for child in self.children[object].clone() {
+            self.free(child, Why::Owner(object));
+        }
+    }
+
+    fn release(&mut self, object: usize, why: Why) {
+        self.bytes -= self.objects[object].bytes;
+        self.freed.push(object);
+        self.objects[object].freed = Some(Freed {
+            line: self.line,
+            tick: self.report.ticks,
+            why,
+        });
+    }
+
+    fn collect(&mut self) {
+        let index = self.report.pauses.len();
+        let tick = self.report.ticks;
+
+        let mut marked = vec![false; self.objects.len()];
+        let mut stack: Vec<usize> = self.scopes.iter().flatten().map(|var| var.object).collect();
+        while let Some(object) = stack.pop() {
+            if marked[object] {
+                continue;
+            }
+            marked[object] = true;
+            stack.extend(&self.children[object]);
+        }
+
+        let mut swept = 0;
+        let mut freed = 0;
+        for (object, marked) in marked.iter().enumerate() {
+            if self.objects[object].freed.is_some() {
+                continue;
+            }
+            swept += 1;
+            if !marked {
+                freed += self.objects[object].bytes;
+                self.release(object, Why::Collected(index));
+            }
+        }
+
+        let marked = marked.iter().filter(|marked| **marked).count();
+        let length = marked + swept;
+        self.report.ticks += length;
+        self.since_collection = 0;
+        self.report.pauses.push(Pause {
+            line: self.line,
+            tick,
+            length,
+            marked,
+            swept,
+            freed,
+        });
+    }
+}
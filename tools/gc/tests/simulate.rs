@@ -0,0 +1,148 @@
+//! Runs scripts on both heaps.
+
+use gc::script::Script;
+use gc::simulate::{self, Report, Strategy, Why};
+
+fn run(source: &str, strategy: Strategy) -> Report {
+    simulate::run(&Script::parse(source).unwrap(), strategy).unwrap()
+}
+
+const TEMPORARIES: &str = "\
+fn main {
+    let name = new 4 \"Gabe\"
+    {
+        let greeting = new 12
+    }
+    {
+        let greeting = new 12
+    }
+    work 5
+}
+";
+
+#[test]
+fn ownership_frees_at_the_end_of_the_scope() {
+    let report = run(TEMPORARIES, Strategy::Ownership);
+    let greeting = report.objects[1].freed.as_ref().unwrap();
+    assert_eq!(greeting.line, 5);
+    assert_eq!(greeting.why, Why::OutOfScope(String::from("greeting")));
+    let name = report.objects[0].freed.as_ref().unwrap();
+    assert_eq!(name.line, 10);
+    assert!(report.pauses.is_empty());
+    assert_eq!(report.peak, 16);
+    assert_eq!(report.samples.last().unwrap().bytes, 0);
+}
+
+#[test]
+fn the_collector_frees_only_when_it_runs() {
+    let report = run(TEMPORARIES, Strategy::MarkSweep { threshold: 16 });
+    assert_eq!(report.pauses.len(), 1);
+    let pause = &report.pauses[0];
+    assert_eq!(pause.line, 7);
+    assert_eq!((pause.marked, pause.swept, pause.freed), (1, 2, 12));
+    assert_eq!(pause.length, 3);
+
+    let greeting = report.objects[1].freed.as_ref().unwrap();
+    assert_eq!(greeting.line, 7);
+    assert_eq!(greeting.why, Why::Collected(0));
+    // Neither the second greeting nor `name` is ever collected.
+    assert!(report.objects[0].freed.is_none());
+    assert!(report.objects[2].freed.is_none());
+    assert_eq!(report.samples.last().unwrap().bytes, 16);
+}
+
+#[test]
+fn a_lower_threshold_pauses_more_often() {
+    let often = run(TEMPORARIES, Strategy::MarkSweep { threshold: 4 });
+    let rarely = run(TEMPORARIES, Strategy::MarkSweep { threshold: 64 });
+    assert_eq!(often.pauses.len(), 2);
+    assert!(rarely.pauses.is_empty());
+    assert_eq!(rarely.peak, 28);
+    assert_eq!(
+        often.ticks,
+        run(TEMPORARIES, Strategy::Ownership).ticks + often.paused()
+    );
+}
+
+#[test]
+fn what_an_object_owns_is_freed_with_it() {
+    let source = "\
+let names = new 8
+{
+    let name = new 4
+    move name into names
+}
+names = new 16
+";
+    let owned = run(source, Strategy::Ownership);
+    let name = owned.objects[1].freed.as_ref().unwrap();
+    assert_eq!(name.line, 6);
+    assert_eq!(name.why, Why::Owner(0));
+    assert_eq!(
+        owned.objects[0].freed.as_ref().unwrap().why,
+        Why::Replaced(String::from("names"))
+    );
+
+    // The collector still finds `name` through `names`, after its variable
+    // is gone.
+    let collected = run(source, Strategy::MarkSweep { threshold: 12 });
+    assert_eq!(collected.pauses[0].line, 6);
+    assert_eq!(collected.pauses[0].freed, 0);
+}
+
+#[test]
+fn growing_frees_the_old_buffer() {
+    let source = "\
+let name = new 4
+{
+    let name_mut = &mut name
+    grow name_mut 9
+}
+";
+    let report = run(source, Strategy::Ownership);
+    assert_eq!(report.peak, 13);
+    assert_eq!(
+        report.objects[0].freed.as_ref().unwrap().why,
+        Why::Grew(String::from("name_mut"))
+    );
+}
+
+#[test]
+fn scripts_rust_would_reject_are_errors() {
+    let moved = Script::parse("let a = new 4\n{\n    let b = a\n    let c = &a\n}\n").unwrap();
+    assert_eq!(
+        simulate::run(&moved, Strategy::Ownership).unwrap_err(),
+        "line 4: `a` has been moved out of"
+    );
+    let missing = Script::parse("grow name 8\n").unwrap();
+    assert_eq!(
+        simulate::run(&missing, Strategy::Ownership).unwrap_err(),
+        "line 1: there's no `name` in scope"
+    );
+}
+
+#[test]
+fn parse_errors_say_which_line() {
+    assert_eq!(
+        Script::parse("{\n    let a = new lots\n}\n").unwrap_err(),
+        "line 2: `lots` isn't a number"
+    );
+    assert_eq!(
+        Script::parse("fn main {\n    work 1\n").unwrap_err(),
+        "line 1: this `{` is never closed"
+    );
+    assert_eq!(
+        Script::parse("}\n").unwrap_err(),
+        "line 1: `}` without a `{`"
+    );
+}
+
+#[test]
+fn the_comparison_covers_every_object() {
+    let source = include_str!("../scripts/borrowing.gc");
+    let comparison = gc::compare(source, gc::THRESHOLD).unwrap();
+    assert!(comparison.contains("#3 \"Gabe Lovea\", 18 bytes"));
+    assert!(comparison.contains("when `name` went out of scope"));
+    assert!(comparison.contains("by collection 1"));
+    assert!(comparison.contains("peak memory"));
+}
@@ -0,0 +1,70 @@
+//! The syntax tree of an expression.
+
+use mini::diagnostic::Span;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprKind {
+    Int(i64),
+    Str(String),
+    /// `()`.
+    Unit,
+    /// A variable, or a path like `Vec::new`.
+    Var(String),
+    /// `|first, second| first + second`.
+    Lambda {
+        params: Vec<(String, Span)>,
+        body: Box<Expr>,
+    },
+    /// `add(x, y)`. `x + y` is a call to `+`.
+    Call {
+        function: Box<Expr>,
+        args: Vec<Expr>,
+    },
+    /// `v.push(5)`, which calls `Vec::push(v, 5)`.
+    Method {
+        receiver: Box<Expr>,
+        method: String,
+        args: Vec<Expr>,
+    },
+    /// `let name = value; body`. A statement like `v.push(5);` is a `let`
+    /// named `_`.
+    Let {
+        name: String,
+        name_span: Span,
+        annotation: Option<Annotation>,
+        value: Box<Expr>,
+        body: Box<Expr>,
+    },
+}
+
+impl ExprKind {
+    /// Whether it's a value, which is all a `let` generalizes. Calls aren't,
+    /// so `let v = Vec::new();` gives `v` one type rather than a type for
+    /// every use. (This is ML's value restriction, and Rust never
+    /// generalizes a `let` at all.)
+    pub fn is_value(&self) -> bool {
+        matches!(
+            self,
+            ExprKind::Int(_)
+                | ExprKind::Str(_)
+                | ExprKind::Unit
+                | ExprKind::Var(_)
+                | ExprKind::Lambda { .. }
+        )
+    }
+}
+
+/// A type written after a `let`'s name, like `Vec<i32>`. `_` leaves a part
+/// for inference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Annotation {
+    Hole,
+    /// `i32`, `&str`, `String`, `()` or `Vec<T>`.
+    Named(String, Vec<Annotation>),
+}
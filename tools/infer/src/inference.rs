@@ -0,0 +1,632 @@
+//! Algorithm W, recording each step it takes.
+//!
+//! Inferring the type of an expression gives a type and a substitution: what
+//! inferring it worked out about the type variables that were around before.
+//!
+//! - A literal has its type, and works nothing out.
+//! - A variable's type scheme is *instantiated*: each of its generic
+//!   variables is replaced by a fresh one, so `Vec::new` is
+//!   `fn() -> Vec<?1>` in one place and `fn() -> Vec<?2>` in another.
+//! - A lambda gets a fresh variable for each parameter, and the type of its
+//!   body.
+//! - A call infers the function and its arguments, then *unifies* the
+//!   function's type with `fn(arguments) -> ?r` for a fresh `?r`. Unifying
+//!   two types finds the substitution that makes them the same, solving one
+//!   variable at a time. If there isn't one, the types are mismatched.
+//! - A `let` infers its value, then *generalizes* it: the variables in its
+//!   type that nothing else in scope mentions become generic, so `let id =
+//!   |x| x;` can be used on an `i32` and a `&str`. Only values are
+//!   generalized, though; `let v = Vec::new();` leaves `v` a `Vec<?1>` until
+//!   something says what `?1` is.
+//!
+//! Each substitution is applied to everything that came before it, and
+//! composed with the ones before it, so the substitution at the end solves
+//! every variable that could be solved. A `let` whose type still has a
+//! variable in it then is ambiguous, which is rustc's E0282.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use mini::diagnostic::{Diagnostic, Span};
+
+use crate::ast::{Annotation, Expr, ExprKind};
+use crate::types::{free_in, Scheme, Subst, Type};
+
+/// What's in scope, and the types it has.
+type Env = BTreeMap<String, Scheme>;
+
+/// Names for generic variables, in order.
+const NAMES: &[&str] = &["T", "U", "V", "W"];
+
+/// How inference went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inference {
+    pub steps: Vec<Step>,
+    /// Every variable that was solved, and what as. It's empty if there was
+    /// an error.
+    pub substitution: Subst,
+    pub result: Result<Type, Diagnostic>,
+}
+
+impl Inference {
+    /// Every step, under the lines of code they're about.
+    pub fn trace(&self, source: &str) -> String {
+        let mut previous = None;
+        let mut text = String::new();
+        for step in &self.steps {
+            text.push_str(&step.render(source, previous));
+            previous = Some(step);
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub span: Span,
+    /// How deep in the expression it happened.
+    pub depth: usize,
+    pub kind: StepKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepKind {
+    /// The type of a literal, or of a variable that isn't generic.
+    Typed(Type),
+    /// A generic variable given fresh type variables.
+    Instantiate {
+        name: String,
+        scheme: Scheme,
+        ty: Type,
+    },
+    /// Two types that have to be the same.
+    Unify { left: Type, right: Type },
+    /// A variable solved, adding to the substitution.
+    Bind { var: u32, ty: Type },
+    /// A `let` made generic over the variables only it mentions.
+    Generalize { name: String, scheme: Scheme },
+    /// A `let` that isn't generalized, since its value isn't a value.
+    Monomorphic { name: String, ty: Type },
+}
+
+impl Step {
+    /// What happened, quoting the code for `Typed` steps.
+    pub fn describe(&self, source: &str) -> String {
+        match &self.kind {
+            StepKind::Typed(ty) => format!("`{}` : {}", snippet(source, self.span), ty),
+            StepKind::Instantiate { name, scheme, ty } => {
+                format!("`{}` : {}, so {} here", name, scheme, ty)
+            }
+            StepKind::Unify { left, right } => format!("unify {} with {}", left, right),
+            StepKind::Bind { var, ty } => format!("?{} := {}", var, ty),
+            StepKind::Generalize { name, scheme } if scheme.vars.is_empty() => {
+                format!("let `{}` : {}", name, scheme)
+            }
+            StepKind::Generalize { name, scheme } => {
+                format!("let `{}` : {}, generalized", name, scheme)
+            }
+            StepKind::Monomorphic { name, ty } => format!(
+                "let `{}` : {}, not generalized, since only values are",
+                name, ty
+            ),
+        }
+    }
+
+    /// The step as part of a trace, like [`mini`]'s interpreter prints them.
+    pub fn render(&self, source: &str, previous: Option<&Step>) -> String {
+        let (line, _) = self.span.position(source);
+        let mut text = String::new();
+        if previous.map(|previous| previous.span.position(source).0) != Some(line) {
+            let code = source.lines().nth(line - 1).unwrap_or("").trim();
+            text.push_str(&format!("{:>4} | {}\n", line, code));
+        }
+        text.push_str(&format!(
+            "     | {}{}\n",
+            "  ".repeat(self.depth),
+            self.describe(source)
+        ));
+        text
+    }
+}
+
+/// The code a span covers, on one line and cut short if it's long.
+fn snippet(source: &str, span: Span) -> String {
+    let code = source.get(span.start..span.end).unwrap_or("");
+    let code = code.split_whitespace().collect::<Vec<_>>().join(" ");
+    if code.chars().count() > 32 {
+        format!("{}...", code.chars().take(29).collect::<String>())
+    } else {
+        code
+    }
+}
+
+/// The functions every expression can use.
+pub fn builtins() -> Env {
+    let t = Type::Var(0);
+    let generic = |ty| Scheme {
+        vars: vec![(0, String::from("T"))],
+        ty,
+    };
+    let mut env = Env::new();
+    env.insert(
+        String::from("Vec::new"),
+        generic(Type::function(Vec::new(), Type::vec(t.clone()))),
+    );
+    env.insert(
+        String::from("Vec::push"),
+        generic(Type::function(
+            vec![Type::vec(t.clone()), t.clone()],
+            Type::con("()"),
+        )),
+    );
+    env.insert(
+        String::from("Vec::len"),
+        generic(Type::function(vec![Type::vec(t)], Type::con("i32"))),
+    );
+    env.insert(
+        String::from("String::from"),
+        Scheme::mono(Type::function(vec![Type::con("&str")], Type::con("String"))),
+    );
+    env.insert(
+        String::from("+"),
+        Scheme::mono(Type::function(
+            vec![Type::con("i32"), Type::con("i32")],
+            Type::con("i32"),
+        )),
+    );
+    env
+}
+
+/// Infers the type of an expression, with [`builtins`] in scope.
+pub fn infer(expr: &Expr) -> Inference {
+    let mut inferrer = Inferrer {
+        // Builtins use `?0` for their generic variable.
+        next: 1,
+        depth: 0,
+        steps: Vec::new(),
+        origins: HashMap::new(),
+        generalized: BTreeSet::new(),
+        monomorphic: Vec::new(),
+    };
+    let result = inferrer
+        .w(&builtins(), expr)
+        .and_then(|(subst, ty)| inferrer.finish(expr, subst, ty));
+    let (substitution, result) = match result {
+        Ok((subst, ty)) => (subst, Ok(ty)),
+        Err(error) => (Subst::default(), Err(error)),
+    };
+    Inference {
+        steps: inferrer.steps,
+        substitution,
+        result,
+    }
+}
+
+/// Why two types couldn't be unified.
+enum Mismatch {
+    Types,
+    /// A variable would have to contain itself, like `?1 := Vec<?1>`.
+    Cyclic,
+}
+
+struct Inferrer {
+    next: u32,
+    depth: usize,
+    steps: Vec<Step>,
+    /// The generic variable each fresh one was made for, like `T` for
+    /// `Vec::new`'s, so errors can say which one is ambiguous.
+    origins: HashMap<u32, String>,
+    /// Every variable that some `let` is generic over.
+    generalized: BTreeSet<u32>,
+    /// The `let`s that weren't generalized, to check for ambiguous types at
+    /// the end.
+    monomorphic: Vec<(String, Span, Type)>,
+}
+
+impl Inferrer {
+    fn fresh(&mut self) -> Type {
+        self.next += 1;
+        Type::Var(self.next - 1)
+    }
+
+    fn step(&mut self, span: Span, kind: StepKind) {
+        self.steps.push(Step {
+            span,
+            depth: self.depth,
+            kind,
+        });
+    }
+
+    fn w(&mut self, env: &Env, expr: &Expr) -> Result<(Subst, Type), Diagnostic> {
+        self.depth += 1;
+        let inferred = self.infer(env, expr)?;
+        self.depth -= 1;
+        Ok(inferred)
+    }
+
+    fn infer(&mut self, env: &Env, expr: &Expr) -> Result<(Subst, Type), Diagnostic> {
+        match &expr.kind {
+            ExprKind::Int(_) => Ok(self.literal(expr.span, "i32")),
+            ExprKind::Str(_) => Ok(self.literal(expr.span, "&str")),
+            ExprKind::Unit => Ok(self.literal(expr.span, "()")),
+            ExprKind::Var(name) => {
+                let scheme = env.get(name).ok_or_else(|| {
+                    Diagnostic::error(
+                        Some("E0425"),
+                        format!("cannot find value `{}` in this scope", name),
+                        expr.span,
+                        "not found in this scope",
+                    )
+                })?;
+                Ok((Subst::default(), self.instantiate(name, scheme, expr.span)))
+            }
+            ExprKind::Lambda { params, body } => {
+                let mut inner = env.clone();
+                let mut types = Vec::new();
+                for (name, _) in params {
+                    let ty = self.fresh();
+                    inner.insert(name.clone(), Scheme::mono(ty.clone()));
+                    types.push(ty);
+                }
+                let (subst, returns) = self.w(&inner, body)?;
+                let params = types.iter().map(|ty| subst.apply(ty)).collect();
+                let ty = Type::function(params, returns);
+                self.step(expr.span, StepKind::Typed(ty.clone()));
+                Ok((subst, ty))
+            }
+            ExprKind::Call { function, args } => {
+                let (subst, ty) = self.w(env, function)?;
+                let args: Vec<&Expr> = args.iter().collect();
+                self.call(env, subst, ty, function.span, &args, expr.span)
+            }
+            ExprKind::Method {
+                receiver,
+                method,
+                args,
+            } => {
+                let name = format!("Vec::{}", method);
+                let scheme = env.get(&name).ok_or_else(|| {
+                    Diagnostic::error(
+                        Some("E0599"),
+                        format!("no method named `{}` found", method),
+                        expr.span,
+                        "method not found",
+                    )
+                })?;
+                let ty = self.instantiate(&name, scheme, expr.span);
+                let args: Vec<&Expr> = Some(&**receiver).into_iter().chain(args).collect();
+                self.call(env, Subst::default(), ty, expr.span, &args, expr.span)
+            }
+            ExprKind::Let {
+                name,
+                name_span,
+                annotation,
+                value,
+                body,
+            } => {
+                let (mut subst, mut ty) = self.w(env, value)?;
+                if let Some(annotation) = annotation {
+                    let annotated = self.annotation(annotation);
+                    self.step(
+                        *name_span,
+                        StepKind::Unify {
+                            left: annotated.clone(),
+                            right: ty.clone(),
+                        },
+                    );
+                    let solved = self
+                        .unify(&annotated, &ty, *name_span)
+                        .map_err(|mismatch| mismatched(mismatch, &annotated, &ty, value.span))?;
+                    subst = solved.compose(&subst);
+                    ty = subst.apply(&ty);
+                }
+
+                let env = apply_env(&subst, env);
+                let mut inner = env.clone();
+                if name == "_" {
+                    // A statement, which is only checked for ambiguity.
+                    self.monomorphic.push((name.clone(), value.span, ty));
+                } else if value.kind.is_value() {
+                    let scheme = self.generalize(&env, &ty);
+                    self.step(
+                        *name_span,
+                        StepKind::Generalize {
+                            name: name.clone(),
+                            scheme: scheme.clone(),
+                        },
+                    );
+                    inner.insert(name.clone(), scheme);
+                } else {
+                    self.step(
+                        *name_span,
+                        StepKind::Monomorphic {
+                            name: name.clone(),
+                            ty: ty.clone(),
+                        },
+                    );
+                    self.monomorphic
+                        .push((name.clone(), *name_span, ty.clone()));
+                    inner.insert(name.clone(), Scheme::mono(ty));
+                }
+
+                // The rest of the block, which isn't any deeper.
+                let (after, ty) = self.infer(&inner, body)?;
+                Ok((after.compose(&subst), ty))
+            }
+        }
+    }
+
+    fn literal(&mut self, span: Span, name: &str) -> (Subst, Type) {
+        let ty = Type::con(name);
+        self.step(span, StepKind::Typed(ty.clone()));
+        (Subst::default(), ty)
+    }
+
+    fn instantiate(&mut self, name: &str, scheme: &Scheme, span: Span) -> Type {
+        if scheme.vars.is_empty() {
+            self.step(span, StepKind::Typed(scheme.ty.clone()));
+            return scheme.ty.clone();
+        }
+        let mut fresh = Subst::default();
+        for (var, generic) in &scheme.vars {
+            let ty = self.fresh();
+            if let Type::Var(id) = ty {
+                self.origins.insert(id, generic.clone());
+            }
+            fresh.0.insert(*var, ty);
+        }
+        let ty = fresh.apply(&scheme.ty);
+        self.step(
+            span,
+            StepKind::Instantiate {
+                name: name.to_string(),
+                scheme: scheme.clone(),
+                ty: ty.clone(),
+            },
+        );
+        ty
+    }
+
+    /// Infers the arguments, then unifies the function's type with the
+    /// call's.
+    fn call(
+        &mut self,
+        env: &Env,
+        mut subst: Subst,
+        function: Type,
+        callee: Span,
+        args: &[&Expr],
+        span: Span,
+    ) -> Result<(Subst, Type), Diagnostic> {
+        let mut types = Vec::new();
+        for arg in args {
+            let (solved, ty) = self.w(&apply_env(&subst, env), arg)?;
+            subst = solved.compose(&subst);
+            types.push(ty);
+        }
+        let types: Vec<Type> = types.iter().map(|ty| subst.apply(ty)).collect();
+        let function = subst.apply(&function);
+        let result = self.fresh();
+        let call = Type::function(types.clone(), result.clone());
+        self.step(
+            span,
+            StepKind::Unify {
+                left: function.clone(),
+                right: call.clone(),
+            },
+        );
+
+        match &function {
+            Type::Fn(params, _) if params.len() != args.len() => {
+                let plural = |count: usize| if count == 1 { "" } else { "s" };
+                return Err(Diagnostic::error(
+                    Some("E0061"),
+                    format!(
+                        "this function takes {} argument{} but {} argument{} were supplied",
+                        params.len(),
+                        plural(params.len()),
+                        args.len(),
+                        plural(args.len())
+                    ),
+                    span,
+                    format!("expected {} argument{}", params.len(), plural(params.len())),
+                ));
+            }
+            Type::Fn(params, returns) => {
+                // One argument at a time, so a mismatch points at the
+                // argument that's wrong.
+                for ((param, ty), arg) in params.iter().zip(&types).zip(args) {
+                    let (param, ty) = (subst.apply(param), subst.apply(ty));
+                    let solved = self
+                        .unify(&param, &ty, span)
+                        .map_err(|mismatch| mismatched(mismatch, &param, &ty, arg.span))?;
+                    subst = solved.compose(&subst);
+                }
+                let returns = subst.apply(returns);
+                let solved = self
+                    .unify(&result, &returns, span)
+                    .map_err(|mismatch| mismatched(mismatch, &returns, &result, span))?;
+                subst = solved.compose(&subst);
+            }
+            Type::Var(_) => {
+                let solved = self
+                    .unify(&function, &call, span)
+                    .map_err(|mismatch| mismatched(mismatch, &function, &call, span))?;
+                subst = solved.compose(&subst);
+            }
+            Type::Con(..) => {
+                return Err(Diagnostic::error(
+                    Some("E0618"),
+                    format!("expected function, found `{}`", hide_vars(&function)),
+                    callee,
+                    "call expression requires function",
+                ));
+            }
+        }
+
+        let ty = subst.apply(&result);
+        self.step(span, StepKind::Typed(ty.clone()));
+        Ok((subst, ty))
+    }
+
+    /// The substitution that makes two types the same.
+    fn unify(&mut self, left: &Type, right: &Type, span: Span) -> Result<Subst, Mismatch> {
+        match (left, right) {
+            (Type::Var(a), Type::Var(b)) if a == b => Ok(Subst::default()),
+            (Type::Var(a), Type::Var(b)) => {
+                // Keep the variable that came from a generic one, so errors
+                // can name it.
+                if self.origins.contains_key(a) && !self.origins.contains_key(b) {
+                    Ok(self.bind(*b, left.clone(), span))
+                } else {
+                    Ok(self.bind(*a, right.clone(), span))
+                }
+            }
+            (Type::Var(var), ty) | (ty, Type::Var(var)) => {
+                if ty.contains(*var) {
+                    Err(Mismatch::Cyclic)
+                } else {
+                    Ok(self.bind(*var, ty.clone(), span))
+                }
+            }
+            (Type::Con(a, left), Type::Con(b, right)) if a == b && left.len() == right.len() => {
+                self.unify_all(left, right, span)
+            }
+            (Type::Fn(left, a), Type::Fn(right, b)) if left.len() == right.len() => {
+                let left: Vec<Type> = left.iter().chain(Some(&**a)).cloned().collect();
+                let right: Vec<Type> = right.iter().chain(Some(&**b)).cloned().collect();
+                self.unify_all(&left, &right, span)
+            }
+            _ => Err(Mismatch::Types),
+        }
+    }
+
+    fn unify_all(&mut self, left: &[Type], right: &[Type], span: Span) -> Result<Subst, Mismatch> {
+        let mut subst = Subst::default();
+        for (left, right) in left.iter().zip(right) {
+            let solved = self.unify(&subst.apply(left), &subst.apply(right), span)?;
+            subst = solved.compose(&subst);
+        }
+        Ok(subst)
+    }
+
+    fn bind(&mut self, var: u32, ty: Type, span: Span) -> Subst {
+        self.depth += 1;
+        self.step(
+            span,
+            StepKind::Bind {
+                var,
+                ty: ty.clone(),
+            },
+        );
+        self.depth -= 1;
+        Subst::single(var, ty)
+    }
+
+    fn generalize(&mut self, env: &Env, ty: &Type) -> Scheme {
+        let fixed = free_in(env.values());
+        let vars: Vec<(u32, String)> = ty
+            .vars()
+            .into_iter()
+            .filter(|var| !fixed.contains(var))
+            .enumerate()
+            .map(|(index, var)| {
+                let name = NAMES
+                    .get(index)
+                    .map_or(format!("T{}", index), |name| name.to_string());
+                (var, name)
+            })
+            .collect();
+        self.generalized.extend(vars.iter().map(|(var, _)| *var));
+        Scheme {
+            vars,
+            ty: ty.clone(),
+        }
+    }
+
+    fn annotation(&mut self, annotation: &Annotation) -> Type {
+        match annotation {
+            Annotation::Hole => self.fresh(),
+            Annotation::Named(name, args) => Type::Con(
+                name.clone(),
+                args.iter().map(|arg| self.annotation(arg)).collect(),
+            ),
+        }
+    }
+
+    /// Checks that nothing was left ambiguous, with the final substitution.
+    fn finish(&self, expr: &Expr, subst: Subst, ty: Type) -> Result<(Subst, Type), Diagnostic> {
+        for (name, span, bound) in &self.monomorphic {
+            self.check_ambiguous(name, *span, &subst.apply(bound))?;
+        }
+        let ty = subst.apply(&ty);
+        let mut tail = expr;
+        while let ExprKind::Let { body, .. } = &tail.kind {
+            tail = body;
+        }
+        self.check_ambiguous("_", tail.span, &ty)?;
+        Ok((subst, ty))
+    }
+
+    fn check_ambiguous(&self, name: &str, span: Span, ty: &Type) -> Result<(), Diagnostic> {
+        let var = match ty
+            .vars()
+            .into_iter()
+            .find(|var| !self.generalized.contains(var))
+        {
+            Some(var) => var,
+            None => return Ok(()),
+        };
+        let generic = self.origins.get(&var).map_or("_", String::as_str);
+        let shown = ty.show(&|var| {
+            self.origins
+                .get(&var)
+                .cloned()
+                .unwrap_or_else(|| "_".into())
+        });
+        let label = match self.origins.get(&var) {
+            Some(generic) => format!("cannot infer type for `{}`", generic),
+            None => String::from("cannot infer type"),
+        };
+        let error = Diagnostic::error(Some("E0282"), "type annotations needed", span, label);
+        if name == "_" {
+            return Err(error.with_note(format!(
+                "ambiguous type: this is a `{}`, and nothing says what `{}` is",
+                shown, generic
+            )));
+        }
+        let example = ty.show(&|_| String::from("i32"));
+        Err(error
+            .with_note(format!(
+                "ambiguous type: `{}` is a `{}`, and nothing says what `{}` is",
+                name, shown, generic
+            ))
+            .with_note(format!(
+                "give `{}` a type, like `let {}: {} = ...`",
+                name, name, example
+            )))
+    }
+}
+
+fn apply_env(subst: &Subst, env: &Env) -> Env {
+    env.iter()
+        .map(|(name, scheme)| (name.clone(), subst.apply_scheme(scheme)))
+        .collect()
+}
+
+/// A type in an error message, where unsolved variables are `_`, as rustc
+/// writes them.
+fn hide_vars(ty: &Type) -> String {
+    ty.show(&|_| String::from("_"))
+}
+
+fn mismatched(mismatch: Mismatch, expected: &Type, found: &Type, span: Span) -> Diagnostic {
+    let label = match mismatch {
+        Mismatch::Types => format!(
+            "expected `{}`, found `{}`",
+            hide_vars(expected),
+            hide_vars(found)
+        ),
+        Mismatch::Cyclic => String::from("cyclic type of infinite size"),
+    };
+    Diagnostic::error(Some("E0308"), "mismatched types", span, label)
+}
@@ -0,0 +1,37 @@
+//! infer: Hindley-Milner type inference, the way `variables_and_functions`
+//! says Rust figures out the types you leave out.
+//!
+//! ```text
+//! let v = Vec::new();
+//! v.push(5);
+//! let add = |first, second| first + second;
+//! add(v.len(), 6)
+//! ```
+//!
+//! Expressions have `let`s, lambdas (`|x| x`), calls, `+`, integers,
+//! strings, and a generic `Vec` with `Vec::new`, `push` and `len`. A `let`
+//! can say its type, like `let v: Vec<i32> = Vec::new();`, with `_` for the
+//! parts to infer. The [`inference`] module is Algorithm W, which records
+//! every substitution it makes, and reports a `Vec::new()` that nothing
+//! says the element type of as ambiguous, like rustc's E0282. Example
+//! programs are in `programs/`.
+
+pub mod ast;
+pub mod inference;
+pub mod parser;
+pub mod types;
+
+use crate::inference::Inference;
+use crate::types::Subst;
+
+/// Parses an expression and infers its type.
+pub fn infer(source: &str) -> Inference {
+    match parser::parse(source) {
+        Ok(expr) => inference::infer(&expr),
+        Err(error) => Inference {
+            steps: Vec::new(),
+            substitution: Subst::default(),
+            result: Err(error),
+        },
+    }
+}
@@ -0,0 +1,78 @@
+//! `infer` shows Hindley-Milner type inference at work.
+
+use std::env;
+use std::fs;
+use std::process;
+
+const USAGE: &str = "\
+usage: infer <command>
+
+commands:
+    type <file>...
+                  infer the type of each expression
+    steps <file>  show every step Algorithm W takes: each unification, the
+                  substitution it makes, and each let it generalizes";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match infer(&args) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("infer: {}", error);
+            process::exit(2);
+        }
+    }
+}
+
+/// Runs a command, returning whether every expression had a type.
+fn infer(args: &[&str]) -> Result<bool, String> {
+    match args {
+        ["type", files @ ..] if !files.is_empty() => {
+            let mut all_ok = true;
+            for file in files {
+                let source = read(file)?;
+                match infer::infer(&source).result {
+                    Ok(ty) => println!("{}: {}", file, ty),
+                    Err(error) => {
+                        println!("{}", error.render(file, &source));
+                        all_ok = false;
+                    }
+                }
+            }
+            Ok(all_ok)
+        }
+        ["steps", file] => {
+            let source = read(file)?;
+            let inference = infer::infer(&source);
+            print!("{}", inference.trace(&source));
+            println!();
+            match &inference.result {
+                Ok(ty) => {
+                    println!("substitution:");
+                    for (var, solved) in &inference.substitution.0 {
+                        println!("    ?{} := {}", var, solved);
+                    }
+                    println!();
+                    println!("type: {}", ty);
+                    Ok(true)
+                }
+                Err(error) => {
+                    println!("{}", error.render(file, &source));
+                    Ok(false)
+                }
+            }
+        }
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{}", USAGE);
+            Ok(true)
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn read(file: &str) -> Result<String, String> {
+    fs::read_to_string(file).map_err(|error| format!("{}: {}", file, error))
+}
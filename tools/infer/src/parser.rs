@@ -0,0 +1,321 @@
+//! Turning source into an [`Expr`], with mini's tokens.
+//!
+//! ```text
+//! program = block
+//! block   = ("let" ident (":" type)? "=" expr ";" | expr ";")* expr?
+//! expr    = "|" (ident ("," ident)*)? "|" expr | sum
+//! sum     = postfix ("+" postfix)*
+//! postfix = primary ("(" args ")" | "." ident "(" args ")")*
+//! primary = int | string | ident ("::" ident)* | "(" ")" | "(" expr ")"
+//!         | "{" block "}"
+//! type    = "_" | "i32" | "String" | "&" "str" | "(" ")" | "Vec" "<" type ">"
+//! ```
+//!
+//! A block without an expression at the end is `()`.
+
+use mini::diagnostic::{Diagnostic, Span};
+use mini::lexer::{self, Token};
+
+use crate::ast::{Annotation, Expr, ExprKind};
+
+pub fn parse(source: &str) -> Result<Expr, Diagnostic> {
+    let tokens = lexer::tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        at: 0,
+        end: source.len(),
+    };
+    let expr = parser.block()?;
+    if parser.at < parser.tokens.len() {
+        return Err(parser.unexpected("the end of the file"));
+    }
+    Ok(expr)
+}
+
+struct Parser {
+    tokens: Vec<(Token, Span)>,
+    at: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.at).map(|(token, _)| token)
+    }
+
+    fn span(&self) -> Span {
+        self.tokens
+            .get(self.at)
+            .map_or(Span::new(self.end, self.end), |(_, span)| *span)
+    }
+
+    fn previous(&self) -> Span {
+        self.tokens[self.at - 1].1
+    }
+
+    fn is(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punct(p)) if *p == punct)
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let is = self.is(punct);
+        if is {
+            self.at += 1;
+        }
+        is
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<Span, Diagnostic> {
+        if self.eat(punct) {
+            Ok(self.previous())
+        } else {
+            Err(self.unexpected(&format!("`{}`", punct)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<(String, Span), Diagnostic> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.at += 1;
+                Ok((name, self.previous()))
+            }
+            _ => Err(self.unexpected("an identifier")),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Diagnostic {
+        let found = match self.peek() {
+            None => String::from("the end of the file"),
+            Some(Token::Ident(name)) => format!("`{}`", name),
+            Some(Token::Macro(name)) => format!("`{}!`", name),
+            Some(Token::Int(value)) => format!("`{}`", value),
+            Some(Token::Str(value)) => format!("{:?}", value),
+            Some(Token::Char(value)) => format!("{:?}", value),
+            Some(Token::Keyword(keyword)) => format!("`{}`", keyword),
+            Some(Token::Punct(punct)) => format!("`{}`", punct),
+        };
+        Diagnostic::error(
+            None,
+            format!("expected {}, found {}", expected, found),
+            self.span(),
+            format!("expected {}", expected),
+        )
+    }
+
+    /// Statements up to the end of the file or a `}`, folded into `let`s
+    /// from the last one back.
+    fn block(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.span();
+        let mut statements = Vec::new();
+        let mut tail = None;
+
+        while self.peek().is_some() && !self.is("}") {
+            if matches!(self.peek(), Some(Token::Keyword("let"))) {
+                self.at += 1;
+                let (name, name_span) = self.ident()?;
+                let annotation = if self.eat(":") {
+                    Some(self.annotation()?)
+                } else {
+                    None
+                };
+                self.expect("=")?;
+                let value = self.expr()?;
+                self.expect(";")?;
+                statements.push((name, name_span, annotation, value));
+                continue;
+            }
+            let expr = self.expr()?;
+            if self.eat(";") {
+                let span = expr.span;
+                statements.push((String::from("_"), span, None, expr));
+            } else {
+                tail = Some(expr);
+                break;
+            }
+        }
+
+        let mut body = tail.unwrap_or(Expr {
+            kind: ExprKind::Unit,
+            span: Span::new(self.span().start, self.span().start),
+        });
+        for (name, name_span, annotation, value) in statements.into_iter().rev() {
+            body = Expr {
+                span: start.to(body.span),
+                kind: ExprKind::Let {
+                    name,
+                    name_span,
+                    annotation,
+                    value: Box::new(value),
+                    body: Box::new(body),
+                },
+            };
+        }
+        Ok(body)
+    }
+
+    fn annotation(&mut self) -> Result<Annotation, Diagnostic> {
+        if self.eat("&") {
+            let (name, _) = self.ident()?;
+            if name != "str" {
+                return Err(Diagnostic::error(
+                    None,
+                    format!("`&{}` isn't a type this language has", name),
+                    self.previous(),
+                    "expected `str`",
+                ));
+            }
+            return Ok(Annotation::Named(String::from("&str"), Vec::new()));
+        }
+        if self.eat("(") {
+            self.expect(")")?;
+            return Ok(Annotation::Named(String::from("()"), Vec::new()));
+        }
+        let (name, span) = self.ident()?;
+        match name.as_str() {
+            "_" => Ok(Annotation::Hole),
+            "i32" | "String" => Ok(Annotation::Named(name, Vec::new())),
+            "Vec" => {
+                self.expect("<")?;
+                let element = self.annotation()?;
+                self.expect(">")?;
+                Ok(Annotation::Named(name, vec![element]))
+            }
+            _ => Err(Diagnostic::error(
+                Some("E0412"),
+                format!("cannot find type `{}` in this scope", name),
+                span,
+                "not found in this scope",
+            )),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.span();
+        if self.eat("|") {
+            let mut params = Vec::new();
+            while !self.is("|") {
+                params.push(self.ident()?);
+                if !self.eat(",") {
+                    break;
+                }
+            }
+            self.expect("|")?;
+            let body = self.expr()?;
+            return Ok(Expr {
+                span: start.to(body.span),
+                kind: ExprKind::Lambda {
+                    params,
+                    body: Box::new(body),
+                },
+            });
+        }
+
+        let mut left = self.postfix()?;
+        while self.is("+") {
+            let plus = self.span();
+            self.at += 1;
+            let right = self.postfix()?;
+            left = Expr {
+                span: left.span.to(right.span),
+                kind: ExprKind::Call {
+                    function: Box::new(Expr {
+                        kind: ExprKind::Var(String::from("+")),
+                        span: plus,
+                    }),
+                    args: vec![left, right],
+                },
+            };
+        }
+        Ok(left)
+    }
+
+    fn postfix(&mut self) -> Result<Expr, Diagnostic> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.is("(") {
+                let (args, end) = self.args()?;
+                expr = Expr {
+                    span: expr.span.to(end),
+                    kind: ExprKind::Call {
+                        function: Box::new(expr),
+                        args,
+                    },
+                };
+            } else if self.eat(".") {
+                let (method, _) = self.ident()?;
+                let (args, end) = self.args()?;
+                expr = Expr {
+                    span: expr.span.to(end),
+                    kind: ExprKind::Method {
+                        receiver: Box::new(expr),
+                        method,
+                        args,
+                    },
+                };
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    /// `(a, b, c)`, returning the span of the `)`.
+    fn args(&mut self) -> Result<(Vec<Expr>, Span), Diagnostic> {
+        self.expect("(")?;
+        let mut args = Vec::new();
+        while !self.is(")") {
+            args.push(self.expr()?);
+            if !self.eat(",") {
+                break;
+            }
+        }
+        let end = self.expect(")")?;
+        Ok((args, end))
+    }
+
+    fn primary(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.span();
+        let kind = match self.peek().cloned() {
+            Some(Token::Int(value)) => {
+                self.at += 1;
+                ExprKind::Int(value)
+            }
+            Some(Token::Str(value)) => {
+                self.at += 1;
+                ExprKind::Str(value)
+            }
+            Some(Token::Ident(_)) => {
+                let (mut path, _) = self.ident()?;
+                while self.eat("::") {
+                    let (segment, _) = self.ident()?;
+                    path = format!("{}::{}", path, segment);
+                }
+                ExprKind::Var(path)
+            }
+            Some(Token::Punct("(")) => {
+                self.at += 1;
+                if self.eat(")") {
+                    return Ok(Expr {
+                        kind: ExprKind::Unit,
+                        span: start.to(self.previous()),
+                    });
+                }
+                let expr = self.expr()?;
+                self.expect(")")?;
+                return Ok(expr);
+            }
+            Some(Token::Punct("{")) => {
+                self.at += 1;
+                let mut expr = self.block()?;
+                let end = self.expect("}")?;
+                expr.span = start.to(end);
+                return Ok(expr);
+            }
+            _ => return Err(self.unexpected("an expression")),
+        };
+        Ok(Expr {
+            kind,
+            span: start.to(self.previous()),
+        })
+    }
+}
@@ -0,0 +1,189 @@
+//! Types, type schemes and substitutions.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    /// A type variable, which stands for a type that isn't known yet. It's
+    /// written `?1`.
+    Var(u32),
+    /// `i32`, or `Vec<T>` with its type arguments.
+    Con(String, Vec<Type>),
+    /// `fn(i32, i32) -> i32`.
+    Fn(Vec<Type>, Box<Type>),
+}
+
+impl Type {
+    pub fn con(name: &str) -> Type {
+        Type::Con(name.to_string(), Vec::new())
+    }
+
+    pub fn vec(element: Type) -> Type {
+        Type::Con(String::from("Vec"), vec![element])
+    }
+
+    pub fn function(params: Vec<Type>, returns: Type) -> Type {
+        Type::Fn(params, Box::new(returns))
+    }
+
+    /// The type variables in it, in the order they first appear.
+    pub fn vars(&self) -> Vec<u32> {
+        let mut vars = Vec::new();
+        self.collect_vars(&mut vars);
+        vars
+    }
+
+    fn collect_vars(&self, vars: &mut Vec<u32>) {
+        match self {
+            Type::Var(var) => {
+                if !vars.contains(var) {
+                    vars.push(*var);
+                }
+            }
+            Type::Con(_, args) => args.iter().for_each(|arg| arg.collect_vars(vars)),
+            Type::Fn(params, returns) => {
+                params.iter().for_each(|param| param.collect_vars(vars));
+                returns.collect_vars(vars);
+            }
+        }
+    }
+
+    pub fn contains(&self, var: u32) -> bool {
+        self.vars().contains(&var)
+    }
+
+    /// The type, with each variable written by `name`.
+    pub fn show(&self, name: &dyn Fn(u32) -> String) -> String {
+        match self {
+            Type::Var(var) => name(*var),
+            Type::Con(con, args) if args.is_empty() => con.clone(),
+            Type::Con(con, args) => {
+                let args: Vec<String> = args.iter().map(|arg| arg.show(name)).collect();
+                format!("{}<{}>", con, args.join(", "))
+            }
+            Type::Fn(params, returns) => {
+                let params: Vec<String> = params.iter().map(|param| param.show(name)).collect();
+                match &**returns {
+                    Type::Con(con, args) if con == "()" && args.is_empty() => {
+                        format!("fn({})", params.join(", "))
+                    }
+                    returns => format!("fn({}) -> {}", params.join(", "), returns.show(name)),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.show(&|var| format!("?{}", var)))
+    }
+}
+
+/// A type that's generic over some of its variables, like
+/// `∀T. fn(T) -> T`. Each use of a variable with a scheme gets its own
+/// fresh variables for them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scheme {
+    /// The generic variables, and the names they're shown with.
+    pub vars: Vec<(u32, String)>,
+    pub ty: Type,
+}
+
+impl Scheme {
+    /// A type that isn't generic.
+    pub fn mono(ty: Type) -> Scheme {
+        Scheme {
+            vars: Vec::new(),
+            ty,
+        }
+    }
+
+    /// The variables that aren't generic.
+    pub fn free_vars(&self) -> Vec<u32> {
+        self.ty
+            .vars()
+            .into_iter()
+            .filter(|var| !self.vars.iter().any(|(generic, _)| generic == var))
+            .collect()
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.vars.is_empty() {
+            return write!(f, "{}", self.ty);
+        }
+        let names: Vec<&str> = self.vars.iter().map(|(_, name)| name.as_str()).collect();
+        let ty = self.ty.show(&|var| {
+            self.vars
+                .iter()
+                .find(|(generic, _)| *generic == var)
+                .map_or(format!("?{}", var), |(_, name)| name.clone())
+        });
+        write!(f, "∀{}. {}", names.join(", "), ty)
+    }
+}
+
+/// What's been worked out about the type variables so far: each one that's
+/// been solved, and the type it stands for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Subst(pub BTreeMap<u32, Type>);
+
+impl Subst {
+    pub fn single(var: u32, ty: Type) -> Subst {
+        let mut subst = Subst::default();
+        subst.0.insert(var, ty);
+        subst
+    }
+
+    /// The type with every solved variable replaced.
+    pub fn apply(&self, ty: &Type) -> Type {
+        match ty {
+            Type::Var(var) => match self.0.get(var) {
+                Some(solved) => solved.clone(),
+                None => ty.clone(),
+            },
+            Type::Con(name, args) => Type::Con(
+                name.clone(),
+                args.iter().map(|arg| self.apply(arg)).collect(),
+            ),
+            Type::Fn(params, returns) => Type::Fn(
+                params.iter().map(|param| self.apply(param)).collect(),
+                Box::new(self.apply(returns)),
+            ),
+        }
+    }
+
+    pub fn apply_scheme(&self, scheme: &Scheme) -> Scheme {
+        let mut inner = self.clone();
+        for (var, _) in &scheme.vars {
+            inner.0.remove(var);
+        }
+        Scheme {
+            vars: scheme.vars.clone(),
+            ty: inner.apply(&scheme.ty),
+        }
+    }
+
+    /// `self` after `earlier`: applying the result is the same as applying
+    /// `earlier` and then `self`.
+    pub fn compose(&self, earlier: &Subst) -> Subst {
+        let mut composed: BTreeMap<u32, Type> = earlier
+            .0
+            .iter()
+            .map(|(var, ty)| (*var, self.apply(ty)))
+            .collect();
+        for (var, ty) in &self.0 {
+            composed.entry(*var).or_insert_with(|| ty.clone());
+        }
+        Subst(composed)
+    }
+}
+
+/// The variables a set of schemes leave free, which a `let` can't
+/// generalize over.
+pub fn free_in<'a>(schemes: impl Iterator<Item = &'a Scheme>) -> BTreeSet<u32> {
+    schemes.flat_map(Scheme::free_vars).collect()
+}
@@ -0,0 +1,142 @@
+//! Infers the example programs, which say on their first line what they
+//! should come to: `// expect: <type>`, or an error code.
+
+use std::fs;
+use std::path::Path;
+
+use infer::inference::StepKind;
+use infer::types::Type;
+
+#[test]
+fn programs_have_the_types_they_expect() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("programs");
+    let mut inferred = 0;
+    let mut failures = Vec::new();
+
+    let mut paths: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "hm"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let source = fs::read_to_string(&path).unwrap();
+        let expect = source
+            .lines()
+            .next()
+            .and_then(|line| line.strip_prefix("// expect: "))
+            .unwrap_or_else(|| panic!("{} doesn't say what it expects", path.display()));
+        let got = match infer::infer(&source).result {
+            Ok(ty) => ty.to_string(),
+            Err(error) => error.code.unwrap_or("?").to_string(),
+        };
+        if got != expect {
+            failures.push(format!(
+                "{}: expected {}, got {}",
+                path.display(),
+                expect,
+                got
+            ));
+        }
+        inferred += 1;
+    }
+
+    assert!(inferred >= 5, "only found {} programs", inferred);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn the_broken_vec_is_ambiguous_in_t() {
+    let source = "let broken_vec = Vec::new();\n()\n";
+    let error = infer::infer(source).result.unwrap_err();
+    assert_eq!(error.code, Some("E0282"));
+    assert_eq!(error.message, "type annotations needed");
+    assert_eq!(error.labels[0].text, "cannot infer type for `T`");
+    assert_eq!(error.span().position(source), (1, 5));
+    assert!(error.notes[0].contains("`Vec<T>`"));
+}
+
+#[test]
+fn an_annotation_fixes_the_broken_vec() {
+    let source = "let working_vec: Vec<i32> = Vec::new();\nworking_vec\n";
+    assert_eq!(infer::infer(source).result, Ok(Type::vec(Type::con("i32"))));
+    let source =
+        "let working_vec: Vec<_> = Vec::new();\nworking_vec.push(\"Gabe\");\nworking_vec\n";
+    assert_eq!(
+        infer::infer(source).result,
+        Ok(Type::vec(Type::con("&str")))
+    );
+}
+
+#[test]
+fn statements_can_be_ambiguous_too() {
+    let error = infer::infer("Vec::new();\n5\n").result.unwrap_err();
+    assert_eq!(error.code, Some("E0282"));
+}
+
+#[test]
+fn every_variable_solved_is_a_step() {
+    let inference = infer::infer("let v = Vec::new();\nv.push(5);\nv\n");
+    let binds: Vec<String> = inference
+        .steps
+        .iter()
+        .filter_map(|step| match &step.kind {
+            StepKind::Bind { var, ty } => Some(format!("?{} := {}", var, ty)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        binds,
+        ["?2 := Vec<?1>", "?3 := ?1", "?1 := i32", "?4 := ()"]
+    );
+    // The final substitution has them all solved.
+    for ty in inference.substitution.0.values() {
+        assert!(ty.vars().is_empty(), "{} isn't solved", ty);
+    }
+}
+
+#[test]
+fn lets_of_lambdas_are_generic_and_calls_are_not() {
+    let inference = infer::infer("let id = |x| x;\nlet a = id(5);\nid(\"Gabe\")\n");
+    assert!(inference.steps.iter().any(|step| matches!(
+        &step.kind,
+        StepKind::Generalize { name, scheme } if name == "id" && scheme.vars.len() == 1
+    )));
+    assert!(inference.steps.iter().any(|step| matches!(
+        &step.kind,
+        StepKind::Monomorphic { name, .. } if name == "a"
+    )));
+    assert_eq!(inference.result, Ok(Type::con("&str")));
+
+    // Passed in as an argument, it isn't a let, so it only gets one type.
+    let error = infer::infer("let both = |id| id(id(5) + 1);\nboth(|x| x)\n")
+        .result
+        .map(|ty| ty.to_string());
+    assert_eq!(error, Ok(String::from("i32")));
+    let error = infer::infer("let both = |id| { id(5); id(\"Gabe\") };\n()\n")
+        .result
+        .unwrap_err();
+    assert_eq!(error.code, Some("E0308"));
+}
+
+#[test]
+fn a_type_cannot_contain_itself() {
+    let error = infer::infer("let apply = |f| f(f);\n()\n")
+        .result
+        .unwrap_err();
+    assert_eq!(error.code, Some("E0308"));
+    assert_eq!(error.labels[0].text, "cyclic type of infinite size");
+}
+
+#[test]
+fn calls_check_their_arguments() {
+    let error = infer::infer("let add = |a, b| a + b;\nadd(1)\n")
+        .result
+        .unwrap_err();
+    assert_eq!(error.code, Some("E0061"));
+    let error = infer::infer("let x = 5;\nx(1)\n").result.unwrap_err();
+    assert_eq!(error.code, Some("E0618"));
+    let error = infer::infer("y + 1\n").result.unwrap_err();
+    assert_eq!(error.code, Some("E0425"));
+}
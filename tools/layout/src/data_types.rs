@@ -0,0 +1,84 @@
+//! The types `data_types` introduces, with the values it gives them.
+
+use std::mem::{self, offset_of};
+
+use crate::layout::Type;
+use crate::registry::Registry;
+
+const LESSON: &str = "data_types";
+
+pub fn register(registry: &mut Registry) {
+    registry.register(LESSON, Type::scalar::<i32>().example("42", &42i32));
+    registry.register(LESSON, Type::scalar::<char>().example("'ß'", &'ß'));
+
+    let hello: &'static str = "你好";
+    let hello_world: &'static str = "Hello, World!";
+    registry.register(
+        LESSON,
+        str_fields(Type::of::<&str>("&str"), "", 0)
+            .example("\"Hello, World!\"", &hello_world)
+            .example("\"你好\"", &hello)
+            .note(&format!("points to {}", hex(hello.as_bytes())))
+            .note("the length is in bytes, and 你 and 好 are three each"),
+    );
+
+    // Safe, since the offsets are where the fields are.
+    let unsigned_and_signed = unsafe {
+        Type::of::<(u32, i32)>("(u32, i32)")
+            .field::<u32>(".0", offset_of!((u32, i32), 0))
+            .field::<i32>(".1", offset_of!((u32, i32), 1))
+    };
+    registry.register(
+        LESSON,
+        unsigned_and_signed.example("(1024, -2048)", &(1024u32, -2048i32)),
+    );
+
+    let str_and_char: (&'static str, char) = ("This is a string", 'a');
+    let ty = str_fields(
+        Type::of::<(&str, char)>("(&str, char)"),
+        ".0",
+        offset_of!((&str, char), 0),
+    );
+    // Safe, since the offset is where the field is.
+    let ty = unsafe { ty.field::<char>(".1", offset_of!((&str, char), 1)) };
+    registry.register(
+        LESSON,
+        ty.example("(\"This is a string\", 'a')", &str_and_char),
+    );
+
+    let mut array = Type::of::<[i32; 5]>("[i32; 5]");
+    for index in 0..5 {
+        // Safe, since the elements of an array are one after the other.
+        array =
+            unsafe { array.field::<i32>(&format!("[{}]", index), index * mem::size_of::<i32>()) };
+    }
+    registry.register(
+        LESSON,
+        array.example("[16, 17, 5, 4, 2002]", &[16i32, 17, 5, 4, 2002]),
+    );
+}
+
+/// Adds the pointer and the length of a `&str` at `offset` in `ty`, as
+/// `<name>.ptr` and `<name>.len`. Rust doesn't promise which comes first,
+/// so we look.
+fn str_fields(ty: Type, name: &str, offset: usize) -> Type {
+    let sample = "你好";
+    // Safe, since a `&str` is two words, which are both initialized.
+    let words: [usize; 2] = unsafe { mem::transmute(sample) };
+    let word = mem::size_of::<usize>();
+    let (ptr, len) = if words[0] == sample.as_ptr() as usize {
+        (offset, offset + word)
+    } else {
+        (offset + word, offset)
+    };
+    // Safe, since those are where we just found the pointer and length.
+    unsafe {
+        ty.field::<*const u8>(&format!("{}.ptr", name), ptr)
+            .field::<usize>(&format!("{}.len", name), len)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let bytes: Vec<String> = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    bytes.join(" ")
+}
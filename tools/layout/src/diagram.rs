@@ -0,0 +1,174 @@
+//! Drawing a type's layout, and dumping its examples' bytes.
+//!
+//! ```text
+//! (u32, i32): 8 bytes, aligned to 4
+//!
+//!          0  1  2  3  4  5  6  7
+//!        +-----------+-----------+
+//!      0 |    .0     |    .1     |
+//!        +-----------+-----------+
+//! ```
+//!
+//! Each byte is three columns wide, and a type is drawn sixteen bytes to a
+//! row, with the offset of each row on its left.
+
+use crate::layout::{Field, Type};
+
+/// Bytes in each row of a diagram or a dump.
+const ROW: usize = 16;
+
+/// A type's size, alignment and fields, a diagram of them, and the bytes
+/// of each of its examples.
+pub fn render(ty: &Type) -> String {
+    let mut text = format!("{}: {} bytes, aligned to {}", ty.name, ty.size, ty.align);
+    match ty.padding() {
+        0 => {}
+        1 => text.push_str(", with 1 byte of padding"),
+        padding => text.push_str(&format!(", with {} bytes of padding", padding)),
+    }
+    text.push_str("\n\n");
+
+    if ty.fields.len() > 1 {
+        let width = ty
+            .fields
+            .iter()
+            .map(|field| field.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("field".len());
+        text.push_str(&format!(
+            "    {:<width$}  offset  size  type\n",
+            "field",
+            width = width
+        ));
+        for field in &ty.fields {
+            text.push_str(&format!(
+                "    {:<width$}  {:>6}  {:>4}  {}\n",
+                field.name,
+                field.offset,
+                field.size,
+                field.ty,
+                width = width
+            ));
+        }
+        text.push('\n');
+    }
+
+    text.push_str(&diagram(ty));
+
+    for example in &ty.examples {
+        text.push('\n');
+        text.push_str(&format!("{}\n", example.value));
+        for start in (0..ty.size).step_by(ROW) {
+            let end = (start + ROW).min(ty.size);
+            let bytes: Vec<String> = example.bytes[start..end]
+                .iter()
+                .map(|byte| byte.map_or(String::from("__"), |byte| format!("{:02x}", byte)))
+                .collect();
+            text.push_str(&format!("{:>6}  {}\n", start, bytes.join(" ")));
+        }
+        for (field, value) in ty.fields.iter().zip(&example.fields) {
+            text.push_str(&format!("    {} = {}\n", label(field), value));
+        }
+        for note in &example.notes {
+            text.push_str(&format!("    ({})\n", note));
+        }
+    }
+    text
+}
+
+/// The boxes: one for each field, and one for each run of padding.
+pub fn diagram(ty: &Type) -> String {
+    let columns = ty.size.min(ROW);
+    let mut text = String::from("       ");
+    for column in 0..columns {
+        text.push_str(&format!("{:>3}", column));
+    }
+    text.push('\n');
+
+    // Where each box starts, and where the row ends, for the rows above
+    // and below each border.
+    let mut above: Vec<usize> = Vec::new();
+    for start in (0..ty.size).step_by(ROW) {
+        let end = (start + ROW).min(ty.size);
+        let segments = segments(ty, start, end);
+        let mut below = vec![0];
+        let mut content = format!("{:>6} ", start);
+        for (label, bytes) in &segments {
+            let width = bytes * 3 - 1;
+            content.push('|');
+            content.push_str(&centre(label, width));
+            below.push(below.last().unwrap() + bytes);
+        }
+        content.push('|');
+        text.push_str(&border(&above, &below));
+        text.push_str(&content);
+        text.push('\n');
+        above = below;
+    }
+    text.push_str(&border(&above, &[]));
+    text
+}
+
+/// A line between two rows, with a `+` wherever a box in either starts or
+/// ends.
+fn border(above: &[usize], below: &[usize]) -> String {
+    let bytes = above.iter().chain(below).copied().max().unwrap_or(0);
+    let mut line = String::from("       ");
+    for byte in 0..=bytes {
+        if above.contains(&byte) || below.contains(&byte) {
+            line.push('+');
+        } else {
+            line.push('-');
+        }
+        if byte < bytes {
+            line.push_str("--");
+        }
+    }
+    line.push('\n');
+    line
+}
+
+/// The pieces of a row, as a label and how many bytes they take up in it.
+fn segments(ty: &Type, start: usize, end: usize) -> Vec<(String, usize)> {
+    let mut segments = Vec::new();
+    let mut at = start;
+    while at < end {
+        let field = ty
+            .fields
+            .iter()
+            .find(|field| field.offset <= at && at < field.offset + field.size);
+        let until = match field {
+            Some(field) => (field.offset + field.size).min(end),
+            None => ty
+                .fields
+                .iter()
+                .map(|field| field.offset)
+                .filter(|offset| *offset > at)
+                .min()
+                .unwrap_or(end)
+                .min(end),
+        };
+        let label = field.map_or(String::from("padding"), label);
+        segments.push((label, until - at));
+        at = until;
+    }
+    segments
+}
+
+/// A field by its name, or by its type if it's the whole thing.
+fn label(field: &Field) -> String {
+    if field.name.is_empty() {
+        field.ty.to_string()
+    } else {
+        field.name.clone()
+    }
+}
+
+/// `text` in the middle of `width` columns, cut short if it doesn't fit.
+fn centre(text: &str, width: usize) -> String {
+    let text: String = text.chars().take(width).collect();
+    let space = width - text.chars().count();
+    let left = space / 2;
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(space - left))
+}
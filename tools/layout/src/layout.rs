@@ -0,0 +1,229 @@
+//! What a type looks like in memory, and the bytes of some of its values.
+//!
+//! A type is described by its size and alignment, which come from
+//! `std::mem`, and its fields, each of which is a [`Scalar`] at an offset
+//! from `std::mem::offset_of!`:
+//!
+//! ```
+//! use std::mem::offset_of;
+//! use layout::layout::Type;
+//!
+//! // Safe, since the offsets are where the fields are.
+//! let pair = unsafe {
+//!     Type::of::<(u32, i32)>("(u32, i32)")
+//!         .field::<u32>(".0", offset_of!((u32, i32), 0))
+//!         .field::<i32>(".1", offset_of!((u32, i32), 1))
+//! };
+//! let pair = pair.example("(1024, -2048)", &(1024u32, -2048i32));
+//! assert_eq!(pair.size, 8);
+//! ```
+//!
+//! Bytes that aren't in any field are padding. They're never read, since
+//! they might not have been written.
+
+use std::any::TypeId;
+use std::convert::TryInto;
+use std::mem;
+
+/// A type with no padding, whose bytes can be read as a value.
+///
+/// # Safety
+///
+/// Every byte of every value of the type must be initialized, because
+/// [`Type::example`] reads them.
+pub unsafe trait Scalar {
+    /// How the type is written in Rust.
+    const NAME: &'static str;
+
+    /// The value that `bytes`, which are `size_of::<Self>()` long, hold.
+    fn read(bytes: &[u8]) -> String;
+}
+
+macro_rules! numbers {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl Scalar for $ty {
+                const NAME: &'static str = stringify!($ty);
+
+                fn read(bytes: &[u8]) -> String {
+                    <$ty>::from_ne_bytes(bytes.try_into().unwrap()).to_string()
+                }
+            }
+        )*
+    };
+}
+
+numbers!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl Scalar for bool {
+    const NAME: &'static str = "bool";
+
+    fn read(bytes: &[u8]) -> String {
+        (bytes[0] != 0).to_string()
+    }
+}
+
+unsafe impl Scalar for char {
+    const NAME: &'static str = "char";
+
+    fn read(bytes: &[u8]) -> String {
+        let value = u32::from_ne_bytes(bytes.try_into().unwrap());
+        char::from_u32(value).map_or_else(|| format!("{:#x}", value), |c| format!("{:?}", c))
+    }
+}
+
+unsafe impl Scalar for *const u8 {
+    const NAME: &'static str = "*const u8";
+
+    fn read(bytes: &[u8]) -> String {
+        format!("{:#x}", usize::from_ne_bytes(bytes.try_into().unwrap()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Field {
+    /// How it's reached from a value, like `.0` or `[2]`.
+    pub name: String,
+    pub ty: &'static str,
+    pub offset: usize,
+    pub size: usize,
+    read: fn(&[u8]) -> String,
+}
+
+/// A value of the type, and its bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// How the value is written in Rust.
+    pub value: String,
+    /// One for each byte of the value, with `None` for padding.
+    pub bytes: Vec<Option<u8>>,
+    /// What each field holds, in the order of the fields.
+    pub fields: Vec<String>,
+    /// Anything else worth knowing about it, like what a pointer points to.
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Type {
+    /// How the type is written in Rust.
+    pub name: String,
+    pub size: usize,
+    pub align: usize,
+    /// Sorted by offset, which needn't be the order they're declared in,
+    /// since Rust may reorder them to waste less space on padding.
+    pub fields: Vec<Field>,
+    pub examples: Vec<Example>,
+    id: TypeId,
+}
+
+impl Type {
+    /// A type with no fields yet, so all padding.
+    pub fn of<T: 'static>(name: &str) -> Type {
+        Type {
+            id: TypeId::of::<T>(),
+            name: name.to_string(),
+            size: mem::size_of::<T>(),
+            align: mem::align_of::<T>(),
+            fields: Vec::new(),
+            examples: Vec::new(),
+        }
+    }
+
+    /// A scalar type, which is one field as big as the whole thing.
+    pub fn scalar<T: Scalar + 'static>() -> Type {
+        // Safe, since the whole of a scalar is initialized.
+        unsafe { Type::of::<T>(T::NAME).field::<T>("", 0) }
+    }
+
+    /// Adds a field of type `F` at `offset`. Fields have to be added
+    /// before any examples.
+    ///
+    /// # Safety
+    ///
+    /// There must be an `F` at `offset` in every value of the type, like
+    /// `offset_of!` says there is, since examples read it.
+    ///
+    /// # Panics
+    ///
+    /// If the field doesn't fit in the type, or overlaps another field.
+    pub unsafe fn field<F: Scalar>(mut self, name: &str, offset: usize) -> Type {
+        let size = mem::size_of::<F>();
+        assert!(
+            self.examples.is_empty(),
+            "`{}` was added to `{}` after an example",
+            name,
+            self.name
+        );
+        assert!(
+            offset + size <= self.size,
+            "`{}` doesn't fit in `{}`",
+            name,
+            self.name
+        );
+        assert!(
+            self.fields
+                .iter()
+                .all(|field| offset + size <= field.offset || field.offset + field.size <= offset),
+            "`{}` overlaps another field of `{}`",
+            name,
+            self.name
+        );
+        self.fields.push(Field {
+            name: name.to_string(),
+            ty: F::NAME,
+            offset,
+            size,
+            read: F::read,
+        });
+        self.fields.sort_by_key(|field| field.offset);
+        self
+    }
+
+    /// Adds a value of the type, written `value` in Rust. Only the bytes of
+    /// its fields are read.
+    ///
+    /// # Panics
+    ///
+    /// If `T` isn't the type this was made with.
+    pub fn example<T: 'static>(mut self, value: &str, example: &T) -> Type {
+        assert!(
+            TypeId::of::<T>() == self.id,
+            "the example `{}` isn't a `{}`",
+            value,
+            self.name
+        );
+        let start = (example as *const T).cast::<u8>();
+        let mut bytes = vec![None; self.size];
+        let mut fields = Vec::new();
+        for field in &self.fields {
+            // Safe, since `field` was promised there's a `Scalar` here, so
+            // every byte of it was written.
+            let field_bytes =
+                unsafe { std::slice::from_raw_parts(start.add(field.offset), field.size) };
+            for (index, byte) in field_bytes.iter().enumerate() {
+                bytes[field.offset + index] = Some(*byte);
+            }
+            fields.push((field.read)(field_bytes));
+        }
+        self.examples.push(Example {
+            value: value.to_string(),
+            bytes,
+            fields,
+            notes: Vec::new(),
+        });
+        self
+    }
+
+    /// Adds a note to the last example.
+    pub fn note(mut self, note: &str) -> Type {
+        if let Some(example) = self.examples.last_mut() {
+            example.notes.push(note.to_string());
+        }
+        self
+    }
+
+    /// The bytes that aren't in any field.
+    pub fn padding(&self) -> usize {
+        self.size - self.fields.iter().map(|field| field.size).sum::<usize>()
+    }
+}
@@ -0,0 +1,13 @@
+//! layout: how the lessons' types are laid out in memory.
+//!
+//! `data_types` says a `&str` has a known size because it "holds some
+//! information about the string, including its location and size", and
+//! introduces tuples and arrays. This shows each of those types' size,
+//! alignment and fields as a [`diagram`], with the actual bytes of the
+//! values the lesson gives them. Types are described with [`layout`], and a
+//! lesson's types are added to the [`registry`] to show up.
+
+pub mod data_types;
+pub mod diagram;
+pub mod layout;
+pub mod registry;
@@ -0,0 +1,85 @@
+//! `layout` draws the lessons' types as they are in memory.
+
+use std::env;
+use std::process;
+
+use layout::registry::Registry;
+
+const USAGE: &str = "\
+usage: layout <command>
+
+commands:
+    list          list the types, by the lesson they're from
+    show [<type>...]
+                  draw each type's layout and dump its examples' bytes, or
+                  every type's if none are named
+    lesson <lesson>
+                  draw every type a lesson registered";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match layout(&args) {
+        Ok(()) => {}
+        Err(error) => {
+            eprintln!("layout: {}", error);
+            process::exit(2);
+        }
+    }
+}
+
+fn layout(args: &[&str]) -> Result<(), String> {
+    let registry = Registry::lessons();
+    match args {
+        ["list"] => {
+            for lesson in registry.lesson_names() {
+                println!("{}", lesson);
+                for ty in registry.from_lesson(lesson) {
+                    println!("    {}", ty.name);
+                }
+            }
+            Ok(())
+        }
+        ["show"] => {
+            show(registry.types());
+            Ok(())
+        }
+        ["show", names @ ..] => {
+            let types = names
+                .iter()
+                .map(|name| {
+                    registry.get(name).ok_or_else(|| {
+                        format!("there's no type `{}`; `layout list` lists them", name)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            show(types.into_iter());
+            Ok(())
+        }
+        ["lesson", lesson] => {
+            if !registry.lesson_names().contains(lesson) {
+                return Err(format!("`{}` hasn't registered any types", lesson));
+            }
+            show(registry.from_lesson(lesson));
+            Ok(())
+        }
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn show<'a>(types: impl Iterator<Item = &'a layout::layout::Type>) {
+    if cfg!(target_endian = "little") {
+        println!("(this machine is little-endian: a number's lowest byte comes first)");
+    } else {
+        println!("(this machine is big-endian: a number's highest byte comes first)");
+    }
+    for ty in types {
+        println!();
+        print!("{}", layout::diagram::render(ty));
+    }
+}
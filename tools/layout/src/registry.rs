@@ -0,0 +1,64 @@
+//! The types to show, grouped by the lesson that introduces them.
+//!
+//! A lesson's types go in a module of their own with a `register` function,
+//! like [`data_types`](crate::data_types), which [`Registry::lessons`] calls.
+
+use crate::data_types;
+use crate::layout::Type;
+
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    /// Each type, and the lesson it's from, in the order they were
+    /// registered.
+    types: Vec<(String, Type)>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    /// Every type the lessons register.
+    pub fn lessons() -> Registry {
+        let mut registry = Registry::new();
+        data_types::register(&mut registry);
+        registry
+    }
+
+    /// Adds a type. A type with the same name as one that's already there
+    /// replaces it.
+    pub fn register(&mut self, lesson: &str, ty: Type) {
+        self.types.retain(|(_, existing)| existing.name != ty.name);
+        self.types.push((lesson.to_string(), ty));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Type> {
+        self.types
+            .iter()
+            .find(|(_, ty)| ty.name == name)
+            .map(|(_, ty)| ty)
+    }
+
+    pub fn types(&self) -> impl Iterator<Item = &Type> {
+        self.types.iter().map(|(_, ty)| ty)
+    }
+
+    /// The types a lesson registered.
+    pub fn from_lesson<'a>(&'a self, lesson: &'a str) -> impl Iterator<Item = &'a Type> {
+        self.types
+            .iter()
+            .filter(move |(from, _)| from == lesson)
+            .map(|(_, ty)| ty)
+    }
+
+    /// The lessons with types, in the order they first registered one.
+    pub fn lesson_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for (lesson, _) in &self.types {
+            if !names.contains(&lesson.as_str()) {
+                names.push(lesson);
+            }
+        }
+        names
+    }
+}
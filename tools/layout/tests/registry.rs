@@ -0,0 +1,121 @@
+//! Checks the layouts of `data_types`' types, and that other lessons'
+//! types can be registered.
+
+use std::mem::offset_of;
+
+use layout::diagram;
+use layout::layout::Type;
+use layout::registry::Registry;
+
+#[test]
+fn data_types_registers_its_types() {
+    let registry = Registry::lessons();
+    let names: Vec<&str> = registry
+        .from_lesson("data_types")
+        .map(|ty| ty.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "i32",
+            "char",
+            "&str",
+            "(u32, i32)",
+            "(&str, char)",
+            "[i32; 5]"
+        ]
+    );
+}
+
+#[test]
+fn the_bytes_are_the_values() {
+    let registry = Registry::lessons();
+    let pair = registry.get("(u32, i32)").unwrap();
+    assert_eq!((pair.size, pair.align, pair.padding()), (8, 4, 0));
+    let example = &pair.examples[0];
+    assert_eq!(example.fields, ["1024", "-2048"]);
+    let mut expected = Vec::new();
+    expected.extend(&1024u32.to_ne_bytes());
+    expected.extend(&(-2048i32).to_ne_bytes());
+    let bytes: Vec<u8> = example.bytes.iter().map(|byte| byte.unwrap()).collect();
+    assert_eq!(bytes, expected);
+
+    let array = registry.get("[i32; 5]").unwrap();
+    assert_eq!(array.size, 20);
+    assert_eq!(array.examples[0].fields, ["16", "17", "5", "4", "2002"]);
+}
+
+#[test]
+fn a_str_is_a_pointer_and_a_length_in_bytes() {
+    let registry = Registry::lessons();
+    let str_ref = registry.get("&str").unwrap();
+    assert_eq!(str_ref.size, 2 * std::mem::size_of::<usize>());
+    let hello = &str_ref.examples[1];
+    assert_eq!(hello.value, "\"你好\"");
+    let len = str_ref
+        .fields
+        .iter()
+        .position(|field| field.name == ".len")
+        .unwrap();
+    assert_eq!(hello.fields[len], "6");
+    assert!(hello.notes[0].contains("e4 bd a0 e5 a5 bd"));
+}
+
+#[test]
+fn padding_is_never_read() {
+    let registry = Registry::lessons();
+    let str_and_char = registry.get("(&str, char)").unwrap();
+    assert_eq!(str_and_char.padding(), 4);
+    let bytes = &str_and_char.examples[0].bytes;
+    assert_eq!(bytes[16], Some(b'a'));
+    assert!(bytes[20..].iter().all(Option::is_none));
+
+    let rendered = diagram::render(str_and_char);
+    assert!(rendered.contains("with 4 bytes of padding"));
+    assert!(rendered.contains("|  padding  |"));
+    assert!(rendered.contains("    16  61 00 00 00 __ __ __ __"));
+}
+
+#[allow(dead_code)]
+struct Point {
+    x: i32,
+    y: u8,
+}
+
+#[test]
+fn other_lessons_can_register_structs_and_enums() {
+    let mut registry = Registry::lessons();
+    // Safe, since the offsets are where the fields are.
+    let point = unsafe {
+        Type::of::<Point>("Point")
+            .field::<i32>("x", offset_of!(Point, x))
+            .field::<u8>("y", offset_of!(Point, y))
+    };
+    registry.register(
+        "structs",
+        point.example("Point { x: 1, y: 2 }", &Point { x: 1, y: 2 }),
+    );
+    // `None` is a value no `char` can have, so an `Option<char>` is no
+    // bigger than a `char`.
+    let maybe_char = unsafe { Type::of::<Option<char>>("Option<char>").field::<u32>("", 0) };
+    registry.register("enums", maybe_char.example("Some('a')", &Some('a')));
+
+    assert_eq!(registry.lesson_names(), ["data_types", "structs", "enums"]);
+    let point = registry.get("Point").unwrap();
+    assert_eq!((point.size, point.align, point.padding()), (8, 4, 3));
+    assert_eq!(point.examples[0].fields, ["1", "2"]);
+    assert_eq!(registry.get("Option<char>").unwrap().size, 4);
+
+    let rendered = diagram::render(point);
+    assert!(
+        rendered.contains("|     x     |y |padding |"),
+        "{}",
+        rendered
+    );
+}
+
+#[test]
+#[should_panic(expected = "the example `5` isn't a `i32`")]
+fn examples_must_be_of_the_type() {
+    Type::scalar::<i32>().example("5", &5u32);
+}
@@ -0,0 +1,309 @@
+//! The "uncomment this and see what the compiler tells you" snippets.
+//!
+//! A lesson marks a commented-out block that should not compile by fencing it
+//! the way rustdoc does, with the error codes it should fail with (or, for a
+//! lint that's an error, its name, like `unconditional_panic`) and,
+//! optionally, the start of the compiler's primary message:
+//!
+//! ```text
+//! // ```compile_fail(E0499): cannot borrow `name` as mutable more than once
+//! // let name_mut1 = &mut name;
+//! // let name_mut2 = &mut name;
+//! // say_hello(&name_mut1);
+//! // ```
+//! ```
+//!
+//! To check a snippet, we make a scratch copy of the lesson with just that
+//! block uncommented, compile it with rustc, and look for the errors.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::diagnostic::Diagnostic;
+use crate::{Error, Lesson};
+
+const OPEN: &str = "// ```compile_fail";
+const CLOSE: &str = "// ```";
+
+/// One fenced block of code that should not compile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// The 1-based line of the opening fence.
+    pub line: usize,
+    /// Every one of these must show up as an error.
+    pub codes: Vec<String>,
+    /// What the primary message of one of those errors should start with.
+    pub message: Option<String>,
+    /// The commented-out code, without the `// `.
+    pub code: Vec<String>,
+}
+
+/// A snippet, and whether it failed the way it says it would.
+#[derive(Debug, Clone)]
+pub struct Checked {
+    pub snippet: Snippet,
+    pub result: Result<(), Failure>,
+}
+
+/// Why a snippet didn't fail the way it says it would.
+#[derive(Debug, Clone)]
+pub enum Failure {
+    /// The lesson doesn't compile even without the snippet, so the snippet
+    /// can't be checked.
+    LessonBroken(Vec<Diagnostic>),
+    /// It compiled!
+    Compiled,
+    /// It failed, but without this error code.
+    MissingCode {
+        code: String,
+        errors: Vec<Diagnostic>,
+    },
+    /// It failed with the right codes, but none of them said this.
+    WrongMessage {
+        message: String,
+        errors: Vec<Diagnostic>,
+    },
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (summary, errors) = match self {
+            Failure::LessonBroken(errors) => {
+                (String::from("the lesson itself doesn't compile"), errors)
+            }
+            Failure::Compiled => return write!(f, "it compiled, but it shouldn't have"),
+            Failure::MissingCode { code, errors } => {
+                (format!("it never failed with {}", code), errors)
+            }
+            Failure::WrongMessage { message, errors } => {
+                (format!("no error said \"{}\"", message), errors)
+            }
+        };
+
+        write!(f, "{}; the errors were:", summary)?;
+        for error in errors {
+            match &error.code {
+                Some(code) => write!(f, "\n    error[{}]: {}", code, error.message)?,
+                None => write!(f, "\n    error: {}", error.message)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Finds the compile-fail snippets in a lesson's source.
+pub fn snippets(source: &str) -> Result<Vec<Snippet>, String> {
+    let mut snippets = Vec::new();
+    let mut lines = source.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let info = match line.trim().strip_prefix(OPEN) {
+            Some(info) => info,
+            None => continue,
+        };
+        let (codes, message) =
+            parse_info(info).map_err(|message| format!("line {}: {}", index + 1, message))?;
+
+        let mut code = Vec::new();
+        loop {
+            match lines.next() {
+                Some((_, line)) if line.trim() == CLOSE => break,
+                Some((number, line)) => match line.trim().strip_prefix("//") {
+                    Some(uncommented) => code.push(
+                        uncommented
+                            .strip_prefix(' ')
+                            .unwrap_or(uncommented)
+                            .to_string(),
+                    ),
+                    None => {
+                        return Err(format!(
+                            "line {}: everything inside a compile_fail block must be commented out",
+                            number + 1
+                        ))
+                    }
+                },
+                None => {
+                    return Err(format!(
+                        "line {}: compile_fail block is never closed",
+                        index + 1
+                    ))
+                }
+            }
+        }
+
+        snippets.push(Snippet {
+            line: index + 1,
+            codes,
+            message,
+            code,
+        });
+    }
+
+    Ok(snippets)
+}
+
+/// Parses `(E0499, E0502): message` from after `compile_fail`.
+fn parse_info(info: &str) -> Result<(Vec<String>, Option<String>), String> {
+    let info = info
+        .strip_prefix('(')
+        .ok_or("expected error codes, like compile_fail(E0382)")?;
+    let close = info
+        .find(')')
+        .ok_or("expected a `)` after the error codes")?;
+
+    let codes: Vec<String> = info[..close]
+        .split(',')
+        .map(|code| code.trim().to_string())
+        .filter(|code| !code.is_empty())
+        .collect();
+    if codes.is_empty() {
+        return Err(String::from("expected at least one error code"));
+    }
+
+    let rest = info[close + 1..].trim();
+    let message = match rest.strip_prefix(':') {
+        Some(message) => Some(message.trim().to_string()),
+        None if rest.is_empty() => None,
+        None => return Err(format!("expected `:` before the message, found `{}`", rest)),
+    };
+
+    Ok((codes, message))
+}
+
+/// The lesson's source with just this snippet uncommented. The fences become
+/// blank lines, so line numbers still match the original.
+pub fn splice(source: &str, snippet: &Snippet) -> String {
+    let mut spliced = String::new();
+    let first = snippet.line - 1;
+    let last = snippet.line + snippet.code.len();
+
+    for (index, line) in source.lines().enumerate() {
+        if index == first || index == last {
+            // A fence.
+        } else if index > first && index < last {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            spliced.push_str(indent);
+            spliced.push_str(&snippet.code[index - first - 1]);
+        } else {
+            spliced.push_str(line);
+        }
+        spliced.push('\n');
+    }
+
+    spliced
+}
+
+/// Compiles a lesson's `main.rs`, with its source replaced by `source`, in a
+/// scratch directory, and returns rustc's errors. The lesson's other files in
+/// `src`, like the modules `main.rs` declares, are copied in beside it.
+pub fn compile(lesson: &Lesson, source: &str, scratch: &Path) -> Result<Vec<Diagnostic>, Error> {
+    let io = |path: &Path| {
+        let path = path.to_path_buf();
+        move |error| Error::Io { path, error }
+    };
+
+    fs::create_dir_all(scratch).map_err(io(scratch))?;
+    let src = lesson.dir.join("src");
+    for entry in fs::read_dir(&src).map_err(io(&src))?.flatten() {
+        let path = entry.path();
+        if path.is_file() && entry.file_name() != "main.rs" {
+            fs::copy(&path, scratch.join(entry.file_name())).map_err(io(&path))?;
+        }
+    }
+    let main = scratch.join("main.rs");
+    fs::write(&main, source).map_err(io(&main))?;
+
+    let output = Command::new(rustc())
+        .arg("--edition")
+        .arg(&lesson.edition)
+        .args(["--crate-name", &lesson.name, "--crate-type", "bin"])
+        // Not just metadata: lints like unconditional_panic only run when
+        // rustc builds the MIR.
+        .args(["--error-format=json", "--emit=mir", "--out-dir", "."])
+        .arg("main.rs")
+        .current_dir(scratch)
+        .output()
+        .map_err(io(Path::new("rustc")))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Ok(Diagnostic::parse_all(&stderr)
+        .into_iter()
+        .filter(|diagnostic| diagnostic.is_error())
+        // The "aborting due to 2 previous errors" summary isn't an error itself.
+        .filter(|diagnostic| !diagnostic.message.starts_with("aborting due to"))
+        .collect())
+}
+
+/// Checks every snippet in a lesson.
+///
+/// Each snippet is compiled in its own directory under `scratch`.
+pub fn check(lesson: &Lesson, scratch: &Path) -> Result<Vec<Checked>, Error> {
+    let path = lesson.main();
+    let source = fs::read_to_string(&path).map_err(|error| Error::Io {
+        path: path.clone(),
+        error,
+    })?;
+    let snippets = snippets(&source).map_err(|message| Error::Annotation {
+        path: path.clone(),
+        message,
+    })?;
+    if snippets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let scratch = scratch.join(&lesson.name);
+    let baseline = compile(lesson, &source, &scratch.join("lesson"))?;
+    let mut results = Vec::new();
+
+    for snippet in snippets {
+        let result = if !baseline.is_empty() {
+            Err(Failure::LessonBroken(baseline.clone()))
+        } else {
+            let dir = scratch.join(format!("line-{}", snippet.line));
+            let errors = compile(lesson, &splice(&source, &snippet), &dir)?;
+            judge(&snippet, errors)
+        };
+        results.push(Checked { snippet, result });
+    }
+
+    Ok(results)
+}
+
+fn judge(snippet: &Snippet, errors: Vec<Diagnostic>) -> Result<(), Failure> {
+    if errors.is_empty() {
+        return Err(Failure::Compiled);
+    }
+
+    let has_code = |code: &str| errors.iter().any(|e| e.code.as_deref() == Some(code));
+    if let Some(code) = snippet.codes.iter().find(|code| !has_code(code)) {
+        return Err(Failure::MissingCode {
+            code: code.clone(),
+            errors,
+        });
+    }
+
+    if let Some(message) = &snippet.message {
+        let said_it = errors.iter().any(|error| {
+            let code = error.code.as_ref();
+            code.is_some_and(|code| snippet.codes.contains(code))
+                && error.message.starts_with(message.as_str())
+        });
+        if !said_it {
+            return Err(Failure::WrongMessage {
+                message: message.clone(),
+                errors,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn rustc() -> PathBuf {
+    env::var_os("RUSTC")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("rustc"))
+}
@@ -0,0 +1,97 @@
+//! Compiler diagnostics, as rustc prints them with `--error-format=json`.
+
+use crate::json::Json;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// `"error"`, `"warning"`, `"note"`, and so on.
+    pub level: String,
+    /// The error code, like `E0382`, if there is one. Lints use their name.
+    pub code: Option<String>,
+    /// The primary message, like "use of moved value: `name`".
+    pub message: String,
+    /// The message as rustc would have printed it to a terminal.
+    pub rendered: Option<String>,
+    pub spans: Vec<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub file: String,
+    /// 1-based, like the compiler prints them.
+    pub line: usize,
+    pub column: usize,
+    pub primary: bool,
+    pub label: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn from_json(json: &Json) -> Option<Diagnostic> {
+        let spans = json
+            .get("spans")?
+            .items()
+            .iter()
+            .filter_map(|span| {
+                Some(Span {
+                    file: span.get("file_name")?.as_str()?.to_string(),
+                    line: span.get("line_start")?.as_u64()? as usize,
+                    column: span.get("column_start")?.as_u64()? as usize,
+                    primary: span.get("is_primary")?.as_bool()?,
+                    label: span.get("label").and_then(Json::as_str).map(String::from),
+                })
+            })
+            .collect();
+
+        Some(Diagnostic {
+            level: json.get("level")?.as_str()?.to_string(),
+            code: json
+                .get("code")
+                .and_then(|code| code.get("code"))
+                .and_then(Json::as_str)
+                .map(String::from),
+            message: json.get("message")?.as_str()?.to_string(),
+            rendered: json
+                .get("rendered")
+                .and_then(Json::as_str)
+                .map(String::from),
+            spans,
+        })
+    }
+
+    /// Reads every diagnostic out of rustc's stderr. Lines that aren't JSON
+    /// diagnostics (rustc can still print those, e.g. when it ICEs) are skipped.
+    pub fn parse_all(stderr: &str) -> Vec<Diagnostic> {
+        stderr
+            .lines()
+            .filter_map(|line| Json::parse(line).ok())
+            .filter_map(|json| Diagnostic::from_json(&json))
+            .collect()
+    }
+
+    /// Reads the diagnostics for one target out of the stdout of
+    /// `cargo build --message-format=json`, which wraps each of rustc's in a
+    /// `compiler-message`.
+    pub fn parse_cargo(stdout: &str, target: &str) -> Vec<Diagnostic> {
+        stdout
+            .lines()
+            .filter_map(|line| Json::parse(line).ok())
+            .filter(|json| {
+                json.get("reason").and_then(Json::as_str) == Some("compiler-message")
+                    && json
+                        .get("target")
+                        .and_then(|t| t.get("name"))
+                        .and_then(Json::as_str)
+                        == Some(target)
+            })
+            .filter_map(|json| Diagnostic::from_json(json.get("message")?))
+            .collect()
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.level == "error"
+    }
+
+    pub fn primary_span(&self) -> Option<&Span> {
+        self.spans.iter().find(|span| span.primary)
+    }
+}
@@ -0,0 +1,187 @@
+//! Compiling and running a snippet of code the way play.rust-lang.org's
+//! `execute` endpoint does, but with the local toolchain.
+//!
+//! Requests and responses have the playground's JSON shape, so anything that
+//! can talk to the playground can talk to `club playground`:
+//!
+//! ```json
+//! {"channel": "stable", "mode": "debug", "edition": "2018",
+//!  "crateType": "bin", "tests": false, "code": "fn main() {}"}
+//!
+//! {"success": true, "stdout": "", "stderr": "..."}
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::compile_fail::rustc;
+use crate::json::Json;
+use crate::sandbox::{self, Limits, Status};
+use crate::Error;
+
+const EDITIONS: &[&str] = &["2015", "2018", "2021", "2024"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Debug,
+    Release,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// `stable`, `beta` or `nightly`. There is only the one local toolchain,
+    /// so this is accepted and otherwise ignored.
+    pub channel: String,
+    pub mode: Mode,
+    pub edition: String,
+    /// Whether to build a test harness and run the tests instead of `main`.
+    pub tests: bool,
+    /// Whether to ask for a backtrace when the code panics.
+    pub backtrace: bool,
+    pub code: String,
+}
+
+impl Request {
+    /// Reads a request, filling in the playground's defaults for anything
+    /// left out except the code.
+    pub fn from_json(json: &Json) -> Result<Request, String> {
+        let string = |key: &str, default: &str| match json.get(key) {
+            None | Some(Json::Null) => Ok(default.to_string()),
+            Some(value) => value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| format!("`{}` should be a string", key)),
+        };
+        let boolean = |key: &str| match json.get(key) {
+            None | Some(Json::Null) => Ok(false),
+            Some(value) => value
+                .as_bool()
+                .ok_or_else(|| format!("`{}` should be true or false", key)),
+        };
+
+        let code = match json.get("code").and_then(Json::as_str) {
+            Some(code) => code.to_string(),
+            None => return Err(String::from("`code` is missing")),
+        };
+
+        let channel = string("channel", "stable")?;
+        if !["stable", "beta", "nightly"].contains(&channel.as_str()) {
+            return Err(format!("unknown channel `{}`", channel));
+        }
+
+        let mode = match string("mode", "debug")?.as_str() {
+            "debug" => Mode::Debug,
+            "release" => Mode::Release,
+            other => return Err(format!("unknown mode `{}`", other)),
+        };
+
+        let edition = string("edition", "2021")?;
+        if !EDITIONS.contains(&edition.as_str()) {
+            return Err(format!("unknown edition `{}`", edition));
+        }
+
+        let crate_type = string("crateType", "bin")?;
+        if crate_type != "bin" {
+            return Err(format!(
+                "crate type `{}` can't be run, only `bin` can",
+                crate_type
+            ));
+        }
+
+        Ok(Request {
+            channel,
+            mode,
+            edition,
+            tests: boolean("tests")?,
+            backtrace: boolean("backtrace")?,
+            code,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// Whether the code compiled and then exited with status 0.
+    pub success: bool,
+    pub stdout: String,
+    /// The compiler's messages, followed by whatever the program printed.
+    pub stderr: String,
+}
+
+impl Response {
+    pub fn to_json(&self) -> Json {
+        let mut object = BTreeMap::new();
+        object.insert(String::from("success"), Json::from(self.success));
+        object.insert(String::from("stdout"), Json::from(self.stdout.as_str()));
+        object.insert(String::from("stderr"), Json::from(self.stderr.as_str()));
+        Json::Object(object)
+    }
+}
+
+/// Compiles a request's code in `scratch`, which should be an empty directory
+/// of its own, then runs it in the [`sandbox`].
+pub fn execute(request: &Request, scratch: &Path) -> Result<Response, Error> {
+    let io = |path: &Path| {
+        let path = path.to_path_buf();
+        move |error| Error::Io { path, error }
+    };
+
+    fs::create_dir_all(scratch).map_err(io(scratch))?;
+    let main = scratch.join("main.rs");
+    fs::write(&main, &request.code).map_err(io(&main))?;
+
+    let edition = ["--edition", request.edition.as_str()];
+    let mut args = vec![&edition[..]];
+    args.push(&["--crate-name", "playground", "--crate-type", "bin"]);
+    args.push(&["--color", "never", "-o", "playground"]);
+    args.push(match request.mode {
+        Mode::Debug => &["-C", "debuginfo=1"],
+        Mode::Release => &["-C", "opt-level=3"],
+    });
+    if request.tests {
+        args.push(&["--test"]);
+    }
+    args.push(&["main.rs"]);
+
+    // The code is untrusted even before it runs: macros and constants can
+    // keep the compiler busy for as long as they like.
+    let compiled = sandbox::run_in(
+        scratch,
+        &rustc(),
+        &args.concat(),
+        &sandbox::toolchain_env(),
+        &Limits::compiler(),
+    )?;
+
+    let mut stderr = compiled.stderr;
+    if compiled.status != Status::Exited(0) {
+        if let Status::Exceeded(_) | Status::Signalled(_) = compiled.status {
+            stderr.push_str(&format!("\nThe compiler {}.\n", compiled.status));
+        }
+        return Ok(Response {
+            success: false,
+            stdout: String::new(),
+            stderr,
+        });
+    }
+
+    let backtrace = if request.backtrace { "1" } else { "0" };
+    let binary = scratch.join("playground");
+    let ran = sandbox::run(
+        &binary,
+        &[("RUST_BACKTRACE", backtrace)],
+        &Limits::default(),
+    )?;
+
+    stderr.push_str(&ran.stderr);
+    if let Status::Exceeded(_) | Status::Signalled(_) = ran.status {
+        // Like the real playground, say why the program stopped.
+        stderr.push_str(&format!("\nThe program {}.\n", ran.status));
+    }
+    Ok(Response {
+        success: ran.status == Status::Exited(0),
+        stdout: ran.stdout,
+        stderr,
+    })
+}
@@ -0,0 +1,269 @@
+//! Exercises: small functions from the lessons for attendees to write
+//! themselves, checked by tests they don't get to see.
+//!
+//! A lesson lists its exercises in its manifest, and each one is three files
+//! in the lesson's `exercises` directory:
+//!
+//! * `exercises/<name>.rs` is the student's copy. It is a complete program
+//!   whose functions have `todo!()` for bodies, and it's the file to edit.
+//! * `exercises/tests/<name>.rs` is the hidden test suite: `#[test]` functions
+//!   that can use anything in the student's copy.
+//! * `exercises/solutions/<name>.rs` is the instructor's copy, which has to
+//!   pass the hidden tests.
+//!
+//! Checking an exercise compiles the two together as a test harness, with the
+//! student's copy `include!`d so that compiler errors point at the file being
+//! edited, then runs the tests in the [`sandbox`](crate::sandbox).
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::compile_fail::rustc;
+use crate::sandbox::{self, Limits, Status};
+use crate::state;
+use crate::{Error, Lesson, Workspace};
+
+/// The message `todo!()` panics with.
+const TODO: &str = "not yet implemented";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exercise {
+    pub lesson: String,
+    pub name: String,
+    pub edition: String,
+    /// The student's copy.
+    pub path: PathBuf,
+    /// The hidden tests.
+    pub tests: PathBuf,
+    /// The instructor's copy.
+    pub solution: PathBuf,
+}
+
+impl Exercise {
+    fn new(lesson: &Lesson, name: &str) -> Exercise {
+        let dir = lesson.dir.join("exercises");
+        Exercise {
+            lesson: lesson.name.clone(),
+            name: name.to_string(),
+            edition: lesson.edition.clone(),
+            path: dir.join(format!("{}.rs", name)),
+            tests: dir.join("tests").join(format!("{}.rs", name)),
+            solution: dir.join("solutions").join(format!("{}.rs", name)),
+        }
+    }
+
+    /// The same exercise with the instructor's copy in place of the
+    /// student's, for checking that the hidden tests can be passed.
+    pub fn solved(&self) -> Exercise {
+        Exercise {
+            path: self.solution.clone(),
+            ..self.clone()
+        }
+    }
+
+    /// How the exercise is known on the command line and in the progress file,
+    /// like `borrowing/append_a`.
+    pub fn id(&self) -> String {
+        format!("{}/{}", self.lesson, self.name)
+    }
+}
+
+/// Every exercise in the workspace, lesson by lesson in presentation order.
+pub fn all(workspace: &Workspace) -> Vec<Exercise> {
+    workspace
+        .lessons
+        .iter()
+        .flat_map(|lesson| {
+            lesson
+                .exercises
+                .iter()
+                .map(move |name| Exercise::new(lesson, name))
+        })
+        .collect()
+}
+
+/// Finds an exercise by its id, or just its name if that's unambiguous.
+pub fn find<'a>(exercises: &'a [Exercise], name: &str) -> Option<&'a Exercise> {
+    let mut matches = exercises
+        .iter()
+        .filter(|exercise| exercise.id() == name || exercise.name == name);
+    match (matches.next(), matches.next()) {
+        (Some(exercise), None) => Some(exercise),
+        _ => exercises.iter().find(|exercise| exercise.id() == name),
+    }
+}
+
+/// How an attempt at an exercise went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attempt {
+    /// Every hidden test passed.
+    Passed,
+    /// It compiles, but some functions are still `todo!()`.
+    Unfinished(String),
+    /// It doesn't compile. Holds the compiler's messages.
+    CompileError(String),
+    /// Some tests failed. Holds the test harness's output.
+    Failed(String),
+    /// The tests went over one of the sandbox's limits, like an infinite loop
+    /// would.
+    Stopped(Status, String),
+}
+
+impl Attempt {
+    pub fn passed(&self) -> bool {
+        *self == Attempt::Passed
+    }
+
+    /// What the compiler or the tests had to say, if anything.
+    pub fn output(&self) -> &str {
+        match self {
+            Attempt::Passed => "",
+            Attempt::Unfinished(output)
+            | Attempt::CompileError(output)
+            | Attempt::Failed(output)
+            | Attempt::Stopped(_, output) => output,
+        }
+    }
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Attempt::Passed => write!(f, "all tests passed"),
+            Attempt::Unfinished(_) => write!(f, "there are still `todo!()`s to fill in"),
+            Attempt::CompileError(_) => write!(f, "it doesn't compile yet"),
+            Attempt::Failed(_) => write!(f, "some tests failed"),
+            Attempt::Stopped(status, _) => write!(f, "the tests {}", status),
+        }
+    }
+}
+
+/// Compiles the student's copy of `exercise` with its hidden tests in
+/// `scratch`, and runs the tests.
+pub fn check(exercise: &Exercise, scratch: &Path) -> Result<Attempt, Error> {
+    let io = |path: &Path| {
+        let path = path.to_path_buf();
+        move |error| Error::Io { path, error }
+    };
+
+    for path in &[&exercise.path, &exercise.tests] {
+        if !path.is_file() {
+            return Err(Error::Io {
+                path: path.to_path_buf(),
+                error: std::io::ErrorKind::NotFound.into(),
+            });
+        }
+    }
+
+    fs::create_dir_all(scratch).map_err(io(scratch))?;
+    let harness = scratch.join("harness.rs");
+    fs::write(&harness, harness_source(exercise)).map_err(io(&harness))?;
+
+    // The student's copy is only as trusted as a playground snippet, and
+    // compiling it can take just as long.
+    let compiled = sandbox::run_in(
+        scratch,
+        &rustc(),
+        &[
+            "--edition",
+            &exercise.edition,
+            "--crate-name",
+            &exercise.name,
+            "--test",
+            "--color",
+            "never",
+            "-o",
+            "harness",
+            // Leftover code from the lesson shouldn't drown out the real
+            // errors.
+            "-A",
+            "dead_code",
+            "-A",
+            "unused",
+            "harness.rs",
+        ],
+        &sandbox::toolchain_env(),
+        &Limits::compiler(),
+    )?;
+    if compiled.status != Status::Exited(0) {
+        let mut stderr = compiled.stderr;
+        if let Status::Exceeded(_) | Status::Signalled(_) = compiled.status {
+            stderr.push_str(&format!("\nThe compiler {}.\n", compiled.status));
+        }
+        return Ok(Attempt::CompileError(stderr));
+    }
+
+    // One test at a time, so that the harness doesn't need threads.
+    let ran = sandbox::run(
+        &scratch.join("harness"),
+        &[("RUST_TEST_THREADS", "1")],
+        &Limits::default(),
+    )?;
+    let output = format!("{}{}", ran.stdout, ran.stderr);
+    Ok(match ran.status {
+        Status::Exited(0) => Attempt::Passed,
+        Status::Exited(_) if output.contains(TODO) => Attempt::Unfinished(output),
+        Status::Exited(_) => Attempt::Failed(output),
+        status => Attempt::Stopped(status, output),
+    })
+}
+
+/// The student's copy with the hidden tests in a module after it, the way
+/// tests usually sit at the bottom of a file.
+fn harness_source(exercise: &Exercise) -> String {
+    format!(
+        "include!({:?});\n\n#[cfg(test)]\nmod hidden_tests {{\n    use super::*;\n\n    include!({:?});\n}}\n",
+        absolute(&exercise.path),
+        absolute(&exercise.tests)
+    )
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Which exercises have been passed, kept one id per line in
+/// `.club/progress` at the root of the workspace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Progress {
+    pub done: Vec<String>,
+}
+
+impl Progress {
+    /// Reads the progress file. No file means nothing is done yet.
+    pub fn load(root: &Path) -> Progress {
+        let done = fs::read_to_string(state::progress(root))
+            .map(|text| {
+                text.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Progress { done }
+    }
+
+    pub fn is_done(&self, exercise: &Exercise) -> bool {
+        self.done.contains(&exercise.id())
+    }
+
+    /// Marks an exercise as done and saves the progress file.
+    pub fn complete(&mut self, root: &Path, exercise: &Exercise) -> Result<(), Error> {
+        if self.is_done(exercise) {
+            return Ok(());
+        }
+        self.done.push(exercise.id());
+
+        let path = state::progress(root);
+        let mut text = self.done.join("\n");
+        text.push('\n');
+        state::save(&path, &text).map_err(|error| Error::Io { path, error })
+    }
+
+    /// The first exercise that isn't done yet.
+    pub fn next<'a>(&self, exercises: &'a [Exercise]) -> Option<&'a Exercise> {
+        exercises.iter().find(|exercise| !self.is_done(exercise))
+    }
+}
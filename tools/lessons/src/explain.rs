@@ -0,0 +1,169 @@
+//! Pointing compiler errors back at the part of a lesson that explains them.
+//!
+//! rustc's message for E0382 is "use of moved value: `name`", which doesn't
+//! help much if you haven't met ownership yet, but the lesson has a paragraph
+//! about exactly that. Lessons tag such paragraphs with a comment just above
+//! them:
+//!
+//! ```text
+//! // EXPLAIN: moves
+//! // There's something odd about `name`, though. Notice that if you attempt
+//! // to call say_hello_to_name again, it will throw a compiler error. ...
+//! ```
+//!
+//! and map error codes to the tags in their manifest:
+//!
+//! ```toml
+//! [package.metadata.club.explain]
+//! E0382 = "moves"
+//! ```
+//!
+//! A section is the run of comment lines after the tag, up to the first line
+//! that isn't one. Tags are left out of the slides.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::diagnostic::Diagnostic;
+use crate::source::{self, Kind};
+use crate::{Error, Lesson, Workspace};
+
+const TAG: &str = "// EXPLAIN:";
+
+/// Whether a line is a tag, which is not part of the lesson.
+pub fn is_tag(line: &str) -> bool {
+    line.trim().starts_with(TAG)
+}
+
+/// A tagged section of a lesson.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub lesson: String,
+    pub tag: String,
+    pub path: PathBuf,
+    /// The 1-based line the section's text starts on.
+    pub line: usize,
+    /// The section's text, with the `//` taken off.
+    pub lines: Vec<String>,
+}
+
+/// Every tagged section in a source file, as `(tag, line, text)`.
+pub fn sections(source: &str) -> Vec<(String, usize, Vec<String>)> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut sections = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let tag = match line.trim().strip_prefix(TAG) {
+            Some(tag) => tag.trim().to_string(),
+            None => continue,
+        };
+        // Reuse the slides' idea of a block of prose, so that a fence ends the
+        // section just like it ends a slide.
+        let rest = lines[index + 1..].join("\n");
+        let text = source::blocks(&rest)
+            .into_iter()
+            .next()
+            .filter(|block| block.kind == Kind::Prose && block.line == 1)
+            .map(|block| block.lines)
+            .unwrap_or_default();
+        sections.push((tag, index + 2, text));
+    }
+    sections
+}
+
+/// The explanations of every lesson in the workspace.
+#[derive(Debug, Clone, Default)]
+pub struct Explainer {
+    /// Error codes and the sections that explain them, in lesson order.
+    entries: Vec<(String, Section)>,
+}
+
+impl Explainer {
+    /// Reads every lesson's explanations, and checks that the tags in its
+    /// manifest are all in its source.
+    pub fn load(workspace: &Workspace) -> Result<Explainer, Error> {
+        let mut entries = Vec::new();
+
+        for lesson in &workspace.lessons {
+            if lesson.explanations.is_empty() {
+                continue;
+            }
+            let path = lesson.main();
+            let source = fs::read_to_string(&path).map_err(|error| Error::Io {
+                path: path.clone(),
+                error,
+            })?;
+            let sections = sections(&source);
+
+            for (code, tag) in &lesson.explanations {
+                let (_, line, lines) = sections
+                    .iter()
+                    .find(|(name, _, _)| name == tag)
+                    .ok_or_else(|| Error::Annotation {
+                        path: path.clone(),
+                        message: format!(
+                            "{} is explained by `{}`, but there's no `{} {}` tag",
+                            code, tag, TAG, tag
+                        ),
+                    })?;
+                entries.push((
+                    code.clone(),
+                    Section {
+                        lesson: lesson.name.clone(),
+                        tag: tag.clone(),
+                        path: path.clone(),
+                        line: *line,
+                        lines: lines.clone(),
+                    },
+                ));
+            }
+        }
+
+        Ok(Explainer { entries })
+    }
+
+    /// The section that explains an error code, preferring the lesson the
+    /// error happened in, then the lessons in order.
+    pub fn explain(&self, code: &str, lesson: Option<&str>) -> Option<&Section> {
+        let mut sections = self
+            .entries
+            .iter()
+            .filter(|(entry, _)| entry == code)
+            .map(|(_, section)| section);
+        let first = sections.next()?;
+        if Some(first.lesson.as_str()) == lesson {
+            return Some(first);
+        }
+        Some(
+            sections
+                .find(|section| Some(section.lesson.as_str()) == lesson)
+                .unwrap_or(first),
+        )
+    }
+
+    /// Every section that explains an error code.
+    pub fn all<'a>(&'a self, code: &'a str) -> impl Iterator<Item = &'a Section> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(entry, _)| entry == code)
+            .map(|(_, section)| section)
+    }
+}
+
+/// Builds a lesson with cargo and returns the compiler's diagnostics for it.
+pub fn build(root: &Path, lesson: &Lesson) -> Result<Vec<Diagnostic>, Error> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+    let output = Command::new(&cargo)
+        .args(["build", "--quiet", "--message-format=json", "--package"])
+        .arg(&lesson.name)
+        .current_dir(root)
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|error| Error::Io {
+            path: PathBuf::from(&cargo),
+            error,
+        })?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(Diagnostic::parse_cargo(&stdout, &lesson.name))
+}
@@ -0,0 +1,467 @@
+//! Loading and checking the lesson manifests of the workspace.
+//!
+//! Every lesson crate describes itself in a `[package.metadata.club]` table in
+//! its `Cargo.toml`:
+//!
+//! ```toml
+//! [package.metadata.club]
+//! title = "Data Types"
+//! position = 2
+//! prerequisites = ["hello_world"]
+//! concepts = ["integers", "char", "&str", "tuples", "arrays"]
+//! outcome = "panic"
+//! panic = "is not a char boundary"
+//! ```
+//!
+//! `outcome` is one of `"success"` (exit cleanly), `"panic"` (panic on purpose,
+//! optionally with a `panic` message to look for), or `"output"` (exit cleanly
+//! and print exactly the lines in `stdout`). An optional `paths` list names
+//! other ways through the lesson, which the [`runner`] takes by setting
+//! `CLUB_PATH`, like the safe path `data_types` has around its panic. A path
+//! must exit cleanly, unless a `[package.metadata.club.path.<name>]` table,
+//! with the same keys as `outcome`, says otherwise. An optional
+//! `[package.metadata.club.release]` table, with the same `outcome`, `panic`
+//! and `stdout` keys, says what the lesson does when it's built with
+//! `--release` instead, for lessons like `overflow` where that's different. An
+//! optional `exercises` list names the lesson's [`exercise`]s, and an optional
+//! `[package.metadata.club.explain]` table maps error codes to the sections of
+//! the lesson that [`explain`] them. Workspace members without the table, such
+//! as the tools, are not lessons.
+
+pub mod compile_fail;
+pub mod diagnostic;
+pub mod execute;
+pub mod exercise;
+pub mod explain;
+pub mod json;
+pub mod ownership;
+pub mod runner;
+pub mod sandbox;
+pub mod snapshot;
+pub mod solution;
+pub mod source;
+pub mod state;
+pub mod toml;
+
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::toml::{Table, Value};
+
+const TABLE: &str = "package.metadata.club";
+
+/// What a lesson is expected to do when it runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Exit with status 0.
+    Success,
+    /// Panic on purpose, like `data_types` does when slicing "你好". If there is
+    /// a message, the panic must mention it.
+    Panic { message: Option<String> },
+    /// Exit with status 0 after printing exactly these lines.
+    Output { stdout: Vec<String> },
+}
+
+/// A lesson crate and what its manifest says about it.
+#[derive(Debug, Clone)]
+pub struct Lesson {
+    /// The package name, which is what `cargo run -p` wants.
+    pub name: String,
+    pub dir: PathBuf,
+    /// The Rust edition the lesson is written in.
+    pub edition: String,
+    pub title: String,
+    pub position: u32,
+    pub prerequisites: Vec<String>,
+    pub concepts: Vec<String>,
+    pub outcome: Outcome,
+    /// What the lesson does when it's built with `--release`, if the runner
+    /// should build it that way too. See [`runner::run_release`].
+    pub release: Option<Outcome>,
+    /// Other ways through the lesson. See [`runner::run_path`].
+    pub paths: Vec<String>,
+    /// What the paths that don't just exit cleanly do instead.
+    pub path_outcomes: BTreeMap<String, Outcome>,
+    /// The exercises that go with the lesson, in the order to do them. See
+    /// [`exercise`].
+    pub exercises: Vec<String>,
+    /// Error codes, and the tag of the section of the lesson that explains
+    /// them. See [`explain`].
+    pub explanations: BTreeMap<String, String>,
+}
+
+/// All the lessons in a workspace, sorted by position.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub lessons: Vec<Lesson>,
+}
+
+impl Workspace {
+    /// Finds the workspace this is run from, and loads and validates it.
+    pub fn find() -> Result<Workspace, Error> {
+        Workspace::load(&find_root()?)
+    }
+
+    /// Loads every lesson manifest under `root` and validates them together.
+    pub fn load(root: &Path) -> Result<Workspace, Error> {
+        let manifest = read(&root.join("Cargo.toml"))?;
+        let mut lessons = Vec::new();
+
+        for member in members(&manifest) {
+            let dir = root.join(&member);
+            if let Some(lesson) = Lesson::load(&dir)? {
+                lessons.push(lesson);
+            }
+        }
+
+        let problems = validate(&lessons);
+        if !problems.is_empty() {
+            return Err(Error::Invalid(problems));
+        }
+
+        lessons.sort_by_key(|lesson| lesson.position);
+        Ok(Workspace {
+            root: root.to_path_buf(),
+            lessons,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Lesson> {
+        self.lessons.iter().find(|lesson| lesson.name == name)
+    }
+
+    /// The index of a lesson in presentation order.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.lessons.iter().position(|lesson| lesson.name == name)
+    }
+
+    /// A path to show the user, relative to the root if it's in the
+    /// workspace.
+    pub fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+impl Lesson {
+    /// Reads the lesson manifest of the crate in `dir`, or `None` if the crate
+    /// is not a lesson.
+    pub fn load(dir: &Path) -> Result<Option<Lesson>, Error> {
+        let path = dir.join("Cargo.toml");
+        let manifest = read(&path)?;
+        let bad = |message: String| Error::Manifest {
+            path: path.clone(),
+            message,
+        };
+
+        let package = toml::table(&manifest, "package")
+            .map_err(&bad)?
+            .unwrap_or_default();
+        let table = match toml::table(&manifest, TABLE).map_err(&bad)? {
+            Some(table) => table,
+            None => return Ok(None),
+        };
+
+        let name = string(&package, "name")
+            .map_err(&bad)?
+            .ok_or_else(|| bad(String::from("`package.name` is missing")))?;
+        let edition = string(&package, "edition")
+            .map_err(&bad)?
+            .unwrap_or_else(|| String::from("2015"));
+        let title = string(&table, "title")
+            .map_err(&bad)?
+            .ok_or_else(|| bad(format!("`{}.title` is missing", TABLE)))?;
+        let position = match table.get("position") {
+            Some(Value::Integer(position)) if *position > 0 => u32::try_from(*position)
+                .map_err(|_| bad(format!("`position` {} is too big", position)))?,
+            Some(_) => return Err(bad(String::from("`position` must be a positive integer"))),
+            None => return Err(bad(format!("`{}.position` is missing", TABLE))),
+        };
+        let prerequisites = strings(&table, "prerequisites").map_err(&bad)?;
+        let concepts = strings(&table, "concepts").map_err(&bad)?;
+        let paths = strings(&table, "paths").map_err(&bad)?;
+        let exercises = strings(&table, "exercises").map_err(&bad)?;
+        let explanations = toml::table(&manifest, &format!("{}.explain", TABLE))
+            .map_err(&bad)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(code, tag)| match tag {
+                Value::String(tag) => Ok((code, tag)),
+                _ => Err(bad(format!("`explain.{}` must be a string", code))),
+            })
+            .collect::<Result<_, _>>()?;
+
+        let outcome = parse_outcome(&table).map_err(&bad)?;
+        let release = toml::table(&manifest, &format!("{}.release", TABLE))
+            .map_err(&bad)?
+            .map(|release| parse_outcome(&release))
+            .transpose()
+            .map_err(|message| bad(format!("in `{}.release`: {}", TABLE, message)))?;
+        let mut path_outcomes = BTreeMap::new();
+        for path in &paths {
+            let key = format!("{}.path.{}", TABLE, path);
+            if let Some(table) = toml::table(&manifest, &key).map_err(&bad)? {
+                let outcome = parse_outcome(&table)
+                    .map_err(|message| bad(format!("in `{}`: {}", key, message)))?;
+                path_outcomes.insert(path.clone(), outcome);
+            }
+        }
+
+        Ok(Some(Lesson {
+            name,
+            dir: dir.to_path_buf(),
+            edition,
+            title,
+            position,
+            prerequisites,
+            concepts,
+            outcome,
+            release,
+            paths,
+            path_outcomes,
+            exercises,
+            explanations,
+        }))
+    }
+
+    /// The lesson's source file.
+    pub fn main(&self) -> PathBuf {
+        self.dir.join("src").join("main.rs")
+    }
+}
+
+/// Something wrong with the lessons as a whole, rather than with one manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// A lesson lists a prerequisite that isn't a lesson.
+    MissingPrerequisite { lesson: String, missing: String },
+    /// The lessons require each other in a loop. The first lesson is repeated
+    /// at the end, so `a -> b -> a` is `["a", "b", "a"]`.
+    Cycle(Vec<String>),
+    /// More than one lesson claims the same position.
+    DuplicatePosition { position: u32, lessons: Vec<String> },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::MissingPrerequisite { lesson, missing } => write!(
+                f,
+                "`{}` requires `{}`, but there is no lesson called that",
+                lesson, missing
+            ),
+            Problem::Cycle(lessons) => write!(
+                f,
+                "lessons require each other in a cycle: {}",
+                lessons.join(" -> ")
+            ),
+            Problem::DuplicatePosition { position, lessons } => write!(
+                f,
+                "lessons {} all have position {}",
+                lessons.join(", "),
+                position
+            ),
+        }
+    }
+}
+
+/// Checks that prerequisites exist and don't form cycles, and that no two
+/// lessons share a position.
+pub fn validate(lessons: &[Lesson]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let by_name: HashMap<&str, &Lesson> = lessons
+        .iter()
+        .map(|lesson| (lesson.name.as_str(), lesson))
+        .collect();
+
+    for lesson in lessons {
+        for prerequisite in &lesson.prerequisites {
+            if !by_name.contains_key(prerequisite.as_str()) {
+                problems.push(Problem::MissingPrerequisite {
+                    lesson: lesson.name.clone(),
+                    missing: prerequisite.clone(),
+                });
+            }
+        }
+    }
+
+    let mut positions: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    for lesson in lessons {
+        positions
+            .entry(lesson.position)
+            .or_default()
+            .push(lesson.name.clone());
+    }
+    for (position, names) in positions {
+        if names.len() > 1 {
+            problems.push(Problem::DuplicatePosition {
+                position,
+                lessons: names,
+            });
+        }
+    }
+
+    // A depth-first search over the prerequisites. Anything we find on the
+    // current path again is a cycle.
+    let mut done: Vec<&str> = Vec::new();
+    for lesson in lessons {
+        let mut path = Vec::new();
+        find_cycles(lesson, &by_name, &mut path, &mut done, &mut problems);
+    }
+
+    problems
+}
+
+fn find_cycles<'a>(
+    lesson: &'a Lesson,
+    by_name: &HashMap<&str, &'a Lesson>,
+    path: &mut Vec<&'a str>,
+    done: &mut Vec<&'a str>,
+    problems: &mut Vec<Problem>,
+) {
+    let name = lesson.name.as_str();
+    if done.contains(&name) {
+        return;
+    }
+    if let Some(start) = path.iter().position(|&on_path| on_path == name) {
+        let mut cycle: Vec<String> = path[start..].iter().map(|s| s.to_string()).collect();
+        cycle.push(name.to_string());
+        problems.push(Problem::Cycle(cycle));
+        return;
+    }
+
+    path.push(name);
+    for prerequisite in &lesson.prerequisites {
+        if let Some(next) = by_name.get(prerequisite.as_str()) {
+            find_cycles(next, by_name, path, done, problems);
+        }
+    }
+    path.pop();
+    done.push(name);
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// A manifest that couldn't be read as a lesson.
+    Manifest {
+        path: PathBuf,
+        message: String,
+    },
+    /// A lesson's source has a malformed annotation in its comments.
+    Annotation {
+        path: PathBuf,
+        message: String,
+    },
+    /// Every manifest was fine on its own, but not together.
+    Invalid(Vec<Problem>),
+    /// Cargo couldn't build this lesson.
+    Build(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            Error::Manifest { path, message } | Error::Annotation { path, message } => {
+                write!(f, "{}: {}", path.display(), message)
+            }
+            Error::Invalid(problems) => {
+                write!(f, "the lesson manifests have problems:")?;
+                for problem in problems {
+                    write!(f, "\n    {}", problem)?;
+                }
+                Ok(())
+            }
+            Error::Build(lesson) => write!(f, "could not build `{}`", lesson),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Walks up from the current directory looking for the workspace `Cargo.toml`,
+/// falling back to the directory this crate was built from.
+pub fn find_root() -> Result<PathBuf, Error> {
+    let cwd = env::current_dir().map_err(|error| Error::Io {
+        path: PathBuf::from("."),
+        error,
+    })?;
+
+    for dir in cwd.ancestors() {
+        let manifest = dir.join("Cargo.toml");
+        if manifest.is_file() && read(&manifest)?.contains("[workspace]") {
+            return Ok(dir.to_path_buf());
+        }
+    }
+
+    let built_from = Path::new(env!("CARGO_MANIFEST_DIR"));
+    Ok(built_from
+        .ancestors()
+        .nth(2)
+        .unwrap_or(built_from)
+        .to_path_buf())
+}
+
+/// The `members` array of the `[workspace]` table.
+fn members(manifest: &str) -> Vec<String> {
+    match toml::table(manifest, "workspace") {
+        Ok(Some(workspace)) => match workspace.get("members") {
+            Some(Value::Array(members)) => members.clone(),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|error| Error::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// The `outcome` of a table, and the `panic` or `stdout` that goes with it.
+fn parse_outcome(table: &Table) -> Result<Outcome, String> {
+    match string(table, "outcome")?.as_deref() {
+        None | Some("success") => Ok(Outcome::Success),
+        Some("panic") => Ok(Outcome::Panic {
+            message: string(table, "panic")?,
+        }),
+        Some("output") => Ok(Outcome::Output {
+            stdout: strings(table, "stdout")?,
+        }),
+        Some(other) => Err(format!(
+            "unknown outcome \"{}\" (expected \"success\", \"panic\" or \"output\")",
+            other
+        )),
+    }
+}
+
+fn string(table: &Table, key: &str) -> Result<Option<String>, String> {
+    match table.get(key) {
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(format!("`{}` must be a string", key)),
+        None => Ok(None),
+    }
+}
+
+fn strings(table: &Table, key: &str) -> Result<Vec<String>, String> {
+    match table.get(key) {
+        Some(Value::Array(values)) => Ok(values.clone()),
+        Some(_) => Err(format!("`{}` must be an array of strings", key)),
+        None => Ok(Vec::new()),
+    }
+}
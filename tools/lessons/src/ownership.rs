@@ -0,0 +1,492 @@
+//! Recorded ownership traces, and "who owns what" diagrams of them.
+//!
+//! A trace is a lesson's `ownership.trace`: what happens to its values, one
+//! event per line, each with the line of the lesson it happens on.
+//!
+//! ```text
+//! # `name` moves into say_hello_to_name, which drops it when it returns.
+//! 20: new name "Gabe"
+//! 24: move name say_hello_to_name::name
+//! 98: drop say_hello_to_name::name
+//! ```
+//!
+//! The events are:
+//!
+//! - `new <owner> <value>`: `owner` comes to own a new value.
+//! - `borrow <borrower> <owner>`: `borrower` gets a `&` to `owner`'s value.
+//! - `borrow_mut <borrower> <owner>`: the same, with a `&mut`.
+//! - `return <borrower>`: the borrow ends.
+//! - `change <who> <value>`: the value changes, through its owner or a `&mut`.
+//! - `move <from> <to>`: the value moves to a new owner.
+//! - `drop <owner>`: the owner goes out of scope, and its value is dropped.
+//!
+//! Replaying a trace checks it against the borrowing rules, so a trace can't
+//! show something the compiler wouldn't allow.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::{Error, Lesson};
+
+/// A trace, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// The line of the lesson the event happens on.
+    pub line: usize,
+    pub event: Event,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    New {
+        owner: String,
+        value: String,
+    },
+    Borrow {
+        borrower: String,
+        owner: String,
+        mutable: bool,
+    },
+    Return {
+        borrower: String,
+    },
+    Change {
+        by: String,
+        value: String,
+    },
+    Move {
+        from: String,
+        to: String,
+    },
+    Drop {
+        owner: String,
+    },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::New { owner, value } => write!(f, "{} now owns {:?}", owner, value),
+            Event::Borrow {
+                borrower,
+                owner,
+                mutable: false,
+            } => write!(f, "{} borrows {} with &", borrower, owner),
+            Event::Borrow {
+                borrower,
+                owner,
+                mutable: true,
+            } => write!(f, "{} borrows {} with &mut", borrower, owner),
+            Event::Return { borrower } => write!(f, "{} gives its borrow back", borrower),
+            Event::Change { by, value } => write!(f, "{} changes the value to {:?}", by, value),
+            Event::Move { from, to } => write!(f, "the value moves from {} to {}", from, to),
+            Event::Drop { owner } => {
+                write!(f, "{} goes out of scope, and its value is dropped", owner)
+            }
+        }
+    }
+}
+
+/// Where a lesson's trace goes.
+pub fn path(lesson: &Lesson) -> PathBuf {
+    lesson.dir.join("ownership.trace")
+}
+
+/// Reads and checks a lesson's trace, if it has one.
+pub fn load(lesson: &Lesson) -> Result<Option<(Trace, Vec<State>)>, Error> {
+    let path = path(lesson);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(Error::Io { path, error }),
+    };
+    let annotation = |message| Error::Annotation {
+        path: path.clone(),
+        message,
+    };
+    let trace = Trace::parse(&text).map_err(annotation)?;
+    let states = trace.replay().map_err(annotation)?;
+    Ok(Some((trace, states)))
+}
+
+impl Trace {
+    /// Reads a trace. Errors say which line of the trace is wrong.
+    pub fn parse(text: &str) -> Result<Trace, String> {
+        let mut steps = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let step =
+                parse_step(line).map_err(|error| format!("line {}: {}", index + 1, error))?;
+            steps.push(step);
+        }
+        Ok(Trace { steps })
+    }
+
+    /// Replays the trace, returning what things look like after each step.
+    /// Fails on the first step that breaks the rules.
+    pub fn replay(&self) -> Result<Vec<State>, String> {
+        let mut state = State::default();
+        let mut states = Vec::new();
+
+        for step in &self.steps {
+            state.dropped = None;
+            state.apply(&step.event).map_err(|error| {
+                format!(
+                    "line {} of the lesson: {}: {}",
+                    step.line, step.event, error
+                )
+            })?;
+            states.push(state.clone());
+        }
+        Ok(states)
+    }
+}
+
+fn parse_step(line: &str) -> Result<Step, String> {
+    let (number, rest) = line.split_once(':').ok_or("expected `<line>: <event>`")?;
+    let number = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{}` isn't a line number", number.trim()))?;
+    let words = words(rest)?;
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+    let event = match words.as_slice() {
+        ["new", owner, value] => Event::New {
+            owner: owner.to_string(),
+            value: value.to_string(),
+        },
+        ["borrow", borrower, owner] | ["borrow_mut", borrower, owner] => Event::Borrow {
+            borrower: borrower.to_string(),
+            owner: owner.to_string(),
+            mutable: words[0] == "borrow_mut",
+        },
+        ["return", borrower] => Event::Return {
+            borrower: borrower.to_string(),
+        },
+        ["change", by, value] => Event::Change {
+            by: by.to_string(),
+            value: value.to_string(),
+        },
+        ["move", from, to] => Event::Move {
+            from: from.to_string(),
+            to: to.to_string(),
+        },
+        ["drop", owner] => Event::Drop {
+            owner: owner.to_string(),
+        },
+        [event, ..] => return Err(format!("`{}` with the wrong arguments", event)),
+        [] => return Err(String::from("expected an event")),
+    };
+    Ok(Step {
+        line: number,
+        event,
+    })
+}
+
+/// Splits on whitespace, keeping `"quoted strings"` together.
+fn words(text: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut chars = text.trim().chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut word = String::new();
+        if c == '"' {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => word.extend(chars.next()),
+                    Some(c) => word.push(c),
+                    None => return Err(String::from("unterminated string")),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+        }
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Who owns and borrows what, at one point in a trace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct State {
+    /// In the order they first showed up.
+    pub owners: Vec<Owner>,
+    pub values: Vec<Value>,
+    pub borrows: Vec<Borrow>,
+    /// The owner and value dropped by this step, if it was a drop.
+    pub dropped: Option<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Owner {
+    pub name: String,
+    /// An index into `values`.
+    pub value: Option<usize>,
+    /// Whether its value was moved out, which makes it unusable.
+    pub moved: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Value {
+    pub label: String,
+    pub dropped: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Borrow {
+    pub borrower: String,
+    /// An index into `values`.
+    pub value: usize,
+    pub mutable: bool,
+}
+
+impl State {
+    fn apply(&mut self, event: &Event) -> Result<(), String> {
+        match event {
+            Event::New { owner, value } => {
+                self.check_new_name(owner)?;
+                self.values.push(Value {
+                    label: value.clone(),
+                    dropped: false,
+                });
+                let value = Some(self.values.len() - 1);
+                match self.owners.iter_mut().find(|o| o.name == *owner) {
+                    Some(existing) => {
+                        existing.value = value;
+                        existing.moved = false;
+                    }
+                    None => self.owners.push(Owner {
+                        name: owner.clone(),
+                        value,
+                        moved: false,
+                    }),
+                }
+            }
+            Event::Borrow {
+                borrower,
+                owner,
+                mutable,
+            } => {
+                if self.borrow(borrower).is_some() {
+                    return Err(format!("{} is already borrowing", borrower));
+                }
+                self.check_new_name(borrower)?;
+                let value = self.owned(owner)?;
+                let borrows = self.borrows_of(value);
+                if *mutable && borrows > 0 {
+                    return Err(format!("{} is already borrowed", owner));
+                }
+                if self.mutably_borrowed(value) {
+                    return Err(format!("{} is already borrowed with &mut", owner));
+                }
+                self.borrows.push(Borrow {
+                    borrower: borrower.clone(),
+                    value,
+                    mutable: *mutable,
+                });
+            }
+            Event::Return { borrower } => {
+                let index = self
+                    .borrows
+                    .iter()
+                    .position(|b| b.borrower == *borrower)
+                    .ok_or_else(|| format!("{} isn't borrowing anything", borrower))?;
+                self.borrows.remove(index);
+            }
+            Event::Change { by, value } => {
+                let index = match self.borrow(by) {
+                    Some(borrow) if borrow.mutable => borrow.value,
+                    Some(_) => return Err(format!("{} only has a &", by)),
+                    None => {
+                        let index = self.owned(by)?;
+                        if self.borrows_of(index) > 0 {
+                            return Err(format!("{} is borrowed", by));
+                        }
+                        index
+                    }
+                };
+                self.values[index].label = value.clone();
+            }
+            Event::Move { from, to } => {
+                let value = self.owned(from)?;
+                if self.borrows_of(value) > 0 {
+                    return Err(format!("{} is borrowed", from));
+                }
+                self.check_new_name(to)?;
+                let owner = self.owners.iter_mut().find(|o| o.name == *from).unwrap();
+                owner.value = None;
+                owner.moved = true;
+                match self.owners.iter_mut().find(|o| o.name == *to) {
+                    Some(existing) => {
+                        existing.value = Some(value);
+                        existing.moved = false;
+                    }
+                    None => self.owners.push(Owner {
+                        name: to.clone(),
+                        value: Some(value),
+                        moved: false,
+                    }),
+                }
+            }
+            Event::Drop { owner } => {
+                let value = self.owned(owner)?;
+                if self.borrows_of(value) > 0 {
+                    return Err(format!("{} is still borrowed", owner));
+                }
+                self.values[value].dropped = true;
+                self.owners.retain(|o| o.name != *owner);
+                self.dropped = Some((owner.clone(), self.values[value].label.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// The value an owner owns right now.
+    fn owned(&self, owner: &str) -> Result<usize, String> {
+        match self.owners.iter().find(|o| o.name == owner) {
+            Some(Owner { moved: true, .. }) => Err(format!("{}'s value was moved out", owner)),
+            Some(Owner {
+                value: Some(value), ..
+            }) => Ok(*value),
+            _ => Err(format!("{} doesn't own anything", owner)),
+        }
+    }
+
+    /// Owners and borrowers share names, so a name can only be one of them.
+    fn check_new_name(&self, name: &str) -> Result<(), String> {
+        if self.borrow(name).is_some() {
+            return Err(format!("{} is borrowing", name));
+        }
+        match self.owners.iter().find(|o| o.name == name) {
+            Some(Owner { value: Some(_), .. }) => Err(format!("{} already owns a value", name)),
+            _ => Ok(()),
+        }
+    }
+
+    fn borrow(&self, borrower: &str) -> Option<&Borrow> {
+        self.borrows.iter().find(|b| b.borrower == borrower)
+    }
+
+    fn borrows_of(&self, value: usize) -> usize {
+        self.borrows.iter().filter(|b| b.value == value).count()
+    }
+
+    fn mutably_borrowed(&self, value: usize) -> bool {
+        self.borrows.iter().any(|b| b.value == value && b.mutable)
+    }
+
+    /// The state as a Graphviz graph: owners are boxes, values are circles,
+    /// ownership is a solid edge, `&` is dashed and `&mut` is bold.
+    pub fn dot(&self, caption: &str) -> String {
+        let mut dot = String::from("digraph ownership {\n");
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str(&format!(
+            "    label={}; labelloc=t; fontname=\"Helvetica\";\n",
+            quote(caption)
+        ));
+        dot.push_str("    node [fontname=\"Helvetica\"];\n");
+
+        for (index, value) in self.values.iter().enumerate() {
+            let owned = self.owners.iter().any(|o| o.value == Some(index));
+            if owned {
+                dot.push_str(&format!(
+                    "    value{} [shape=circle, label={}];\n",
+                    index,
+                    quote(&format!("{:?}", value.label))
+                ));
+            }
+        }
+        if let Some((owner, label)) = &self.dropped {
+            dot.push_str(&format!(
+                "    dropped [shape=circle, style=dashed, color=gray, fontcolor=gray, label={}];\n",
+                quote(&format!("{:?}\n(dropped)", label))
+            ));
+            dot.push_str(&format!(
+                "    gone [shape=box, style=dashed, color=gray, fontcolor=gray, label={}];\n",
+                quote(owner)
+            ));
+            dot.push_str("    gone -> dropped [color=gray];\n");
+        }
+
+        for (index, owner) in self.owners.iter().enumerate() {
+            if owner.moved {
+                dot.push_str(&format!(
+                    "    owner{} [shape=box, color=gray, fontcolor=gray, label={}];\n",
+                    index,
+                    quote(&format!("{}\n(moved out)", owner.name))
+                ));
+                continue;
+            }
+            dot.push_str(&format!(
+                "    owner{} [shape=box, label={}];\n",
+                index,
+                quote(&owner.name)
+            ));
+            if let Some(value) = owner.value {
+                dot.push_str(&format!("    owner{} -> value{};\n", index, value));
+            }
+        }
+
+        for (index, borrow) in self.borrows.iter().enumerate() {
+            let (style, label) = if borrow.mutable {
+                ("bold", "&mut")
+            } else {
+                ("dashed", "&")
+            };
+            dot.push_str(&format!(
+                "    borrower{} [shape=box, label={}];\n",
+                index,
+                quote(&borrow.borrower)
+            ));
+            dot.push_str(&format!(
+                "    borrower{} -> value{} [style={}, label={}];\n",
+                index,
+                borrow.value,
+                style,
+                quote(label)
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// A DOT string literal.
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,213 @@
+//! Building and running lessons, and deciding whether they did what we expect.
+//!
+//! Lessons run in the [`sandbox`](crate::sandbox), like anything else `club`
+//! runs, since attendees edit them.
+
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::sandbox::{self, Limit, Limits, Status};
+use crate::{Error, Lesson, Outcome};
+
+/// The exit code Rust uses when `main` panics.
+const PANIC_EXIT_CODE: i32 = 101;
+
+/// The environment variable that tells a lesson which of its
+/// [`paths`](Lesson::paths) to take.
+pub const PATH_VARIABLE: &str = "CLUB_PATH";
+
+/// Everything a lesson printed, and how it exited.
+#[derive(Debug)]
+pub struct Run {
+    pub stdout: String,
+    pub stderr: String,
+    pub status: Status,
+}
+
+impl Run {
+    pub fn panicked(&self) -> bool {
+        self.status == Status::Exited(PANIC_EXIT_CODE) && self.stderr.contains("panicked at")
+    }
+}
+
+/// How a run compares to what the lesson said it would do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Exited with status 0, as expected.
+    Clean,
+    /// Panicked, and the lesson says it should.
+    ExpectedPanic,
+    /// Panicked when it should have exited cleanly.
+    UnexpectedPanic,
+    /// Panicked as expected, but not with the message the lesson mentions.
+    WrongPanic,
+    /// Exited cleanly when it should have panicked.
+    MissingPanic,
+    /// Exited cleanly, but printed something other than what it should.
+    WrongOutput,
+    /// Stopped by the sandbox for going over one of its limits.
+    Exceeded(Limit),
+    /// Any other non-zero exit, or killed by a signal.
+    Failed(Option<i32>),
+}
+
+impl Verdict {
+    pub fn judge(lesson: &Lesson, run: &Run) -> Verdict {
+        Verdict::expecting(&lesson.outcome, run)
+    }
+
+    /// Like [`judge`](Verdict::judge), for a release build of the lesson.
+    pub fn judge_release(lesson: &Lesson, run: &Run) -> Verdict {
+        Verdict::expecting(lesson.release.as_ref().unwrap_or(&lesson.outcome), run)
+    }
+
+    /// Like [`judge`](Verdict::judge), for a run down one of the lesson's
+    /// other paths, which must exit cleanly unless the lesson says otherwise.
+    pub fn judge_path(lesson: &Lesson, path: &str, run: &Run) -> Verdict {
+        let outcome = lesson.path_outcomes.get(path);
+        Verdict::expecting(outcome.unwrap_or(&Outcome::Success), run)
+    }
+
+    fn expecting(outcome: &Outcome, run: &Run) -> Verdict {
+        if let Status::Exceeded(limit) = run.status {
+            return Verdict::Exceeded(limit);
+        }
+        match (outcome, run.status.code(), run.panicked()) {
+            (Outcome::Success, Some(0), _) => Verdict::Clean,
+            (Outcome::Output { stdout }, Some(0), _) => {
+                if run.stdout.lines().eq(stdout.iter().map(String::as_str)) {
+                    Verdict::Clean
+                } else {
+                    Verdict::WrongOutput
+                }
+            }
+            (Outcome::Panic { .. }, Some(0), _) => Verdict::MissingPanic,
+            (Outcome::Panic { message }, _, true) => match message {
+                Some(message) if !run.stderr.contains(message.as_str()) => Verdict::WrongPanic,
+                _ => Verdict::ExpectedPanic,
+            },
+            (_, _, true) => Verdict::UnexpectedPanic,
+            (_, code, false) => Verdict::Failed(code),
+        }
+    }
+
+    pub fn is_ok(self) -> bool {
+        matches!(self, Verdict::Clean | Verdict::ExpectedPanic)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Clean => write!(f, "exited cleanly"),
+            Verdict::ExpectedPanic => write!(f, "panicked (expected)"),
+            Verdict::UnexpectedPanic => write!(f, "panicked unexpectedly"),
+            Verdict::WrongPanic => write!(f, "panicked, but not with the expected message"),
+            Verdict::MissingPanic => write!(f, "exited cleanly, but was expected to panic"),
+            Verdict::WrongOutput => write!(f, "exited cleanly, but printed the wrong output"),
+            Verdict::Exceeded(limit) => write!(f, "was stopped for using too much {}", limit),
+            Verdict::Failed(Some(code)) => write!(f, "failed with exit code {}", code),
+            Verdict::Failed(None) => write!(f, "was killed by a signal"),
+        }
+    }
+}
+
+/// Builds the lesson with cargo, then runs its binary in the sandbox and
+/// captures the output.
+///
+/// The binary is run directly rather than through `cargo run`, so that the
+/// captured stderr only has what the lesson printed and not cargo's chatter.
+pub fn run(root: &Path, lesson: &Lesson) -> Result<Run, Error> {
+    build_and_run(root, lesson, Profile::Debug, &[])
+}
+
+/// Like [`run`], but down another of the lesson's paths, with
+/// [`PATH_VARIABLE`] set to `path`.
+pub fn run_path(root: &Path, lesson: &Lesson, path: &str) -> Result<Run, Error> {
+    build_and_run(root, lesson, Profile::Debug, &[(PATH_VARIABLE, path)])
+}
+
+/// Like [`run`], but built with `--release`, which checks less and
+/// optimizes more.
+pub fn run_release(root: &Path, lesson: &Lesson) -> Result<Run, Error> {
+    build_and_run(root, lesson, Profile::Release, &[])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Profile {
+    Debug,
+    Release,
+}
+
+fn build_and_run(
+    root: &Path,
+    lesson: &Lesson,
+    profile: Profile,
+    env: &[(&str, &str)],
+) -> Result<Run, Error> {
+    let mut build = Command::new(cargo());
+    build.args(["build", "--quiet", "--package", &lesson.name]);
+    if profile == Profile::Release {
+        build.arg("--release");
+    }
+    let status = build
+        .current_dir(root)
+        .stdout(Stdio::null())
+        .status()
+        .map_err(|error| Error::Io {
+            path: PathBuf::from(cargo()),
+            error,
+        })?;
+    if !status.success() {
+        return Err(Error::Build(lesson.name.clone()));
+    }
+
+    let binary = binary(root, &lesson.name, profile);
+    let run = sandbox::run(&binary, env, &Limits::default())?;
+    Ok(Run {
+        stdout: run.stdout,
+        stderr: run.stderr,
+        status: run.status,
+    })
+}
+
+/// Prints a run with every line labelled by the lesson it came from. Lines on
+/// stdout get a `|`, lines on stderr get a `!`.
+pub fn print(lesson: &Lesson, run: &Run, verdict: Verdict) {
+    print_labelled(&lesson.name, run, verdict);
+}
+
+/// Like [`print`], for a run down one of the lesson's other paths, or of
+/// its release build, with the lines labelled `<lesson>:<path>` or
+/// `<lesson>:release`.
+pub fn print_variant(lesson: &Lesson, variant: &str, run: &Run, verdict: Verdict) {
+    print_labelled(&format!("{}:{}", lesson.name, variant), run, verdict);
+}
+
+fn print_labelled(label: &str, run: &Run, verdict: Verdict) {
+    for line in run.stdout.lines() {
+        println!("{} | {}", label, line);
+    }
+    for line in run.stderr.lines() {
+        println!("{} ! {}", label, line);
+    }
+    println!("--- {} {}", label, verdict);
+}
+
+fn cargo() -> String {
+    env::var("CARGO").unwrap_or_else(|_| String::from("cargo"))
+}
+
+fn binary(root: &Path, name: &str, profile: Profile) -> PathBuf {
+    let target = env::var_os("CARGO_TARGET_DIR")
+        .map(|dir| root.join(dir))
+        .unwrap_or_else(|| root.join("target"));
+    target
+        .join(match profile {
+            Profile::Debug => "debug",
+            Profile::Release => "release",
+        })
+        .join(format!("{}{}", name, env::consts::EXE_SUFFIX))
+}
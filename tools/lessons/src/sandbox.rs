@@ -0,0 +1,766 @@
+//! Running code that we didn't write, like a snippet pasted into the
+//! playground, without letting it take the shared lab machine down with it.
+//!
+//! The program runs as a child process in a fresh, empty temporary directory,
+//! with none of our environment, in a process group of its own. On Linux it
+//! also gets rlimits on CPU time and file size, which the kernel enforces with
+//! `SIGXCPU` and `SIGXFSZ`. We enforce the rest ourselves by watching it: the
+//! wall clock, its output, and, on Linux, the memory and the processes and
+//! threads of everything it started, read from `/proc`. Whatever goes over
+//! gets its whole group killed, so how a run is judged only ever depends on
+//! signals and on what we did, never on what the program printed.
+//!
+//! Memory and processes are measured every few milliseconds, so each has a
+//! backstop rlimit for programs too quick for that:
+//!
+//! * A program whose memory grows too fast to catch runs out of address
+//!   space, and its allocator aborts it with `SIGABRT`. That counts as going
+//!   over the memory limit if we saw its memory or address space get close to
+//!   their limits. Otherwise it's reported as the signal it is, since
+//!   `std::process::abort`, a panic while panicking, or a single allocation
+//!   far too big to have used anything all abort the same way.
+//! * A fork bomb runs out of processes, since `RLIMIT_NPROC` only allows the
+//!   user a few times the limit more than they already have. That holds for
+//!   processes that leave the group too, which we couldn't kill. Root isn't
+//!   held to `RLIMIT_NPROC`.
+//!
+//! The group is killed once more when the program exits, for anything it left
+//! running in the background, before the program is reaped so that its group
+//! can't have been reused by then. Only Linux can wait without reaping; on
+//! other Unixes, whatever is left in the background stays there. Something
+//! that left the group, with `setsid`, can hold on to the program's output
+//! after that, so the output is only waited for until the wall-clock limit.
+//!
+//! Off Unix, only the wall-clock and output limits apply.
+
+use std::env;
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+#[cfg(unix)]
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::Error;
+
+/// What a sandboxed program is allowed to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub cpu_time: Duration,
+    pub wall_time: Duration,
+    /// Resident memory of the program and everything it started, in bytes.
+    pub memory: u64,
+    /// Bytes of stdout and stderr together.
+    pub output: usize,
+    /// The size of any one file written, in bytes.
+    pub files: u64,
+    /// Processes and threads, counting the program itself.
+    pub processes: u64,
+}
+
+impl Limits {
+    /// For compiling untrusted code with rustc, which we trust. It's the
+    /// default, but with room for the threads rustc and LLVM start, of which
+    /// there are more the more cores the machine has.
+    pub fn compiler() -> Limits {
+        Limits {
+            processes: 256,
+            ..Limits::default()
+        }
+    }
+}
+
+impl Default for Limits {
+    /// Generous enough for any of the lessons, and small enough that a room
+    /// full of people running infinite loops won't notice each other.
+    fn default() -> Limits {
+        Limits {
+            cpu_time: Duration::from_secs(5),
+            wall_time: Duration::from_secs(10),
+            memory: 512 * 1024 * 1024,
+            output: 1024 * 1024,
+            files: 64 * 1024 * 1024,
+            processes: 16,
+        }
+    }
+}
+
+/// A limit that a program went over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    CpuTime,
+    WallTime,
+    Memory,
+    Output,
+    FileSize,
+    Processes,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Limit::CpuTime => write!(f, "CPU time"),
+            Limit::WallTime => write!(f, "wall-clock time"),
+            Limit::Memory => write!(f, "memory"),
+            Limit::Output => write!(f, "output"),
+            Limit::FileSize => write!(f, "space for a file"),
+            Limit::Processes => write!(f, "processes"),
+        }
+    }
+}
+
+/// How a sandboxed program finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Exited(i32),
+    /// Killed by a signal that wasn't one of our limits.
+    Signalled(i32),
+    /// Stopped for going over a limit.
+    Exceeded(Limit),
+}
+
+impl Status {
+    /// The exit code, if the program exited by itself.
+    pub fn code(self) -> Option<i32> {
+        match self {
+            Status::Exited(code) => Some(code),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Exited(code) => write!(f, "exited with code {}", code),
+            Status::Signalled(signal) => write!(f, "was killed by signal {}", signal),
+            Status::Exceeded(limit) => write!(f, "was stopped for using too much {}", limit),
+        }
+    }
+}
+
+/// Everything a sandboxed program printed, and how it finished. The output is
+/// cut off at the output limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sandboxed {
+    pub stdout: String,
+    pub stderr: String,
+    pub status: Status,
+}
+
+#[cfg(unix)]
+const SIGABRT: i32 = 6;
+#[cfg(unix)]
+const SIGKILL: i32 = 9;
+#[cfg(unix)]
+const SIGXCPU: i32 = 24;
+#[cfg(unix)]
+const SIGXFSZ: i32 = 25;
+
+/// How often to check on the program while it runs.
+const POLL: Duration = Duration::from_millis(10);
+
+/// How long to wait for the last of the output after the program has gone,
+/// when that's already past the wall-clock limit.
+const DRAIN: Duration = Duration::from_millis(100);
+
+/// Runs `binary` in the sandbox, with only the environment variables in `env`.
+/// The binary is copied into the sandbox's directory first, and the directory
+/// is removed afterwards.
+pub fn run(binary: &Path, env: &[(&str, &str)], limits: &Limits) -> Result<Sandboxed, Error> {
+    let dir = scratch_dir();
+    fs::create_dir_all(&dir).map_err(io(&dir))?;
+    let name = binary.file_name().unwrap_or_else(|| "program".as_ref());
+    let copy = dir.join(name);
+    let result = fs::copy(binary, &copy)
+        .map_err(io(binary))
+        .and_then(|_| run_in(&dir, &copy, &[], env, limits));
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+/// Runs `program` with `args` in the sandbox, in `dir`, which is left as it
+/// is afterwards. This is for programs we trust, like rustc, working on
+/// input we don't, so `dir` should hold nothing but that input.
+pub fn run_in<V: AsRef<OsStr>>(
+    dir: &Path,
+    program: &Path,
+    args: &[&str],
+    env: &[(&str, V)],
+    limits: &Limits,
+) -> Result<Sandboxed, Error> {
+    spawn(program, args, env, dir, limits)
+        .and_then(|child| watch(child, limits))
+        .map_err(io(program))
+}
+
+/// The little of our environment that rustc needs to find its toolchain and
+/// the linker, for passing to [`run_in`].
+pub fn toolchain_env() -> Vec<(&'static str, String)> {
+    [
+        "PATH",
+        "HOME",
+        "RUSTUP_HOME",
+        "RUSTUP_TOOLCHAIN",
+        "CARGO_HOME",
+    ]
+    .iter()
+    .filter_map(|&name| Some((name, env::var(name).ok()?)))
+    .collect()
+}
+
+fn io(path: &Path) -> impl FnOnce(io::Error) -> Error {
+    let path = path.to_path_buf();
+    move |error| Error::Io { path, error }
+}
+
+fn scratch_dir() -> PathBuf {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let run = RUNS.fetch_add(1, Ordering::SeqCst);
+    env::temp_dir().join(format!("club-sandbox-{}-{}", std::process::id(), run))
+}
+
+fn spawn<V: AsRef<OsStr>>(
+    program: &Path,
+    args: &[&str],
+    env: &[(&str, V)],
+    dir: &Path,
+    limits: &Limits,
+) -> io::Result<Child> {
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .current_dir(dir)
+        .env_clear()
+        .envs(env.iter().map(|(name, value)| (name, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(unix)]
+    {
+        let limits = *limits;
+        // Counted here, since /proc can't be read between fork and exec.
+        let processes = sys::user_processes();
+        // Safety: the closure runs in the child between fork and exec, so it
+        // may only make async-signal-safe calls, which setpgid and setrlimit
+        // are.
+        unsafe {
+            command.pre_exec(move || {
+                if sys::setpgid(0, 0) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                sys::limit(&limits, processes)
+            });
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = limits;
+
+    command.spawn()
+}
+
+/// Waits for the program to finish, killing it if it goes over the limits
+/// that we enforce ourselves.
+fn watch(mut child: Child, limits: &Limits) -> io::Result<Sandboxed> {
+    let overflowed = Arc::new(AtomicBool::new(false));
+    let printed = Arc::new(AtomicUsize::new(0));
+    let stdout = Collector::start(child.stdout.take(), &printed, &overflowed, limits.output);
+    let stderr = Collector::start(child.stderr.take(), &printed, &overflowed, limits.output);
+
+    let started = Instant::now();
+    let mut killed_for = None;
+    let mut seen = Measured::default();
+    let (status, measured) = loop {
+        if let Some(finished) = wait(&mut child)? {
+            break finished;
+        }
+        if let Some(usage) = sys::usage(child.id()) {
+            seen.memory = seen.memory.max(usage.memory);
+            seen.address_space = seen.address_space.max(usage.address_space);
+        }
+        if killed_for.is_none() {
+            killed_for = over(&child, limits, &overflowed, started);
+            if killed_for.is_some() {
+                kill(&mut child);
+            }
+        }
+        thread::sleep(POLL);
+    };
+
+    // Everything in the group is gone by now, but anything that left it can
+    // still hold the pipes open, for as long as it likes.
+    let deadline = (started + limits.wall_time).max(Instant::now() + DRAIN);
+    let (stdout, stdout_closed) = stdout.finish(deadline);
+    let (stderr, stderr_closed) = stderr.finish(deadline);
+    if !(stdout_closed && stderr_closed) {
+        killed_for = killed_for.or(Some(Limit::WallTime));
+    }
+    // It may have printed too much and exited before we noticed.
+    if overflowed.load(Ordering::SeqCst) {
+        killed_for = killed_for.or(Some(Limit::Output));
+    }
+    let status = match killed_for {
+        Some(limit) => Status::Exceeded(limit),
+        None => judge(status, seen.max(measured), limits),
+    };
+
+    Ok(Sandboxed {
+        stdout,
+        stderr,
+        status,
+    })
+}
+
+/// Which of the limits we enforce ourselves the program is over, if any.
+fn over(
+    child: &Child,
+    limits: &Limits,
+    overflowed: &AtomicBool,
+    started: Instant,
+) -> Option<Limit> {
+    if overflowed.load(Ordering::SeqCst) {
+        return Some(Limit::Output);
+    }
+    if started.elapsed() > limits.wall_time {
+        return Some(Limit::WallTime);
+    }
+    let usage = sys::usage(child.id())?;
+    if usage.tasks > limits.processes {
+        Some(Limit::Processes)
+    } else if usage.memory > limits.memory {
+        Some(Limit::Memory)
+    } else {
+        None
+    }
+}
+
+/// What we measured of a program, for judging how it finished.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Measured {
+    /// CPU time used by the program and the children it waited for, where we
+    /// can tell.
+    cpu_time: Option<Duration>,
+    /// The most resident memory it had, in bytes.
+    memory: u64,
+    /// The most address space it had, in bytes.
+    address_space: u64,
+}
+
+impl Measured {
+    fn max(self, other: Measured) -> Measured {
+        Measured {
+            cpu_time: self.cpu_time.max(other.cpu_time),
+            memory: self.memory.max(other.memory),
+            address_space: self.address_space.max(other.address_space),
+        }
+    }
+}
+
+/// Whether the program has finished, and if so how, and what we could measure
+/// of it once it had.
+///
+/// On Linux the rest of its group is killed before it's reaped, for anything
+/// it left running in the background, which would hold the pipes open. Until
+/// it's reaped, its process ID, and so its group's, can't be anyone else's.
+#[cfg(target_os = "linux")]
+fn wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Measured)>> {
+    if !sys::exited(child.id())? {
+        return Ok(None);
+    }
+    sys::kill_group(child.id());
+    let (status, cpu_time, memory) = sys::reap(child.id())?;
+    let measured = Measured {
+        cpu_time: Some(cpu_time),
+        memory,
+        address_space: 0,
+    };
+    Ok(Some((ExitStatus::from_raw(status), measured)))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Measured)>> {
+    Ok(child
+        .try_wait()?
+        .map(|status| (status, Measured::default())))
+}
+
+/// How a program that finished by itself did, from its exit status and what
+/// we measured of it.
+#[cfg(unix)]
+fn judge(status: ExitStatus, measured: Measured, limits: &Limits) -> Status {
+    let over_cpu_time = measured
+        .cpu_time
+        .is_some_and(|cpu_time| cpu_time >= limits.cpu_time);
+    // Within a quarter of the limit, or of the address-space backstop.
+    let near = |used: u64, limit: u64| used >= limit - limit / 4;
+    let out_of_memory = near(measured.memory, limits.memory)
+        || near(measured.address_space, sys::address_space(limits));
+    match (status.code(), status.signal()) {
+        (Some(code), _) => Status::Exited(code),
+        (None, Some(SIGXCPU)) => Status::Exceeded(Limit::CpuTime),
+        // The hard CPU limit, for a program that caught SIGXCPU.
+        (None, Some(SIGKILL)) if over_cpu_time => Status::Exceeded(Limit::CpuTime),
+        (None, Some(SIGXFSZ)) => Status::Exceeded(Limit::FileSize),
+        (None, Some(SIGABRT)) if out_of_memory => Status::Exceeded(Limit::Memory),
+        (None, Some(signal)) => Status::Signalled(signal),
+        (None, None) => Status::Signalled(0),
+    }
+}
+
+#[cfg(not(unix))]
+fn judge(status: ExitStatus, _measured: Measured, _limits: &Limits) -> Status {
+    Status::Exited(status.code().unwrap_or(1))
+}
+
+/// Kills the program, and on Unix, everything else in its group. It mustn't
+/// have been reaped yet.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    sys::kill_group(child.id());
+    #[cfg(not(unix))]
+    let _ = child.kill();
+}
+
+/// Reads a pipe on its own thread, keeping at most `limit` bytes across all
+/// the pipes that share `printed`, and raising `overflowed` past that.
+struct Collector {
+    kept: Arc<Mutex<Vec<u8>>>,
+    /// Disconnects when the pipe is closed and the thread is done.
+    done: mpsc::Receiver<()>,
+}
+
+impl Collector {
+    fn start(
+        pipe: Option<impl Read + Send + 'static>,
+        printed: &Arc<AtomicUsize>,
+        overflowed: &Arc<AtomicBool>,
+        limit: usize,
+    ) -> Collector {
+        let kept = Arc::new(Mutex::new(Vec::new()));
+        let (done, finished) = mpsc::channel();
+        let printed = Arc::clone(printed);
+        let overflowed = Arc::clone(overflowed);
+        let keep = Arc::clone(&kept);
+        thread::spawn(move || {
+            let _done = done;
+            let mut pipe = match pipe {
+                Some(pipe) => pipe,
+                None => return,
+            };
+            let mut buffer = [0; 8192];
+            while let Ok(read) = pipe.read(&mut buffer) {
+                if read == 0 {
+                    break;
+                }
+                let before = printed.fetch_add(read, Ordering::SeqCst);
+                let room = limit.saturating_sub(before);
+                if let Ok(mut kept) = keep.lock() {
+                    kept.extend_from_slice(&buffer[..read.min(room)]);
+                }
+                if read > room {
+                    overflowed.store(true, Ordering::SeqCst);
+                }
+            }
+        });
+        Collector {
+            kept,
+            done: finished,
+        }
+    }
+
+    /// What was read, waiting until `deadline` at the latest for the pipe to
+    /// close, and whether it did. If it didn't, the thread is left to read
+    /// into nothing until whatever has the other end lets go of it.
+    fn finish(self, deadline: Instant) -> (String, bool) {
+        let left = deadline.saturating_duration_since(Instant::now());
+        let closed = matches!(
+            self.done.recv_timeout(left),
+            Err(mpsc::RecvTimeoutError::Disconnected) | Ok(())
+        );
+        let kept = match self.kept.lock() {
+            Ok(kept) => kept,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        (String::from_utf8_lossy(&kept).into_owned(), closed)
+    }
+}
+
+/// What a program and everything it started are using.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Usage {
+    /// Processes and threads.
+    tasks: u64,
+    /// Resident memory, in bytes.
+    memory: u64,
+    /// Address space, in bytes.
+    address_space: u64,
+}
+
+/// The few libc calls we need, which std already links against.
+#[cfg(unix)]
+mod sys {
+    use std::io;
+    use std::os::raw::c_int;
+
+    use super::Limits;
+    #[cfg(target_os = "linux")]
+    use super::Usage;
+
+    const SIGKILL: c_int = 9;
+
+    extern "C" {
+        pub fn setpgid(pid: c_int, pgid: c_int) -> c_int;
+        fn kill(pid: c_int, signal: c_int) -> c_int;
+    }
+
+    /// Kills the process group led by `pid`.
+    pub fn kill_group(pid: u32) {
+        // Safety: kill has no memory safety requirements. It fails harmlessly
+        // if the group is already gone.
+        unsafe {
+            kill(-(pid as c_int), SIGKILL);
+        }
+    }
+
+    /// Waits for `pid` to exit, without reaping it. Returns whether it has.
+    #[cfg(target_os = "linux")]
+    pub fn exited(pid: u32) -> io::Result<bool> {
+        extern "C" {
+            fn waitid(idtype: c_int, id: c_int, info: *mut SigInfo, options: c_int) -> c_int;
+        }
+        /// `siginfo_t`, which is 128 bytes, with the signal number first.
+        #[repr(C)]
+        struct SigInfo {
+            signal: c_int,
+            rest: [c_int; 31],
+        }
+        const P_PID: c_int = 1;
+        const WNOHANG: c_int = 1;
+        const WEXITED: c_int = 4;
+        const WNOWAIT: c_int = 0x0100_0000;
+
+        let mut info = SigInfo {
+            signal: 0,
+            rest: [0; 31],
+        };
+        // Safety: `info` is a valid siginfo_t for the duration of the call.
+        if unsafe { waitid(P_PID, pid as c_int, &mut info, WEXITED | WNOHANG | WNOWAIT) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // With WNOHANG, nothing is filled in if it's still running.
+        Ok(info.signal != 0)
+    }
+
+    /// Reaps `pid`, which has exited. Returns its raw wait status, and the
+    /// CPU time and the most resident memory that it and the children it
+    /// waited for used.
+    #[cfg(target_os = "linux")]
+    pub fn reap(pid: u32) -> io::Result<(c_int, std::time::Duration, u64)> {
+        use std::os::raw::c_long;
+        use std::time::Duration;
+
+        #[repr(C)]
+        struct TimeVal {
+            seconds: c_long,
+            micros: c_long,
+        }
+        #[repr(C)]
+        struct Rusage {
+            user: TimeVal,
+            system: TimeVal,
+            /// In kilobytes.
+            max_rss: c_long,
+            rest: [c_long; 13],
+        }
+        extern "C" {
+            fn wait4(pid: c_int, status: *mut c_int, options: c_int, usage: *mut Rusage) -> c_int;
+        }
+
+        let mut status = 0;
+        let mut usage = Rusage {
+            user: TimeVal {
+                seconds: 0,
+                micros: 0,
+            },
+            system: TimeVal {
+                seconds: 0,
+                micros: 0,
+            },
+            max_rss: 0,
+            rest: [0; 13],
+        };
+        // Safety: `status` and `usage` are valid for the duration of the call.
+        if unsafe { wait4(pid as c_int, &mut status, 0, &mut usage) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let time = |time: &TimeVal| {
+            Duration::from_secs(time.seconds.max(0) as u64)
+                + Duration::from_micros(time.micros.max(0) as u64)
+        };
+        let memory = (usage.max_rss.max(0) as u64).saturating_mul(1024);
+        Ok((status, time(&usage.user) + time(&usage.system), memory))
+    }
+
+    /// How many processes and threads the user we're running as has, which
+    /// is what `RLIMIT_NPROC` counts.
+    #[cfg(target_os = "linux")]
+    pub fn user_processes() -> u64 {
+        use std::fs;
+
+        extern "C" {
+            fn getuid() -> u32;
+        }
+        // Safety: getuid has no memory safety requirements.
+        let uid = unsafe { getuid() }.to_string();
+
+        let mut count = 0;
+        for entry in fs::read_dir("/proc").into_iter().flatten().flatten() {
+            let status = match fs::read_to_string(entry.path().join("status")) {
+                Ok(status) => status,
+                Err(_) => continue,
+            };
+            let field = |name: &str| {
+                status
+                    .lines()
+                    .find_map(|line| line.strip_prefix(name))
+                    .and_then(|value| value.split_whitespace().next())
+            };
+            if field("Uid:") == Some(uid.as_str()) {
+                count += field("Threads:")
+                    .and_then(|threads| threads.parse().ok())
+                    .unwrap_or(1);
+            }
+        }
+        count
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn user_processes() -> u64 {
+        0
+    }
+
+    /// The backstop on address space. It leaves room to see the memory grow
+    /// past its limit before the address space runs out, since a program's
+    /// address space is usually far bigger than what it has in memory.
+    pub fn address_space(limits: &Limits) -> u64 {
+        limits.memory.saturating_mul(4)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn limit(limits: &Limits, processes: u64) -> io::Result<()> {
+        #[repr(C)]
+        struct Rlimit {
+            current: u64,
+            max: u64,
+        }
+
+        extern "C" {
+            fn setrlimit(resource: c_int, limit: *const Rlimit) -> c_int;
+        }
+
+        const RLIMIT_CPU: c_int = 0;
+        const RLIMIT_FSIZE: c_int = 1;
+        const RLIMIT_CORE: c_int = 4;
+        const RLIMIT_NPROC: c_int = 6;
+        const RLIMIT_AS: c_int = 9;
+
+        // The soft CPU limit sends SIGXCPU, which is how we tell it apart from
+        // anything else. The hard limit is a second later, in case the program
+        // catches it.
+        let cpu = limits.cpu_time.as_secs().max(1);
+        let address_space = address_space(limits);
+        // Likewise room to see the processes go over before forks start to
+        // fail. The count is for the whole user, so it starts from what the
+        // user already has.
+        let processes = processes.saturating_add(limits.processes.saturating_mul(4));
+        let rlimits = [
+            (RLIMIT_CPU, cpu, cpu + 1),
+            (RLIMIT_FSIZE, limits.files, limits.files),
+            (RLIMIT_CORE, 0, 0),
+            (RLIMIT_NPROC, processes, processes),
+            (RLIMIT_AS, address_space, address_space),
+        ];
+        for (resource, current, max) in rlimits {
+            let limit = Rlimit { current, max };
+            // Safety: `limit` is a valid rlimit for the duration of the call.
+            if unsafe { setrlimit(resource, &limit) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn limit(_limits: &Limits, _processes: u64) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Adds up the threads, resident memory and address space of `pid` and all
+    /// of its descendants, from `/proc`. `None` if `pid` has already gone.
+    #[cfg(target_os = "linux")]
+    pub fn usage(pid: u32) -> Option<Usage> {
+        use std::fs;
+
+        extern "C" {
+            fn sysconf(name: c_int) -> std::os::raw::c_long;
+        }
+        const SC_PAGESIZE: c_int = 30;
+        // Safety: sysconf has no memory safety requirements.
+        let page = unsafe { sysconf(SC_PAGESIZE) }.max(1) as u64;
+
+        let mut usage = Usage::default();
+        let mut found = false;
+        let mut pending = vec![pid];
+        while let Some(pid) = pending.pop() {
+            let stat = match fs::read_to_string(format!("/proc/{}/stat", pid)) {
+                Ok(stat) => stat,
+                Err(_) => continue,
+            };
+            found = true;
+            // The name is in parentheses, and may have spaces in it. After it
+            // comes the state, which is field 3, so field n is at n - 3.
+            let fields: Vec<u64> = stat[stat.rfind(')').map_or(0, |end| end + 1)..]
+                .split_whitespace()
+                .map(|field| field.parse().unwrap_or(0))
+                .collect();
+            usage.tasks += fields.get(20 - 3).copied().unwrap_or(1);
+            usage.address_space += fields.get(23 - 3).copied().unwrap_or(0);
+            usage.memory += fields.get(24 - 3).copied().unwrap_or(0) * page;
+
+            let tasks = fs::read_dir(format!("/proc/{}/task", pid));
+            for task in tasks.into_iter().flatten().flatten() {
+                let children = fs::read_to_string(task.path().join("children"));
+                pending.extend(
+                    children
+                        .unwrap_or_default()
+                        .split_whitespace()
+                        .filter_map(|child| child.parse::<u32>().ok()),
+                );
+            }
+        }
+        if found {
+            Some(usage)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn usage(_pid: u32) -> Option<super::Usage> {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+mod sys {
+    pub fn usage(_pid: u32) -> Option<super::Usage> {
+        None
+    }
+}
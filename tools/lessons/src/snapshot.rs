@@ -0,0 +1,242 @@
+//! Golden-output snapshots of what each lesson prints.
+//!
+//! A snapshot lives in `snapshots/<lesson>.snap` at the root of the workspace
+//! and records the exit status, stdout and stderr of a run. Each of the
+//! lesson's other paths gets one too, in `<lesson>.path.<name>.snap`, and so
+//! does its release build, in `<lesson>.release.snap`, if it has one. Anything
+//! that changes from machine to machine, like where a panic happened or the id
+//! of the panicking thread, is normalised first.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::runner::{self, Run};
+use crate::sandbox::Status;
+use crate::{Error, Lesson};
+
+/// One of the ways a lesson is run, each with a snapshot of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant<'a> {
+    /// Built for debugging, down the lesson's default path.
+    Default,
+    /// Down another of the lesson's paths.
+    Path(&'a str),
+    /// Built with `--release`.
+    Release,
+}
+
+impl<'a> Variant<'a> {
+    /// Every way `lesson` is run: the default first, then its other paths,
+    /// then its release build if it says what that does.
+    pub fn all(lesson: &'a Lesson) -> Vec<Variant<'a>> {
+        let mut variants = vec![Variant::Default];
+        variants.extend(lesson.paths.iter().map(|path| Variant::Path(path)));
+        if lesson.release.is_some() {
+            variants.push(Variant::Release);
+        }
+        variants
+    }
+
+    pub fn run(self, root: &Path, lesson: &Lesson) -> Result<Run, Error> {
+        match self {
+            Variant::Default => runner::run(root, lesson),
+            Variant::Path(path) => runner::run_path(root, lesson, path),
+            Variant::Release => runner::run_release(root, lesson),
+        }
+    }
+
+    /// The lesson's name, with the path or `release` after a `:` the way the
+    /// runner labels them.
+    pub fn label(self, lesson: &Lesson) -> String {
+        match self {
+            Variant::Default => lesson.name.clone(),
+            Variant::Path(path) => format!("{}:{}", lesson.name, path),
+            Variant::Release => format!("{}:release", lesson.name),
+        }
+    }
+
+    fn file_name(self, lesson: &Lesson) -> String {
+        match self {
+            Variant::Default => format!("{}.snap", lesson.name),
+            Variant::Path(path) => format!("{}.path.{}.snap", lesson.name, path),
+            Variant::Release => format!("{}.release.snap", lesson.name),
+        }
+    }
+}
+
+/// A run, cleaned up and ready to compare with the checked-in one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub status: String,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Snapshot {
+    pub fn of(run: &Run) -> Snapshot {
+        let status = match run.status {
+            Status::Exited(code) => format!("exit {}", code),
+            Status::Signalled(signal) => format!("killed by signal {}", signal),
+            Status::Exceeded(limit) => format!("stopped for using too much {}", limit),
+        };
+
+        Snapshot {
+            status,
+            stdout: run.stdout.clone(),
+            stderr: run
+                .stderr
+                .lines()
+                .map(normalise)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "status: {}", self.status)?;
+        writeln!(f, "--- stdout")?;
+        write!(f, "{}", self.stdout)?;
+        if !self.stdout.is_empty() && !self.stdout.ends_with('\n') {
+            writeln!(f)?;
+        }
+        writeln!(f, "--- stderr")?;
+        if !self.stderr.is_empty() {
+            writeln!(f, "{}", self.stderr)?;
+        }
+        Ok(())
+    }
+}
+
+/// Turns `thread 'main' (2094) panicked at data_types/src/main.rs:39:37:` into
+/// `thread 'main' panicked at $DIR/src/main.rs:LL:CC:`. The thread id is
+/// different every run, and the path depends on where cargo was run from.
+fn normalise(line: &str) -> String {
+    let line = match (line.find("thread '"), line.find(") panicked at ")) {
+        (Some(thread), Some(id_end)) if thread < id_end => {
+            let name_end = line[thread + "thread '".len()..].find('\'');
+            match name_end {
+                Some(name_end) => {
+                    let name_end = thread + "thread '".len() + name_end + 1;
+                    format!("{}{}", &line[..name_end], &line[id_end + 1..])
+                }
+                None => line.to_string(),
+            }
+        }
+        _ => line.to_string(),
+    };
+
+    let at = match line.find("panicked at ") {
+        Some(at) => at + "panicked at ".len(),
+        None => return line,
+    };
+    let location = line[at..].trim_end_matches(':');
+    let mut parts = location.rsplitn(3, ':');
+    let (column, row, path) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(column), Some(row), Some(path)) => (column, row, path),
+        _ => return line,
+    };
+    if column.parse::<u32>().is_err() || row.parse::<u32>().is_err() {
+        return line;
+    }
+
+    let path = match path.find("src/") {
+        Some(src) => format!("$DIR/{}", &path[src..]),
+        None => path.to_string(),
+    };
+    format!("{}{}:LL:CC:", &line[..at], path)
+}
+
+/// How a run compares to its checked-in snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    Same,
+    /// There is no snapshot for this lesson, or this variant of it, yet.
+    Missing,
+    /// The snapshot is different. This is a readable diff from the old one to
+    /// the new one.
+    Changed(String),
+}
+
+pub fn path(root: &Path, lesson: &Lesson, variant: Variant) -> PathBuf {
+    root.join("snapshots").join(variant.file_name(lesson))
+}
+
+pub fn compare(
+    root: &Path,
+    lesson: &Lesson,
+    variant: Variant,
+    snapshot: &Snapshot,
+) -> Result<Comparison, Error> {
+    let path = path(root, lesson, variant);
+    let old = match fs::read_to_string(&path) {
+        Ok(old) => old,
+        Err(_) if !path.exists() => return Ok(Comparison::Missing),
+        Err(error) => return Err(Error::Io { path, error }),
+    };
+
+    let new = snapshot.to_string();
+    if old == new {
+        Ok(Comparison::Same)
+    } else {
+        Ok(Comparison::Changed(diff(&old, &new)))
+    }
+}
+
+/// Writes (or overwrites) the snapshot of one variant of a lesson.
+pub fn update(
+    root: &Path,
+    lesson: &Lesson,
+    variant: Variant,
+    snapshot: &Snapshot,
+) -> Result<(), Error> {
+    let path = path(root, lesson, variant);
+    let write = || {
+        fs::create_dir_all(path.parent().unwrap_or(root))?;
+        fs::write(&path, snapshot.to_string())
+    };
+    write().map_err(|error| Error::Io {
+        path: path.clone(),
+        error,
+    })
+}
+
+/// A line-by-line diff, with `-` for lines only in `old`, `+` for lines only in
+/// `new`, and two spaces for lines in both.
+pub fn diff(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // The classic longest-common-subsequence table, built from the end so we
+    // can walk it forwards.
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push_str(&format!("  {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push_str(&format!("- {}\n", old[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+ {}\n", new[j]));
+            j += 1;
+        }
+    }
+
+    diff
+}
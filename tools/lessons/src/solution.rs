@@ -0,0 +1,272 @@
+//! The instructor's copy of the lessons and the student's copy, from the same
+//! source.
+//!
+//! The lessons are the instructor's copy. Code that students should write for
+//! themselves is marked, with the stub to leave in its place:
+//!
+//! ```text
+//! fn append_a(name: &mut String) {
+//!     // SOLUTION BEGIN: todo!("push an 'a' onto the end of the name")
+//!     // This is similar to the .push_str() function, except that it only pushes
+//!     // one character to the string.
+//!     name.push('a');
+//!     // SOLUTION END
+//! }
+//! ```
+//!
+//! The student's copy keeps every comment in a region, since those are the
+//! lesson, and swaps the code for the stub. Without a stub after the colon, the
+//! stub is `todo!()`. The markers themselves are left out of the slides.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::exercise;
+use crate::runner::{self, Verdict};
+use crate::state;
+use crate::{Error, Workspace};
+
+const BEGIN: &str = "// SOLUTION BEGIN";
+const END: &str = "// SOLUTION END";
+
+/// Whether a line is one of the markers, which are not part of the lesson.
+pub fn is_marker(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with(BEGIN) || line.starts_with(END)
+}
+
+/// The student's copy of a source file. Fails on markers that don't pair up.
+pub fn student(source: &str) -> Result<String, String> {
+    let mut student = String::new();
+    // The indentation and stub of the region we're in, and whether the stub has
+    // gone in yet.
+    let mut region: Option<(String, String, bool)> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let number = index + 1;
+        let trimmed = line.trim();
+
+        if let Some(rest) = trimmed.strip_prefix(BEGIN) {
+            if region.is_some() {
+                return Err(format!("line {}: solution regions can't nest", number));
+            }
+            let stub = match rest.trim() {
+                "" => "todo!()",
+                rest => match rest.strip_prefix(':') {
+                    Some(stub) if !stub.trim().is_empty() => stub.trim(),
+                    _ => return Err(format!("line {}: expected `{}: <stub>`", number, BEGIN)),
+                },
+            };
+            let indent = &line[..line.len() - line.trim_start().len()];
+            region = Some((indent.to_string(), stub.to_string(), false));
+            continue;
+        }
+
+        if trimmed.starts_with(END) {
+            match region.take() {
+                Some((indent, stub, false)) => {
+                    student.push_str(&format!("{}{}\n", indent, stub));
+                }
+                Some(_) => {}
+                None => return Err(format!("line {}: `{}` without a `{}`", number, END, BEGIN)),
+            }
+            continue;
+        }
+
+        match &mut region {
+            None => {}
+            // The lesson's prose stays, and so do blank lines between it.
+            Some(_) if trimmed.is_empty() || trimmed.starts_with("//") => {}
+            // The first line of code becomes the stub, and the rest goes.
+            Some((indent, stub, placed)) => {
+                if !*placed {
+                    student.push_str(&format!("{}{}\n", indent, stub));
+                    *placed = true;
+                }
+                continue;
+            }
+        }
+        student.push_str(line);
+        student.push('\n');
+    }
+
+    if region.is_some() {
+        return Err(format!("`{}` without a `{}`", BEGIN, END));
+    }
+    Ok(student)
+}
+
+/// Writes the student's copy of every lesson into `out`, as a workspace of its
+/// own. Returns the files it wrote.
+pub fn generate(workspace: &Workspace, out: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut written = Vec::new();
+    let mut members = Vec::new();
+
+    for lesson in &workspace.lessons {
+        let relative = lesson
+            .dir
+            .strip_prefix(&workspace.root)
+            .unwrap_or_else(|_| Path::new(&lesson.name));
+        members.push(relative.display().to_string());
+        let dir = out.join(relative);
+
+        copy(&lesson.dir.join("Cargo.toml"), &dir.join("Cargo.toml"))?;
+        written.push(dir.join("Cargo.toml"));
+
+        for source in rust_files(&lesson.dir.join("src"))? {
+            let text = read(&source)?;
+            let student = student(&text).map_err(|message| Error::Annotation {
+                path: source.clone(),
+                message,
+            })?;
+            let path = dir.join(source.strip_prefix(&lesson.dir).unwrap_or(&source));
+            write(&path, &student)?;
+            written.push(path);
+        }
+
+        // Exercises are already the student's copy, but their tests stay
+        // hidden.
+        for name in &lesson.exercises {
+            let file = Path::new("exercises").join(format!("{}.rs", name));
+            copy(&lesson.dir.join(&file), &dir.join(&file))?;
+            written.push(dir.join(file));
+        }
+    }
+
+    let members: Vec<String> = members
+        .iter()
+        .map(|member| format!("    \"{}\",", member))
+        .collect();
+    let manifest = format!("[workspace]\nmembers = [\n{}\n]\n", members.join("\n"));
+    let path = out.join("Cargo.toml");
+    write(&path, &manifest)?;
+    written.push(path);
+
+    Ok(written)
+}
+
+/// Checks both copies. The instructor's copy is the lessons: every lesson must
+/// build, do what its manifest says and pass its tests, and every exercise's
+/// solution must pass the hidden tests. The student's copy generated into
+/// `out` must build, even though it panics wherever a stub is reached. Returns
+/// what went wrong, if anything.
+pub fn check(workspace: &Workspace, out: &Path) -> Result<Vec<String>, Error> {
+    let mut problems = Vec::new();
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+    let run_cargo = |command: &mut Command| {
+        command.output().map_err(|error| Error::Io {
+            path: PathBuf::from(&cargo),
+            error,
+        })
+    };
+
+    for lesson in &workspace.lessons {
+        let run = match runner::run(&workspace.root, lesson) {
+            Ok(run) => run,
+            Err(Error::Build(_)) => {
+                problems.push(format!("{} doesn't build", lesson.name));
+                continue;
+            }
+            Err(error) => return Err(error),
+        };
+        let verdict = Verdict::judge(lesson, &run);
+        if !verdict.is_ok() {
+            problems.push(format!("{} {}", lesson.name, verdict));
+        }
+    }
+
+    // Only the lessons' own tests: the tools' would include this one.
+    let mut test = Command::new(&cargo);
+    test.args(["test", "--quiet", "--color", "never"])
+        .current_dir(&workspace.root);
+    for lesson in &workspace.lessons {
+        test.args(["-p", &lesson.name]);
+    }
+    let output = run_cargo(&mut test)?;
+    if !output.status.success() {
+        // Of what the tests printed, only the failures are worth showing.
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let failures = stdout
+            .find("\nfailures:")
+            .map_or("", |at| &stdout[at + 1..]);
+        problems.push(format!(
+            "the lessons' tests failed:\n{}{}",
+            failures,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    for exercise in exercise::all(workspace) {
+        let scratch = state::scratch(
+            &workspace.root,
+            &format!("{}-{}-solution", exercise.lesson, exercise.name),
+        );
+        let attempt = exercise::check(&exercise.solved(), &scratch)?;
+        if !attempt.passed() {
+            problems.push(format!(
+                "the solution to {}: {}\n{}",
+                exercise.id(),
+                attempt,
+                attempt.output()
+            ));
+        }
+    }
+
+    generate(workspace, out)?;
+    let output = run_cargo(
+        Command::new(&cargo)
+            .args(["check", "--workspace", "--quiet", "--color", "never"])
+            .current_dir(out)
+            .env("CARGO_TARGET_DIR", out.join("target"))
+            .stdout(Stdio::null()),
+    )?;
+    if !output.status.success() {
+        problems.push(format!(
+            "the student's copy doesn't build:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(problems)
+}
+
+fn rust_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let entries = fs::read_dir(dir).map_err(|error| Error::Io {
+        path: dir.to_path_buf(),
+        error,
+    })?;
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(rust_files(&path)?);
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|error| Error::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), Error> {
+    let io = |error| Error::Io {
+        path: path.to_path_buf(),
+        error,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io)?;
+    }
+    fs::write(path, contents).map_err(io)
+}
+
+fn copy(from: &Path, to: &Path) -> Result<(), Error> {
+    write(to, &read(from)?)
+}
@@ -0,0 +1,215 @@
+//! Splitting a lesson's `main.rs` into the prose of its comments and the code
+//! they talk about.
+//!
+//! The comments are the slides, so a lesson reads as alternating blocks: a run
+//! of `//` lines, then a run of code. Commented-out code fenced with ```` ``` ````
+//! (see [`compile_fail`](crate::compile_fail)) is code too, even though it
+//! lives in a comment. The [`solution`](crate::solution) markers and
+//! [`explain`](crate::explain) tags aren't part of either.
+
+use crate::{explain, solution};
+
+/// What kind of text a block holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Comment text, with the `//` taken off.
+    Prose,
+    /// Code that the lesson runs.
+    Code,
+    /// Code inside a fenced comment, which usually doesn't compile on purpose.
+    /// The first line is the fence's info string, like `compile_fail(E0382)`.
+    CommentedCode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub kind: Kind,
+    /// The 1-based line the block starts on in the source.
+    pub line: usize,
+    pub lines: Vec<String>,
+}
+
+impl Block {
+    /// For commented code, what the fence says about it.
+    pub fn info(&self) -> Option<&str> {
+        match self.kind {
+            Kind::CommentedCode => self.lines.first().map(String::as_str),
+            _ => None,
+        }
+    }
+
+    /// The lines of code, without the fence's info string.
+    pub fn code(&self) -> &[String] {
+        match self.kind {
+            Kind::CommentedCode => &self.lines[1..],
+            _ => &self.lines,
+        }
+    }
+}
+
+/// Splits source into blocks. Blank lines end a block, so two comments with a
+/// blank line between them are two blocks of prose.
+pub fn blocks(source: &str) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut current: Option<Block> = None;
+    let mut in_fence = false;
+
+    for (index, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        if solution::is_marker(trimmed) || explain::is_tag(trimmed) {
+            continue;
+        }
+        let comment = trimmed.strip_prefix("//").map(|text| {
+            // Keep any indentation past the single space after `//`, since that
+            // is how lists continue onto the next line.
+            text.strip_prefix(' ').unwrap_or(text).trim_end().to_string()
+        });
+
+        let (kind, text) = match comment {
+            Some(text) if in_fence && text.starts_with("```") => {
+                in_fence = false;
+                blocks.extend(current.take());
+                continue;
+            }
+            Some(text) if in_fence => (Kind::CommentedCode, text),
+            Some(text) if text.starts_with("```") => {
+                in_fence = true;
+                blocks.extend(current.take());
+                (Kind::CommentedCode, text[3..].trim().to_string())
+            }
+            Some(text) => (Kind::Prose, text),
+            None if trimmed.is_empty() => {
+                blocks.extend(current.take());
+                continue;
+            }
+            None => (Kind::Code, line.trim_end().to_string()),
+        };
+
+        match &mut current {
+            Some(block) if block.kind == kind => block.lines.push(text),
+            _ => {
+                blocks.extend(current.take());
+                current = Some(Block {
+                    kind,
+                    line: index + 1,
+                    lines: vec![text],
+                });
+            }
+        }
+    }
+
+    blocks.extend(current);
+    blocks
+}
+
+/// A block of prose, with the code that comes after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slide {
+    pub blocks: Vec<Block>,
+}
+
+/// Groups a lesson's blocks into slides. Code at the very top of a lesson, with
+/// no prose before it, gets a slide of its own.
+pub fn slides(source: &str) -> Vec<Slide> {
+    let mut slides: Vec<Slide> = Vec::new();
+    for block in blocks(source) {
+        match (block.kind, slides.last_mut()) {
+            (Kind::Code, Some(slide)) | (Kind::CommentedCode, Some(slide)) => {
+                slide.blocks.push(block)
+            }
+            _ => slides.push(Slide {
+                blocks: vec![block],
+            }),
+        }
+    }
+    slides
+}
+
+/// A piece of prose, once lines have been joined back into sentences.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Item {
+    Paragraph(String),
+    /// A list item like `1. The fn keyword.` or `a) having two mutable
+    /// references`. `depth` is 0 for the outermost list.
+    ListItem {
+        marker: String,
+        depth: usize,
+        text: String,
+    },
+    /// A definition like `&mut  => the mutable reference. ...`.
+    Definition {
+        term: String,
+        text: String,
+    },
+}
+
+/// Joins the lines of a prose block into paragraphs, list items and
+/// definitions. Lines that continue a list item or definition are indented.
+pub fn items(lines: &[String]) -> Vec<Item> {
+    let mut items: Vec<Item> = Vec::new();
+
+    for line in lines {
+        let indent = line.len() - line.trim_start().len();
+        let text = line.trim();
+
+        if let Some((marker, rest)) = list_marker(text) {
+            items.push(Item::ListItem {
+                marker: marker.to_string(),
+                depth: if indent == 0 { 0 } else { 1 },
+                text: rest.to_string(),
+            });
+            continue;
+        }
+        if let Some((term, rest)) = definition(text) {
+            items.push(Item::Definition {
+                term: term.to_string(),
+                text: rest.to_string(),
+            });
+            continue;
+        }
+
+        match items.last_mut() {
+            Some(Item::ListItem { text: item, .. }) | Some(Item::Definition { text: item, .. })
+                if indent > 0 =>
+            {
+                join(item, text)
+            }
+            Some(Item::Paragraph(paragraph)) => join(paragraph, text),
+            _ => items.push(Item::Paragraph(text.to_string())),
+        }
+    }
+
+    items
+}
+
+fn join(onto: &mut String, text: &str) {
+    if !onto.is_empty() {
+        onto.push(' ');
+    }
+    onto.push_str(text);
+}
+
+/// `1. ` or `a) ` at the start of a line.
+fn list_marker(text: &str) -> Option<(&str, &str)> {
+    let end = text.find(|c: char| !c.is_ascii_alphanumeric())?;
+    let (label, rest) = text.split_at(end);
+    let numbered = !label.is_empty() && label.chars().all(|c| c.is_ascii_digit());
+    let lettered = label.len() == 1 && label.chars().all(|c| c.is_ascii_lowercase());
+
+    if (numbered && rest.starts_with(". ")) || (lettered && rest.starts_with(") ")) {
+        Some((&text[..end + 1], rest[2..].trim_start()))
+    } else {
+        None
+    }
+}
+
+/// `term => text` at the start of a line, where the term is short and has no
+/// spaces in it, like `&` or `&mut`.
+fn definition(text: &str) -> Option<(&str, &str)> {
+    let arrow = text.find("=>")?;
+    let term = text[..arrow].trim();
+    if term.is_empty() || term.contains(' ') || term.len() > 8 {
+        return None;
+    }
+    Some((term, text[arrow + 2..].trim_start()))
+}
@@ -0,0 +1,40 @@
+//! Where `club` keeps what it remembers between runs: a `.club` directory at
+//! the root of the workspace, which git ignores and which is safe to delete.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub fn dir(root: &Path) -> PathBuf {
+    root.join(".club")
+}
+
+/// The last lesson that ran, which `club next` picks up from.
+pub fn last_run(root: &Path) -> PathBuf {
+    dir(root).join("last")
+}
+
+/// Whether the lesson `club watch` last ran passed.
+pub fn status(root: &Path) -> PathBuf {
+    dir(root).join("status")
+}
+
+/// Which exercises have been passed. See
+/// [`Progress`](crate::exercise::Progress).
+pub fn progress(root: &Path) -> PathBuf {
+    dir(root).join("progress")
+}
+
+/// Somewhere to compile things that aren't part of the workspace, like
+/// exercises.
+pub fn scratch(root: &Path, name: &str) -> PathBuf {
+    dir(root).join("build").join(name)
+}
+
+/// Writes one of the files above, making `.club` first if it isn't there.
+pub fn save(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents)
+}
@@ -0,0 +1,149 @@
+//! Just enough TOML to read lesson manifests.
+//!
+//! We only ever look at a single table at a time, and the only values lessons
+//! use are strings, integers, and arrays of strings, so that is all this
+//! understands. Arrays may span several lines.
+
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Array(Vec<String>),
+}
+
+/// The keys and values of one table.
+pub type Table = BTreeMap<String, Value>;
+
+/// Reads the `[name]` table out of a manifest.
+///
+/// Errors carry the (1-based) line number they happened on.
+pub fn table(manifest: &str, name: &str) -> Result<Option<Table>, String> {
+    let header = format!("[{}]", name);
+    let mut lines = manifest.lines().enumerate().peekable();
+
+    while let Some((_, line)) = lines.next() {
+        if strip_comment(line).trim() != header {
+            continue;
+        }
+
+        let mut table = Table::new();
+        while let Some(&(number, line)) = lines.peek() {
+            let line = strip_comment(line).trim().to_string();
+            if line.starts_with('[') {
+                break;
+            }
+            lines.next();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap_or("").trim().to_string();
+            let mut value = match parts.next() {
+                Some(value) => value.trim().to_string(),
+                None => return Err(format!("line {}: expected `key = value`", number + 1)),
+            };
+
+            // Keep reading lines until a multi-line array is closed.
+            if value.starts_with('[') {
+                while !value.ends_with(']') {
+                    match lines.next() {
+                        Some((_, more)) => {
+                            value.push(' ');
+                            value.push_str(strip_comment(more).trim());
+                        }
+                        None => return Err(format!("line {}: unclosed array", number + 1)),
+                    }
+                }
+            }
+
+            let value = parse_value(&value).map_err(|e| format!("line {}: {}", number + 1, e))?;
+            table.insert(key, value);
+        }
+        return Ok(Some(table));
+    }
+
+    Ok(None)
+}
+
+fn parse_value(value: &str) -> Result<Value, String> {
+    if value.starts_with('"') {
+        let (string, rest) = parse_string(value)?;
+        if !rest.trim().is_empty() {
+            return Err(format!("unexpected `{}` after string", rest.trim()));
+        }
+        return Ok(Value::String(string));
+    }
+
+    if let Some(rest) = value.strip_prefix('[') {
+        let mut items = Vec::new();
+        let mut rest = rest.trim_start();
+        loop {
+            if let Some(after) = rest.strip_prefix(']') {
+                if !after.trim().is_empty() {
+                    return Err(format!("unexpected `{}` after array", after.trim()));
+                }
+                return Ok(Value::Array(items));
+            }
+            let (item, after) = parse_string(rest)?;
+            items.push(item);
+            rest = after.trim_start();
+            rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+        }
+    }
+
+    value
+        .replace('_', "")
+        .parse()
+        .map(Value::Integer)
+        .map_err(|_| format!("`{}` is not a string, integer, or array of strings", value))
+}
+
+/// Parses a basic `"..."` string from the front of `input`, returning it and
+/// whatever follows the closing quote.
+fn parse_string(input: &str) -> Result<(String, &str), String> {
+    if !input.starts_with('"') {
+        return Err(format!("expected a string, found `{}`", input));
+    }
+
+    let mut string = String::new();
+    let mut chars = input.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok((string, &input[index + 1..])),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => string.push('\n'),
+                Some((_, 't')) => string.push('\t'),
+                Some((_, '"')) => string.push('"'),
+                Some((_, '\\')) => string.push('\\'),
+                Some((_, other)) => return Err(format!("unknown escape `\\{}`", other)),
+                None => break,
+            },
+            c => string.push(c),
+        }
+    }
+
+    Err(String::from("unterminated string"))
+}
+
+/// Drops a `#` comment from the end of a line, as long as it isn't inside a
+/// string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '\\' if in_string => {
+                escaped = !escaped;
+                continue;
+            }
+            '"' if !escaped => in_string = !in_string,
+            '#' if !in_string => return &line[..index],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
@@ -0,0 +1,14 @@
+//! What the tests share.
+
+use std::path::Path;
+
+use lessons::Workspace;
+
+/// The workspace these tests are in, with its manifests loaded.
+pub fn workspace() -> Workspace {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .canonicalize()
+        .unwrap();
+    Workspace::load(&root).expect("the lesson manifests should load")
+}
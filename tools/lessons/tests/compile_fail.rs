@@ -0,0 +1,51 @@
+//! Uncomments each `compile_fail` snippet in the lessons, one at a time, and
+//! checks that rustc still rejects it with the errors the lesson talks about.
+
+use std::path::Path;
+
+use lessons::compile_fail;
+
+mod common;
+
+use common::workspace;
+
+#[test]
+fn snippets_fail_to_compile_as_annotated() {
+    let scratch = Path::new(env!("CARGO_TARGET_TMPDIR")).join("compile_fail");
+    let mut failures = Vec::new();
+
+    for lesson in &workspace().lessons {
+        let results = compile_fail::check(lesson, &scratch).expect("checking should run");
+        for checked in results {
+            if let Err(failure) = checked.result {
+                failures.push(format!(
+                    "{}:{}: {}",
+                    lesson.main().display(),
+                    checked.snippet.line,
+                    failure
+                ));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}\n", failures.join("\n"));
+}
+
+#[test]
+fn snippets_cover_the_errors_the_lessons_quote() {
+    let mut codes = Vec::new();
+    for lesson in &workspace().lessons {
+        let source = std::fs::read_to_string(lesson.main()).unwrap();
+        for snippet in compile_fail::snippets(&source).unwrap() {
+            codes.extend(snippet.codes);
+        }
+    }
+
+    for code in &["E0277", "E0282", "E0382", "E0499"] {
+        assert!(
+            codes.iter().any(|found| found == code),
+            "no snippet checks {}",
+            code
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Checks every exercise's hidden tests against the stub it ships with and
+//! against its solution, and keeps track of progress through them.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use lessons::exercise::{self, Attempt, Exercise, Progress};
+use lessons::state;
+
+mod common;
+
+fn scratch() -> PathBuf {
+    static TESTS: AtomicUsize = AtomicUsize::new(0);
+    let test = TESTS.fetch_add(1, Ordering::SeqCst);
+    env::temp_dir().join(format!(
+        "club-exercise-test-{}-{}",
+        std::process::id(),
+        test
+    ))
+}
+
+/// Checks `exercise` as if the student's copy were `source`.
+fn attempt(exercise: &Exercise, source: &str) -> Attempt {
+    let scratch = scratch();
+    fs::create_dir_all(&scratch).unwrap();
+    let copy = scratch.join(format!("{}.rs", exercise.name));
+    fs::write(&copy, source).unwrap();
+    let exercise = Exercise {
+        path: copy,
+        ..exercise.clone()
+    };
+    let attempt = exercise::check(&exercise, &scratch.join("build")).unwrap();
+    let _ = fs::remove_dir_all(&scratch);
+    attempt
+}
+
+fn add() -> Exercise {
+    let exercises = exercise::all(&common::workspace());
+    exercise::find(&exercises, "add").unwrap().clone()
+}
+
+fn stub(exercise: &Exercise) -> String {
+    fs::read_to_string(&exercise.path).unwrap()
+}
+
+#[test]
+fn every_exercise_fails_as_shipped_and_passes_when_solved() {
+    for exercise in exercise::all(&common::workspace()) {
+        match attempt(&exercise, &stub(&exercise)) {
+            Attempt::Unfinished(_) => {}
+            other => panic!("{} as shipped: {:?}", exercise.id(), other),
+        }
+        let solution = fs::read_to_string(&exercise.solution).unwrap();
+        assert_eq!(
+            attempt(&exercise, &solution),
+            Attempt::Passed,
+            "{}",
+            exercise.id()
+        );
+    }
+}
+
+#[test]
+fn the_lessons_own_add_passes_the_add_tests() {
+    let add = add();
+    let lesson = common::workspace()
+        .get("variables_and_functions")
+        .unwrap()
+        .main();
+    let lesson = fs::read_to_string(lesson).unwrap();
+    assert!(lesson.contains("\n    first + second\n"));
+    let solved = stub(&add).replace("todo!()", "first + second");
+    assert_eq!(attempt(&add, &solved), Attempt::Passed);
+}
+
+#[test]
+fn a_wrong_answer_fails_the_tests() {
+    let add = add();
+    let wrong = stub(&add).replace("todo!()", "first - second");
+    match attempt(&add, &wrong) {
+        Attempt::Failed(output) => assert!(output.contains("adds_two_numbers"), "{}", output),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn code_that_doesnt_compile_is_a_compile_error() {
+    let add = add();
+    let broken = stub(&add).replace("todo!()", "first + \"second\"");
+    match attempt(&add, &broken) {
+        Attempt::CompileError(output) => assert!(output.contains("E0277"), "{}", output),
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn a_missing_student_copy_is_an_error() {
+    let add = Exercise {
+        path: scratch().join("add.rs"),
+        ..add()
+    };
+    assert!(exercise::check(&add, &scratch()).is_err());
+}
+
+#[test]
+fn exercises_are_found_by_id_or_by_unambiguous_name() {
+    let exercises = exercise::all(&common::workspace());
+    let find = |name| exercise::find(&exercises, name).map(Exercise::id);
+    assert_eq!(find("append_a").as_deref(), Some("borrowing/append_a"));
+    assert_eq!(
+        find("borrowing/say_hello").as_deref(),
+        Some("borrowing/say_hello")
+    );
+    assert_eq!(find("nope"), None);
+}
+
+#[test]
+fn progress_round_trips_through_the_progress_file() {
+    let root = scratch();
+    let exercises = exercise::all(&common::workspace());
+    assert_eq!(Progress::load(&root), Progress::default());
+
+    let mut progress = Progress::load(&root);
+    progress.complete(&root, &exercises[2]).unwrap();
+    progress.complete(&root, &exercises[0]).unwrap();
+    // Doing one again doesn't count it twice.
+    progress.complete(&root, &exercises[2]).unwrap();
+
+    assert_eq!(state::progress(&root), root.join(".club").join("progress"));
+    let loaded = Progress::load(&root);
+    assert_eq!(loaded, progress);
+    assert_eq!(loaded.done, [exercises[2].id(), exercises[0].id()]);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn next_is_the_first_exercise_not_done() {
+    let exercises = exercise::all(&common::workspace());
+    let mut progress = Progress::default();
+    assert_eq!(progress.next(&exercises), Some(&exercises[0]));
+
+    progress.done = vec![exercises[0].id(), exercises[2].id()];
+    assert_eq!(progress.next(&exercises), Some(&exercises[1]));
+
+    progress.done = exercises.iter().map(Exercise::id).collect();
+    assert_eq!(progress.next(&exercises), None);
+}
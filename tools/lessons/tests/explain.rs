@@ -0,0 +1,61 @@
+//! Checks that the error codes the lessons map to explanations point at tagged
+//! sections that exist, and that the errors the lessons show off are covered.
+
+use std::fs;
+
+use lessons::compile_fail;
+use lessons::explain::{self, Explainer};
+
+mod common;
+
+use common::workspace;
+
+#[test]
+fn sections_run_until_the_comments_stop() {
+    let source = "\
+// EXPLAIN: moves
+// `name` was moved into the function.
+// It can't be used again.
+
+fn main() {}
+";
+    let sections = explain::sections(source);
+    assert_eq!(sections.len(), 1);
+    let (tag, line, lines) = &sections[0];
+    assert_eq!(tag, "moves");
+    assert_eq!(*line, 2);
+    assert_eq!(
+        lines,
+        &[
+            "`name` was moved into the function.",
+            "It can't be used again."
+        ]
+    );
+}
+
+#[test]
+fn compile_fail_errors_are_explained() {
+    let workspace = workspace();
+    let explainer = Explainer::load(&workspace).expect("every mapped tag should exist");
+    let mut missing = Vec::new();
+
+    for lesson in &workspace.lessons {
+        if lesson.explanations.is_empty() {
+            continue;
+        }
+        let source = fs::read_to_string(lesson.main()).unwrap();
+        for snippet in compile_fail::snippets(&source).unwrap() {
+            for code in &snippet.codes {
+                match explainer.explain(code, Some(&lesson.name)) {
+                    Some(section) if section.lesson == lesson.name => {}
+                    _ => missing.push(format!("{}:{}: {}", lesson.name, snippet.line, code)),
+                }
+            }
+        }
+    }
+    assert!(
+        missing.is_empty(),
+        "unexplained errors:\n{}",
+        missing.join("\n")
+    );
+}
@@ -0,0 +1,37 @@
+//! Reads JSON that's been made to trip the reader up: broken surrogate pairs
+//! and arrays nested far deeper than anything real.
+
+use lessons::json::{Json, MAX_DEPTH};
+
+#[test]
+fn surrogate_pairs_are_joined() {
+    assert_eq!(
+        Json::parse(r#""\uD83E\uDD80""#),
+        Ok(Json::from("\u{1F980}"))
+    );
+}
+
+#[test]
+fn a_high_surrogate_needs_a_low_one_after_it() {
+    let error = Json::parse(r#""\uD800\u0041""#).unwrap_err();
+    assert!(error.contains("surrogate"), "{}", error);
+    assert!(Json::parse(r#""\uD800\uD800""#).is_err());
+}
+
+#[test]
+fn nesting_is_allowed_up_to_the_limit() {
+    let deepest = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+    assert!(Json::parse(&deepest).is_ok());
+    let objects = format!("{}1{}", "{\"a\":".repeat(MAX_DEPTH), "}".repeat(MAX_DEPTH));
+    assert!(Json::parse(&objects).is_ok());
+
+    let deeper = format!("[{}]", deepest);
+    let error = Json::parse(&deeper).unwrap_err();
+    assert!(error.contains("nested"), "{}", error);
+}
+
+#[test]
+fn a_megabyte_of_brackets_is_an_error_not_a_crash() {
+    assert!(Json::parse(&"[".repeat(1 << 20)).is_err());
+    assert!(Json::parse(&"{\"a\":".repeat(1 << 18)).is_err());
+}
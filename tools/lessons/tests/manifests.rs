@@ -0,0 +1,200 @@
+//! Checks the lesson manifests, made up for each test, on their own and
+//! together.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lessons::{validate, Error, Lesson, Outcome, Problem};
+
+fn lesson(name: &str, position: u32, prerequisites: &[&str]) -> Lesson {
+    Lesson {
+        name: name.to_string(),
+        dir: PathBuf::from(name),
+        edition: String::from("2018"),
+        title: name.to_string(),
+        position,
+        prerequisites: prerequisites.iter().map(|s| s.to_string()).collect(),
+        concepts: Vec::new(),
+        outcome: Outcome::Success,
+        release: None,
+        paths: Vec::new(),
+        path_outcomes: BTreeMap::new(),
+        exercises: Vec::new(),
+        explanations: BTreeMap::new(),
+    }
+}
+
+/// Writes `club` as the `[package.metadata.club]` table of a crate called
+/// `name`, and loads it.
+fn load(name: &str, club: &str) -> Result<Option<Lesson>, Error> {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("manifests")
+        .join(name);
+    fs::create_dir_all(&dir).unwrap();
+    let manifest = format!(
+        "[package]\nname = \"{}\"\nedition = \"2018\"\n\n[package.metadata.club]\n{}",
+        name, club
+    );
+    fs::write(dir.join("Cargo.toml"), manifest).unwrap();
+    Lesson::load(&dir)
+}
+
+fn manifest_error(result: Result<Option<Lesson>, Error>) -> String {
+    match result {
+        Err(Error::Manifest { message, .. }) => message,
+        other => panic!("expected a manifest error, got {:?}", other),
+    }
+}
+
+#[test]
+fn lessons_in_order_have_no_problems() {
+    let lessons = [
+        lesson("hello_world", 1, &[]),
+        lesson("data_types", 2, &["hello_world"]),
+        lesson("borrowing", 3, &["hello_world", "data_types"]),
+    ];
+    assert_eq!(validate(&lessons), []);
+}
+
+#[test]
+fn a_prerequisite_that_isnt_a_lesson_is_missing() {
+    let lessons = [lesson("data_types", 1, &["hello_wrold"])];
+    assert_eq!(
+        validate(&lessons),
+        [Problem::MissingPrerequisite {
+            lesson: String::from("data_types"),
+            missing: String::from("hello_wrold"),
+        }]
+    );
+}
+
+#[test]
+fn lessons_that_require_each_other_are_a_cycle() {
+    let lessons = [
+        lesson("a", 1, &["c"]),
+        lesson("b", 2, &["a"]),
+        lesson("c", 3, &["b"]),
+        lesson("d", 4, &["a"]),
+    ];
+    // The cycle is only reported once, however many lessons lead into it.
+    assert_eq!(
+        validate(&lessons),
+        [Problem::Cycle(vec![
+            String::from("a"),
+            String::from("c"),
+            String::from("b"),
+            String::from("a"),
+        ])]
+    );
+
+    let lessons = [lesson("a", 1, &["a"])];
+    assert_eq!(
+        validate(&lessons),
+        [Problem::Cycle(vec![String::from("a"), String::from("a")])]
+    );
+}
+
+#[test]
+fn two_lessons_cant_share_a_position() {
+    let lessons = [
+        lesson("a", 1, &[]),
+        lesson("b", 2, &[]),
+        lesson("c", 2, &[]),
+    ];
+    let problems = validate(&lessons);
+    assert_eq!(
+        problems,
+        [Problem::DuplicatePosition {
+            position: 2,
+            lessons: vec![String::from("b"), String::from("c")],
+        }]
+    );
+    assert_eq!(problems[0].to_string(), "lessons b, c all have position 2");
+}
+
+#[test]
+fn a_manifest_is_read_into_a_lesson() {
+    let lesson = load(
+        "loaded",
+        "title = \"Loaded\"\nposition = 7\nprerequisites = [\"hello_world\"]\noutcome = \"panic\"\npanic = \"oh no\"\n",
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(lesson.position, 7);
+    assert_eq!(lesson.prerequisites, ["hello_world"]);
+    assert_eq!(
+        lesson.outcome,
+        Outcome::Panic {
+            message: Some(String::from("oh no")),
+        }
+    );
+    assert_eq!(lesson.release, None);
+}
+
+#[test]
+fn a_crate_without_the_table_isnt_a_lesson() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("manifests")
+        .join("tool");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("Cargo.toml"), "[package]\nname = \"tool\"\n").unwrap();
+    assert!(Lesson::load(&dir).unwrap().is_none());
+}
+
+#[test]
+fn an_unknown_outcome_is_an_error() {
+    let message = manifest_error(load(
+        "unknown_outcome",
+        "title = \"T\"\nposition = 1\noutcome = \"explode\"\n",
+    ));
+    assert_eq!(
+        message,
+        "unknown outcome \"explode\" (expected \"success\", \"panic\" or \"output\")"
+    );
+
+    let message = manifest_error(load(
+        "unknown_release_outcome",
+        "title = \"T\"\nposition = 1\n\n[package.metadata.club.release]\noutcome = \"explode\"\n",
+    ));
+    assert!(message.starts_with("in `package.metadata.club.release`: unknown outcome"));
+}
+
+#[test]
+fn a_path_can_say_what_it_does() {
+    let lesson = load(
+        "path_outcomes",
+        "title = \"T\"\nposition = 1\npaths = [\"safe\", \"crash\"]\n\n[package.metadata.club.path.crash]\noutcome = \"panic\"\npanic = \"oh no\"\n",
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(lesson.paths, ["safe", "crash"]);
+    assert_eq!(lesson.path_outcomes.len(), 1);
+    assert_eq!(
+        lesson.path_outcomes["crash"],
+        Outcome::Panic {
+            message: Some(String::from("oh no")),
+        }
+    );
+
+    let message = manifest_error(load(
+        "unknown_path_outcome",
+        "title = \"T\"\nposition = 1\npaths = [\"crash\"]\n\n[package.metadata.club.path.crash]\noutcome = \"explode\"\n",
+    ));
+    assert!(message.starts_with("in `package.metadata.club.path.crash`: unknown outcome"));
+}
+
+#[test]
+fn a_position_has_to_fit_in_a_u32() {
+    let message = manifest_error(load(
+        "huge_position",
+        "title = \"T\"\nposition = 4294967296\n",
+    ));
+    assert_eq!(message, "`position` 4294967296 is too big");
+
+    let message = manifest_error(load("zero_position", "title = \"T\"\nposition = 0\n"));
+    assert_eq!(message, "`position` must be a positive integer");
+
+    let lesson = load("biggest_position", "title = \"T\"\nposition = 4294967295\n");
+    assert_eq!(lesson.unwrap().unwrap().position, u32::MAX);
+}
@@ -0,0 +1,74 @@
+//! Replays the lessons' ownership traces, which checks them against the
+//! borrowing rules, and makes sure the rules are actually enforced.
+
+use lessons::ownership::{self, Trace};
+
+mod common;
+
+use common::workspace;
+
+#[test]
+fn lesson_traces_replay() {
+    let workspace = workspace();
+    for name in ["variables_and_functions", "borrowing"] {
+        let lesson = workspace.get(name).unwrap();
+        let (trace, states) = ownership::load(lesson)
+            .unwrap_or_else(|error| panic!("{}", error))
+            .unwrap_or_else(|| panic!("{} should have a trace", name));
+        assert_eq!(trace.steps.len(), states.len());
+        // The lines still point at code, after any edits to the lesson.
+        let source = std::fs::read_to_string(lesson.main()).unwrap();
+        for step in &trace.steps {
+            let line = source.lines().nth(step.line - 1).unwrap_or("").trim();
+            assert!(
+                !line.is_empty() && !line.starts_with("//"),
+                "{}:{} isn't code, for `{}`",
+                name,
+                step.line,
+                step.event
+            );
+        }
+        // Every value is dropped by the end.
+        let last = states.last().unwrap();
+        assert!(last.values.iter().all(|value| value.dropped), "{}", name);
+        assert!(last.borrows.is_empty(), "{}", name);
+    }
+}
+
+#[test]
+fn traces_follow_the_borrowing_rules() {
+    let replay = |text: &str| Trace::parse(text).unwrap().replay();
+
+    let two_mutable = "1: new name \"Gabe\"\n2: borrow_mut a name\n3: borrow_mut b name\n";
+    assert!(replay(two_mutable).is_err());
+
+    let shared_and_mutable = "1: new name \"Gabe\"\n2: borrow a name\n3: borrow_mut b name\n";
+    assert!(replay(shared_and_mutable).is_err());
+
+    let use_after_move = "1: new name \"Gabe\"\n2: move name other\n3: borrow a name\n";
+    assert!(replay(use_after_move).is_err());
+
+    let change_through_shared = "1: new name \"Gabe\"\n2: borrow a name\n3: change a \"Gabe!\"\n";
+    assert!(replay(change_through_shared).is_err());
+
+    let shared_twice = "1: new name \"Gabe\"\n2: borrow a name\n3: borrow b name\n4: return a\n5: return b\n6: drop name\n";
+    assert!(replay(shared_twice).is_ok());
+}
+
+#[test]
+fn graphs_draw_ownership_and_both_kinds_of_borrow() {
+    let trace =
+        Trace::parse("1: new name \"Gabe\"\n2: borrow a name\n3: return a\n4: borrow_mut b name\n")
+            .unwrap();
+    let states = trace.replay().unwrap();
+
+    let shared = states[1].dot("shared");
+    assert!(shared.contains("[shape=box, label=\"name\"]"));
+    assert!(shared.contains("[shape=circle, label=\"\\\"Gabe\\\"\"]"));
+    assert!(shared.contains("owner0 -> value0;"));
+    assert!(shared.contains("style=dashed"));
+
+    let mutable = states[3].dot("mutable");
+    assert!(mutable.contains("style=bold"));
+    assert!(!mutable.contains("style=dashed"));
+}
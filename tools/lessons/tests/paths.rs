@@ -0,0 +1,75 @@
+//! Runs the lessons' other paths, which have to exit cleanly unless their
+//! lesson says otherwise.
+
+use lessons::runner::{self, Verdict};
+
+mod common;
+
+use common::workspace;
+
+#[test]
+fn every_path_does_what_its_lesson_says() {
+    let workspace = workspace();
+    for lesson in &workspace.lessons {
+        for path in &lesson.paths {
+            let run = runner::run_path(&workspace.root, lesson, path)
+                .expect("the lesson should build and run");
+            let verdict = Verdict::judge_path(lesson, path, &run);
+            assert!(
+                verdict.is_ok(),
+                "{} with {}={} {}:\n{}",
+                lesson.name,
+                runner::PATH_VARIABLE,
+                path,
+                verdict,
+                run.stderr
+            );
+        }
+    }
+}
+
+#[test]
+fn data_types_slices_safely_on_its_safe_path() {
+    let workspace = workspace();
+    let lesson = workspace.get("data_types").unwrap();
+    assert_eq!(lesson.paths, ["safe", "index"]);
+
+    let run = runner::run_path(&workspace.root, lesson, "safe").unwrap();
+    for line in [
+        "    '你' is bytes 0..3, U+4F60, 11100100 10111101 10100000",
+        "    [0..1] would crash: 1 is inside '你'",
+        "    [3..6] is \"好\"",
+        "hello_str.get(0..1) is None",
+        "hello_str.get(0..3) is Some(\"你\")",
+        "utf8::slice_chars(hello_str, 0..1) is Some(\"你\")",
+        "    'ß' is bytes 0..2, U+00DF, 11000011 10011111",
+        "esstzet_str.get(0..1) is None",
+        "array.get(5) is None",
+    ] {
+        assert!(
+            run.stdout.lines().any(|printed| printed == line),
+            "expected {:?} in:\n{}",
+            line,
+            run.stdout
+        );
+    }
+    assert_eq!(Verdict::judge_path(lesson, "safe", &run), Verdict::Clean);
+}
+
+#[test]
+fn data_types_indexes_out_of_bounds_on_its_index_path() {
+    let workspace = workspace();
+    let lesson = workspace.get("data_types").unwrap();
+
+    let run = runner::run_path(&workspace.root, lesson, "index").unwrap();
+    assert!(run.panicked());
+    assert!(run
+        .stderr
+        .contains("index out of bounds: the len is 5 but the index is 5"));
+    // It gets past the slicing crash first.
+    assert!(!run.stderr.contains("is not a char boundary"));
+    assert_eq!(
+        Verdict::judge_path(lesson, "index", &run),
+        Verdict::ExpectedPanic
+    );
+}
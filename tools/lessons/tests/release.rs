@@ -0,0 +1,59 @@
+//! Runs the lessons that do something different when built with `--release`.
+
+use lessons::runner::{self, Verdict};
+use lessons::Outcome;
+
+mod common;
+
+use common::workspace;
+
+#[test]
+fn every_release_build_does_what_its_lesson_says() {
+    let workspace = workspace();
+    for lesson in workspace
+        .lessons
+        .iter()
+        .filter(|lesson| lesson.release.is_some())
+    {
+        let run =
+            runner::run_release(&workspace.root, lesson).expect("the lesson should build and run");
+        let verdict = Verdict::judge_release(lesson, &run);
+        assert!(
+            verdict.is_ok(),
+            "{} {}:\n{}",
+            lesson.name,
+            verdict,
+            run.stderr
+        );
+    }
+}
+
+#[test]
+fn overflow_panics_in_debug_and_wraps_in_release() {
+    let workspace = workspace();
+    let lesson = workspace.get("overflow").unwrap();
+    assert_eq!(lesson.release, Some(Outcome::Success));
+
+    let debug = runner::run(&workspace.root, lesson).unwrap();
+    assert_eq!(Verdict::judge(lesson, &debug), Verdict::ExpectedPanic);
+    assert!(debug.stderr.contains("attempt to add with overflow"));
+
+    let release = runner::run_release(&workspace.root, lesson).unwrap();
+    assert_eq!(Verdict::judge_release(lesson, &release), Verdict::Clean);
+
+    // Everything before the last line is the same either way, including
+    // the properties that held.
+    let release: Vec<&str> = release.stdout.lines().collect();
+    let (last, before) = release.split_last().unwrap();
+    assert_eq!(*last, "add(i32::MAX, 1) is -2147483648");
+    assert_eq!(debug.stdout.lines().collect::<Vec<_>>(), before);
+    assert_eq!(
+        before
+            .iter()
+            .filter(|line| line.starts_with("ok: "))
+            .count(),
+        4,
+        "{}",
+        debug.stdout
+    );
+}
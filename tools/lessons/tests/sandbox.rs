@@ -0,0 +1,222 @@
+//! Compiles and runs snippets that misbehave in different ways, the way the
+//! playground would, and checks that the sandbox says which limit stopped them.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use lessons::execute::{execute, Mode, Request, Response};
+use lessons::sandbox::{self, Limit, Limits, Status};
+
+fn scratch() -> PathBuf {
+    static TESTS: AtomicUsize = AtomicUsize::new(0);
+    let test = TESTS.fetch_add(1, Ordering::SeqCst);
+    env::temp_dir().join(format!("club-sandbox-test-{}-{}", std::process::id(), test))
+}
+
+fn run(code: &str) -> Response {
+    let request = Request {
+        channel: String::from("stable"),
+        mode: Mode::Debug,
+        edition: String::from("2021"),
+        tests: false,
+        backtrace: false,
+        code: code.to_string(),
+    };
+    let scratch = scratch();
+    let response = execute(&request, &scratch).expect("the snippet should compile and run");
+    let _ = fs::remove_dir_all(&scratch);
+    response
+}
+
+/// Compiles `code` and runs it in the sandbox with `limits`, for limits the
+/// playground doesn't use.
+fn run_with(code: &str, limits: &Limits) -> Status {
+    let scratch = scratch();
+    fs::create_dir_all(&scratch).unwrap();
+    fs::write(scratch.join("main.rs"), code).unwrap();
+    let compiled = Command::new("rustc")
+        .args(["--edition", "2021", "-o", "main", "main.rs"])
+        .current_dir(&scratch)
+        .status()
+        .unwrap();
+    assert!(compiled.success());
+    let ran = sandbox::run(&scratch.join("main"), &[], limits).unwrap();
+    let _ = fs::remove_dir_all(&scratch);
+    ran.status
+}
+
+#[test]
+fn a_well_behaved_snippet_runs_to_the_end() {
+    let response = run("fn main() { println!(\"hello\"); }");
+    assert!(response.success, "{}", response.stderr);
+    assert_eq!(response.stdout, "hello\n");
+}
+
+#[test]
+fn a_compile_error_is_reported_without_running_anything() {
+    let response = run("fn main() { let x: u8 = \"no\"; }");
+    assert!(!response.success);
+    assert_eq!(response.stdout, "");
+    assert!(response.stderr.contains("E0308"), "{}", response.stderr);
+}
+
+#[test]
+fn a_program_only_fails_by_what_it_does_not_what_it_prints() {
+    let response = run("fn main() { eprintln!(\"memory allocation of (os error 11)\"); }");
+    assert!(response.success, "{}", response.stderr);
+}
+
+#[test]
+fn an_infinite_loop_is_stopped() {
+    let response = run("fn main() { loop { std::hint::black_box(()); } }");
+    assert!(!response.success);
+    assert!(
+        response.stderr.contains("too much CPU time"),
+        "{}",
+        response.stderr
+    );
+}
+
+#[test]
+fn too_much_output_is_stopped() {
+    let response = run("fn main() { loop { println!(\"spam\"); } }");
+    assert!(!response.success);
+    assert!(
+        response.stderr.contains("too much output"),
+        "{}",
+        response.stderr
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn too_much_memory_is_stopped() {
+    let response =
+        run("fn main() { let mut v = Vec::new(); loop { v.push(vec![1u8; 1 << 20]); } }");
+    assert!(!response.success);
+    assert!(
+        response.stderr.contains("too much memory"),
+        "{}",
+        response.stderr
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn address_space_that_runs_out_is_too_much_memory() {
+    let limits = Limits {
+        memory: 256 << 20,
+        ..Limits::default()
+    };
+    // Reserved but never touched, so it's never resident, slowly enough for
+    // the address space to be seen growing until the backstop stops it.
+    let status = run_with(
+        "fn main() {
+            let mut reserved = Vec::new();
+            loop {
+                reserved.push(Vec::<u8>::with_capacity(64 << 20));
+                std::thread::sleep(std::time::Duration::from_millis(20));
+            }
+        }",
+        &limits,
+    );
+    assert_eq!(status, Status::Exceeded(Limit::Memory));
+}
+
+#[cfg(unix)]
+#[test]
+fn aborting_without_using_memory_is_just_a_signal() {
+    // One allocation far past the address space fails before it uses any.
+    let status = run_with(
+        "fn main() { let v = vec![1u8; 3 << 30]; println!(\"{}\", v[0]); }",
+        &Limits::default(),
+    );
+    assert_eq!(status, Status::Signalled(6));
+
+    let status = run_with("fn main() { std::process::abort(); }", &Limits::default());
+    assert_eq!(status, Status::Signalled(6));
+}
+
+#[cfg(unix)]
+#[test]
+fn output_held_open_outside_the_group_only_waits_for_the_wall_clock() {
+    let limits = Limits {
+        wall_time: Duration::from_secs(1),
+        ..Limits::default()
+    };
+    let dir = scratch();
+    fs::create_dir_all(&dir).unwrap();
+    let started = Instant::now();
+    // The sleep leaves the group, so killing the group doesn't close the
+    // pipes it inherited.
+    let ran = sandbox::run_in(
+        &dir,
+        Path::new("/bin/sh"),
+        &["-c", "setsid sleep 20 & echo hi"],
+        &sandbox::toolchain_env(),
+        &limits,
+    )
+    .unwrap();
+    let _ = fs::remove_dir_all(&dir);
+    assert!(
+        started.elapsed() < Duration::from_secs(5),
+        "{:?}",
+        started.elapsed()
+    );
+    assert_eq!(ran.status, Status::Exceeded(Limit::WallTime));
+    assert_eq!(ran.stdout, "hi\n");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn ignoring_sigxcpu_still_runs_out_of_cpu_time() {
+    let limits = Limits {
+        cpu_time: Duration::from_secs(1),
+        wall_time: Duration::from_secs(20),
+        ..Limits::default()
+    };
+    let status = run_with(
+        "extern \"C\" { fn signal(signal: i32, handler: usize) -> usize; }
+        fn main() {
+            // SIG_IGN, so only the hard limit's SIGKILL stops it.
+            unsafe { signal(24, 1) };
+            loop { std::hint::black_box(()); }
+        }",
+        &limits,
+    );
+    assert_eq!(status, Status::Exceeded(Limit::CpuTime));
+}
+
+#[cfg(unix)]
+#[test]
+fn other_signals_are_reported_as_they_are() {
+    let status = run_with(
+        "extern \"C\" { fn raise(signal: i32) -> i32; }
+        fn main() { unsafe { raise(15) }; }",
+        &Limits::default(),
+    );
+    assert_eq!(status, Status::Signalled(15));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn too_many_threads_are_stopped() {
+    let response = run(
+        "fn main() {
+            let threads: Vec<_> = (0..32)
+                .map(|_| std::thread::spawn(|| std::thread::sleep(std::time::Duration::from_secs(5))))
+                .collect();
+            for thread in threads { let _ = thread.join(); }
+        }",
+    );
+    assert!(!response.success);
+    assert!(
+        response.stderr.contains("too much processes"),
+        "{}",
+        response.stderr
+    );
+}
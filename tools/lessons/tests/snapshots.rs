@@ -0,0 +1,36 @@
+//! Runs every lesson, down each of its paths and built for release as well if
+//! it says what that does, and compares what it prints with its snapshots.
+//!
+//! If a lesson's output changes on purpose, update the snapshots with
+//! `cargo run -p club -- snapshot --update`.
+
+use lessons::snapshot::{self, Comparison, Snapshot, Variant};
+
+mod common;
+
+#[test]
+fn lessons_match_their_snapshots() {
+    let workspace = common::workspace();
+    let root = &workspace.root;
+    let mut failures = Vec::new();
+
+    for lesson in &workspace.lessons {
+        for variant in Variant::all(lesson) {
+            let run = variant
+                .run(root, lesson)
+                .expect("the lesson should build and run");
+            let label = variant.label(lesson);
+            match snapshot::compare(root, lesson, variant, &Snapshot::of(&run)).unwrap() {
+                Comparison::Same => {}
+                Comparison::Missing => failures.push(format!("{} has no snapshot", label)),
+                Comparison::Changed(diff) => failures.push(format!("{} changed:\n{}", label, diff)),
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "\n{}\nrun `cargo run -p club -- snapshot --update` if this is on purpose\n",
+        failures.join("\n")
+    );
+}
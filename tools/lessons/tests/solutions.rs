@@ -0,0 +1,53 @@
+//! Checks that the instructor's copy of the lessons works, and that the
+//! student's copy, with the solutions swapped for stubs, still builds.
+
+use std::path::Path;
+
+use lessons::solution;
+
+mod common;
+
+#[test]
+fn student_copy_keeps_comments_and_stubs_out_code() {
+    let source = "\
+fn add(first: i32, second: i32) -> i32 {
+    // SOLUTION BEGIN: todo!(\"add them\")
+    // The last expression is returned.
+    first + second
+    // SOLUTION END
+}
+
+fn append_a(name: &mut String) {
+    // SOLUTION BEGIN
+    name.push('a');
+    // SOLUTION END
+}
+";
+    let expected = "\
+fn add(first: i32, second: i32) -> i32 {
+    // The last expression is returned.
+    todo!(\"add them\")
+}
+
+fn append_a(name: &mut String) {
+    todo!()
+}
+";
+    assert_eq!(solution::student(source).unwrap(), expected);
+}
+
+#[test]
+fn unpaired_markers_are_errors() {
+    assert!(solution::student("// SOLUTION BEGIN\nfn main() {}\n").is_err());
+    assert!(solution::student("fn main() {}\n// SOLUTION END\n").is_err());
+    assert!(solution::student("// SOLUTION BEGIN\n// SOLUTION BEGIN\n").is_err());
+}
+
+#[test]
+fn both_copies_build() {
+    let workspace = common::workspace();
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("student");
+
+    let problems = solution::check(&workspace, &out).expect("checking should run");
+    assert!(problems.is_empty(), "\n{}\n", problems.join("\n"));
+}
@@ -0,0 +1,96 @@
+//! Splits made-up lessons into blocks, slides, and the items of their prose.
+
+use lessons::source::{blocks, items, slides, Item, Kind};
+
+fn lines(text: &[&str]) -> Vec<String> {
+    text.iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn comments_and_code_alternate() {
+    let source = "\
+// Some prose
+// about the code.
+let x = 5;
+// ```compile_fail(E0382): use of moved value
+// take(name);
+// ```
+
+// More prose.
+";
+    let blocks = blocks(source);
+    let kinds: Vec<Kind> = blocks.iter().map(|block| block.kind).collect();
+    assert_eq!(
+        kinds,
+        [Kind::Prose, Kind::Code, Kind::CommentedCode, Kind::Prose]
+    );
+    assert_eq!(blocks[0].lines, ["Some prose", "about the code."]);
+    assert_eq!(
+        blocks[2].info(),
+        Some("compile_fail(E0382): use of moved value")
+    );
+    assert_eq!(blocks[2].code(), ["take(name);"]);
+    assert_eq!(blocks[3].line, 8);
+
+    // The code and commented code go on the slide of the prose before them.
+    let slides = slides(source);
+    assert_eq!(slides.len(), 2);
+    assert_eq!(slides[0].blocks.len(), 3);
+}
+
+#[test]
+fn definitions_are_short_terms_before_an_arrow() {
+    let items = items(&lines(&[
+        "&     => the reference.",
+        "  It can be copied.",
+        "&mut  => the mutable reference.",
+        "a match arm => isn't a definition",
+        "=> isn't either",
+    ]));
+    assert_eq!(
+        items,
+        [
+            Item::Definition {
+                term: String::from("&"),
+                text: String::from("the reference. It can be copied."),
+            },
+            Item::Definition {
+                term: String::from("&mut"),
+                text: String::from("the mutable reference."),
+            },
+            Item::Paragraph(String::from(
+                "a match arm => isn't a definition => isn't either"
+            )),
+        ]
+    );
+}
+
+#[test]
+fn list_items_nest_and_continue_when_indented() {
+    let items = items(&lines(&[
+        "The rules:",
+        "1. One owner.",
+        "   Only one.",
+        "2. Either:",
+        "  a) one &mut, or",
+        "  b) any number of &.",
+    ]));
+    assert_eq!(items[0], Item::Paragraph(String::from("The rules:")));
+    assert_eq!(
+        items[1],
+        Item::ListItem {
+            marker: String::from("1."),
+            depth: 0,
+            text: String::from("One owner. Only one."),
+        }
+    );
+    assert_eq!(
+        items[3],
+        Item::ListItem {
+            marker: String::from("a)"),
+            depth: 1,
+            text: String::from("one &mut, or"),
+        }
+    );
+    assert_eq!(items.len(), 5);
+}
@@ -0,0 +1,121 @@
+//! Reads tables out of made-up manifests with the TOML subset the lesson
+//! manifests use.
+
+use lessons::toml::{table, Table, Value};
+
+fn read(manifest: &str) -> Table {
+    table(manifest, "t")
+        .unwrap()
+        .expect("the table should be there")
+}
+
+#[test]
+fn strings_integers_and_arrays_are_read() {
+    let table = read(
+        r#"
+[t]
+title = "Data Types"
+position = 1_000
+concepts = ["integers", "char"]
+empty = []
+"#,
+    );
+    assert_eq!(table["title"], Value::String(String::from("Data Types")));
+    assert_eq!(table["position"], Value::Integer(1000));
+    assert_eq!(
+        table["concepts"],
+        Value::Array(vec![String::from("integers"), String::from("char")])
+    );
+    assert_eq!(table["empty"], Value::Array(Vec::new()));
+}
+
+#[test]
+fn escapes_in_strings_are_unescaped() {
+    let table = read(
+        r#"[t]
+message = "a \"quote\", a \\, a\ttab and a\nnewline"
+"#,
+    );
+    assert_eq!(
+        table["message"],
+        Value::String(String::from("a \"quote\", a \\, a\ttab and a\nnewline"))
+    );
+}
+
+#[test]
+fn arrays_can_span_lines_and_have_comments() {
+    let table = read(
+        r##"
+[t]
+stdout = [
+    "Hello!",  # the first line
+    "# not a comment",
+]
+"##,
+    );
+    assert_eq!(
+        table["stdout"],
+        Value::Array(vec![
+            String::from("Hello!"),
+            String::from("# not a comment")
+        ])
+    );
+}
+
+#[test]
+fn only_the_named_table_is_read() {
+    let manifest = r#"
+[package]
+name = "data_types"
+
+[package.metadata.club]
+title = "Data Types"
+
+[package.metadata.club.explain]
+E0382 = "moves"
+"#;
+    let club = table(manifest, "package.metadata.club").unwrap().unwrap();
+    assert_eq!(club.len(), 1);
+    assert_eq!(club["title"], Value::String(String::from("Data Types")));
+    let explain = table(manifest, "package.metadata.club.explain")
+        .unwrap()
+        .unwrap();
+    assert_eq!(explain["E0382"], Value::String(String::from("moves")));
+    assert_eq!(table(manifest, "workspace").unwrap(), None);
+}
+
+#[test]
+fn malformed_values_say_which_line_they_are_on() {
+    for (manifest, error) in [
+        ("[t]\njust a key\n", "line 2: expected `key = value`"),
+        (
+            "[t]\ntitle = \"never closed\n",
+            "line 2: unterminated string",
+        ),
+        (
+            "[t]\ntitle = \"ends in a backslash\\",
+            "line 2: unterminated string",
+        ),
+        (
+            "[t]\ntitle = \"bad \\q escape\"\n",
+            "line 2: unknown escape `\\q`",
+        ),
+        (
+            "[t]\ntitle = \"a\" \"b\"\n",
+            "line 2: unexpected `\"b\"` after string",
+        ),
+        ("[t]\n\nlist = [\n\"a\",\n", "line 3: unclosed array"),
+        (
+            "[t]\nlist = [1, 2]\n",
+            "line 2: expected a string, found `1, 2]`",
+        ),
+        (
+            "[t]\nyes = true\n",
+            "line 2: `true` is not a string, integer, or array of strings",
+        ),
+    ]
+    .iter()
+    {
+        assert_eq!(table(manifest, "t").unwrap_err(), *error, "{:?}", manifest);
+    }
+}
@@ -0,0 +1,115 @@
+//! The syntax tree of a mini program.
+
+use crate::diagnostic::Span;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    pub functions: Vec<Function>,
+}
+
+impl Program {
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub returns: Option<Type>,
+    pub body: Block,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: String,
+    pub mutable: bool,
+    pub ty: Type,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    /// `i32`, `String`, `str` and the like.
+    Named(String),
+    Ref {
+        mutable: bool,
+        inner: Box<Type>,
+    },
+}
+
+impl Type {
+    pub fn is_ref(&self) -> bool {
+        matches!(self, Type::Ref { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub statements: Vec<Statement>,
+    /// The expression at the end without a semicolon, which is the block's
+    /// value.
+    pub tail: Option<Box<Expr>>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Let {
+        name: String,
+        mutable: bool,
+        ty: Option<Type>,
+        value: Expr,
+        span: Span,
+    },
+    Assign {
+        name: String,
+        value: Expr,
+        span: Span,
+    },
+    Expr(Expr),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprKind {
+    Int(i64),
+    Str(String),
+    Char(char),
+    Var(String),
+    /// `&value` or `&mut value`.
+    Ref {
+        mutable: bool,
+        value: Box<Expr>,
+    },
+    Binary {
+        op: char,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    /// `say_hello(&name)` or `String::from("Gabe")`. The path is joined with
+    /// `::`.
+    Call {
+        function: String,
+        args: Vec<Expr>,
+    },
+    /// `name.push_str(" Love")`.
+    Method {
+        receiver: Box<Expr>,
+        method: String,
+        args: Vec<Expr>,
+    },
+    /// `println!("Hello, {}!", name)`.
+    Macro {
+        name: String,
+        args: Vec<Expr>,
+    },
+    Block(Block),
+}
@@ -0,0 +1,578 @@
+//! A simplified borrow checker, with non-lexical lifetimes.
+//!
+//! Each function is walked in the order it runs, giving every read, write and
+//! borrow a point in time. A borrow (a *loan*) starts at the point it's made
+//! and lasts until the last use of anything holding it: the variable it was
+//! stored in, or the call it was passed to. Then every access to a variable is
+//! checked against the loans on that variable that are live at that point:
+//!
+//! - a `&mut` while another `&mut` is live breaks rule 2a,
+//! - a `&mut` while a `&` is live, or a `&` or use while a `&mut` is live,
+//!   breaks rule 2b,
+//! - and assigning while a `&` is live breaks rule 1, since the value would
+//!   change beneath the reference.
+//!
+//! Mini programs have no branches or loops, so "the order it runs" is simply
+//! the order it's written in. Moves aren't checked here; that's the
+//! interpreter's job.
+
+use std::collections::HashMap;
+
+use crate::ast::{Block, Expr, ExprKind, Function, Program, Statement, Type};
+use crate::diagnostic::{Diagnostic, Rule, Span};
+
+/// Every borrow error in the program, in the order they appear.
+pub fn check(program: &Program) -> Vec<Diagnostic> {
+    let mut errors = Vec::new();
+    for function in &program.functions {
+        let mut checker = Checker::new(program);
+        checker.function(function);
+        errors.extend(checker.finish());
+    }
+    errors.sort_by_key(|error| error.span().start);
+    errors.dedup_by_key(|error| error.span());
+    errors
+}
+
+/// How a method takes `self`.
+fn method(name: &str) -> Option<bool> {
+    match name {
+        "push_str" | "push" | "clear" | "truncate" => Some(true),
+        "len" | "is_empty" | "clone" => Some(false),
+        _ => None,
+    }
+}
+
+/// Functions every program can call, which return owned values.
+const BUILTINS: &[&str] = &["String::from", "String::new"];
+
+struct Binding {
+    name: String,
+    mutable: bool,
+    /// Whether it holds a reference, and if so whether it's a `&mut`.
+    reference: Option<bool>,
+    /// The loans it holds, which stay live as long as it's used.
+    loans: Vec<usize>,
+    span: Span,
+}
+
+struct Loan {
+    /// The variable that's borrowed.
+    place: usize,
+    mutable: bool,
+    span: Span,
+    created: usize,
+    /// The last point it's used at.
+    end: usize,
+    /// Where that last use was, and whether it was a call it was passed to.
+    used: Option<(Span, bool)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Read,
+    Write,
+    Shared,
+    Mutable,
+}
+
+struct Access {
+    binding: usize,
+    kind: Kind,
+    point: usize,
+    span: Span,
+    /// The loan this access made, if it was a borrow.
+    loan: Option<usize>,
+}
+
+/// The loans a value holds, and whether it's a reference.
+#[derive(Default)]
+struct Flow {
+    loans: Vec<usize>,
+    reference: Option<bool>,
+}
+
+struct Checker<'p> {
+    program: &'p Program,
+    bindings: Vec<Binding>,
+    scopes: Vec<HashMap<String, usize>>,
+    loans: Vec<Loan>,
+    accesses: Vec<Access>,
+    point: usize,
+    errors: Vec<Diagnostic>,
+}
+
+impl<'p> Checker<'p> {
+    fn new(program: &'p Program) -> Checker<'p> {
+        Checker {
+            program,
+            bindings: Vec::new(),
+            scopes: vec![HashMap::new()],
+            loans: Vec::new(),
+            accesses: Vec::new(),
+            point: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    fn tick(&mut self) -> usize {
+        self.point += 1;
+        self.point
+    }
+
+    fn bind(&mut self, name: &str, mutable: bool, flow: Flow, span: Span) {
+        self.bindings.push(Binding {
+            name: name.to_string(),
+            mutable,
+            reference: flow.reference,
+            loans: flow.loans,
+            span,
+        });
+        let id = self.bindings.len() - 1;
+        self.scopes.last_mut().unwrap().insert(name.to_string(), id);
+    }
+
+    fn lookup(&mut self, name: &str, span: Span) -> Option<usize> {
+        let found = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .copied();
+        if found.is_none() {
+            self.errors.push(Diagnostic::error(
+                Some("E0425"),
+                format!("cannot find value `{}` in this scope", name),
+                span,
+                "not found in this scope",
+            ));
+        }
+        found
+    }
+
+    /// Using a variable that holds references keeps its loans alive.
+    fn use_loans(&mut self, binding: usize, span: Span) {
+        let point = self.tick();
+        for &loan in &self.bindings[binding].loans {
+            let loan = &mut self.loans[loan];
+            loan.end = point;
+            loan.used = Some((span, false));
+        }
+    }
+
+    fn access(&mut self, binding: usize, kind: Kind, span: Span) -> Option<usize> {
+        let point = self.tick();
+        let loan = match kind {
+            Kind::Shared | Kind::Mutable => {
+                self.loans.push(Loan {
+                    place: binding,
+                    mutable: kind == Kind::Mutable,
+                    span,
+                    created: point,
+                    end: point,
+                    used: None,
+                });
+                Some(self.loans.len() - 1)
+            }
+            Kind::Read | Kind::Write => None,
+        };
+        self.accesses.push(Access {
+            binding,
+            kind,
+            point,
+            span,
+            loan,
+        });
+        loan
+    }
+
+    /// Loans passed to a call last until the call's done.
+    fn end_at_call(&mut self, loans: &[usize], span: Span) {
+        let point = self.tick();
+        for &loan in loans {
+            let loan = &mut self.loans[loan];
+            loan.end = point;
+            // A variable holding the loan that was passed to this call is the
+            // use worth pointing at, rather than the call.
+            match loan.used {
+                Some((used, false)) if span.start <= used.start && used.end <= span.end => {}
+                _ => loan.used = Some((span, true)),
+            }
+        }
+    }
+
+    fn function(&mut self, function: &Function) {
+        for param in &function.params {
+            let reference = match &param.ty {
+                Type::Ref { mutable, .. } => Some(*mutable),
+                Type::Named(_) => None,
+            };
+            let flow = Flow {
+                loans: Vec::new(),
+                reference,
+            };
+            self.bind(&param.name, param.mutable, flow, param.span);
+        }
+        self.block(&function.body);
+    }
+
+    fn block(&mut self, block: &Block) -> Flow {
+        self.scopes.push(HashMap::new());
+        for statement in &block.statements {
+            self.statement(statement);
+        }
+        let flow = match &block.tail {
+            Some(tail) => self.expr(tail),
+            None => Flow::default(),
+        };
+        self.scopes.pop();
+        flow
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Let {
+                name,
+                mutable,
+                value,
+                span,
+                ..
+            } => {
+                let flow = self.expr(value);
+                self.bind(name, *mutable, flow, *span);
+            }
+            Statement::Assign { name, value, span } => {
+                let flow = self.expr(value);
+                let binding = match self.lookup(name, *span) {
+                    Some(binding) => binding,
+                    None => return,
+                };
+                if !self.bindings[binding].mutable {
+                    self.errors.push(
+                        Diagnostic::error(
+                            Some("E0384"),
+                            format!("cannot assign twice to immutable variable `{}`", name),
+                            *span,
+                            "cannot assign twice to immutable variable",
+                        )
+                        .with_label(
+                            self.bindings[binding].span,
+                            format!("first assignment to `{}`", name),
+                        ),
+                    );
+                }
+                self.access(binding, Kind::Write, *span);
+                if flow.reference.is_some() {
+                    self.bindings[binding].loans = flow.loans;
+                }
+            }
+            Statement::Expr(expr) => {
+                self.expr(expr);
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Flow {
+        match &expr.kind {
+            ExprKind::Int(_) | ExprKind::Str(_) | ExprKind::Char(_) => Flow::default(),
+            ExprKind::Var(name) => {
+                let binding = match self.lookup(name, expr.span) {
+                    Some(binding) => binding,
+                    None => return Flow::default(),
+                };
+                match self.bindings[binding].reference {
+                    Some(mutable) => {
+                        self.use_loans(binding, expr.span);
+                        Flow {
+                            loans: self.bindings[binding].loans.clone(),
+                            reference: Some(mutable),
+                        }
+                    }
+                    None => {
+                        self.access(binding, Kind::Read, expr.span);
+                        Flow::default()
+                    }
+                }
+            }
+            ExprKind::Ref { mutable, value } => self.borrow(*mutable, value, expr.span),
+            ExprKind::Binary { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+                Flow::default()
+            }
+            ExprKind::Call { function, args } => self.call(function, args, expr.span),
+            ExprKind::Method {
+                receiver,
+                method: name,
+                args,
+            } => {
+                let mut loans = Vec::new();
+                for arg in args {
+                    loans.extend(self.expr(arg).loans);
+                }
+                let mutable = match method(name) {
+                    Some(mutable) => mutable,
+                    None => {
+                        self.errors.push(Diagnostic::error(
+                            Some("E0599"),
+                            format!("no method named `{}` found", name),
+                            expr.span,
+                            "method not found",
+                        ));
+                        return Flow::default();
+                    }
+                };
+                // Arguments are evaluated before the receiver is borrowed, like
+                // rustc's two-phase borrows allow for `name.push_str(...)`.
+                loans.extend(self.receiver(receiver, mutable).loans);
+                self.end_at_call(&loans, expr.span);
+                Flow::default()
+            }
+            ExprKind::Macro { args, .. } => {
+                // println! and friends take a reference to everything after the
+                // format string.
+                let mut loans = Vec::new();
+                for arg in args.iter().skip(1) {
+                    let flow = match &arg.kind {
+                        ExprKind::Var(_) => self.receiver(arg, false),
+                        _ => self.expr(arg),
+                    };
+                    loans.extend(flow.loans);
+                }
+                self.end_at_call(&loans, expr.span);
+                Flow::default()
+            }
+            ExprKind::Block(block) => self.block(block),
+        }
+    }
+
+    /// `&value` or `&mut value`.
+    fn borrow(&mut self, mutable: bool, value: &Expr, span: Span) -> Flow {
+        let name = match &value.kind {
+            ExprKind::Var(name) => name,
+            // A reference to a temporary, which nothing else can see.
+            _ => {
+                let flow = self.expr(value);
+                return Flow {
+                    loans: flow.loans,
+                    reference: Some(mutable),
+                };
+            }
+        };
+        let binding = match self.lookup(name, value.span) {
+            Some(binding) => binding,
+            None => return Flow::default(),
+        };
+
+        if self.bindings[binding].reference.is_some() {
+            // A reference to a reference keeps the first one's loans alive.
+            self.use_loans(binding, value.span);
+            return Flow {
+                loans: self.bindings[binding].loans.clone(),
+                reference: Some(mutable),
+            };
+        }
+
+        if mutable && !self.bindings[binding].mutable {
+            self.not_mutable(binding, span);
+        }
+        let kind = if mutable { Kind::Mutable } else { Kind::Shared };
+        let loan = self.access(binding, kind, span);
+        Flow {
+            loans: loan.into_iter().collect(),
+            reference: Some(mutable),
+        }
+    }
+
+    /// The receiver of a method, which is borrowed automatically.
+    fn receiver(&mut self, receiver: &Expr, mutable: bool) -> Flow {
+        let name = match &receiver.kind {
+            ExprKind::Var(name) => name,
+            _ => return self.expr(receiver),
+        };
+        let binding = match self.lookup(name, receiver.span) {
+            Some(binding) => binding,
+            None => return Flow::default(),
+        };
+        match self.bindings[binding].reference {
+            Some(is_mutable) => {
+                if mutable && !is_mutable {
+                    self.errors.push(Diagnostic::error(
+                        Some("E0596"),
+                        format!("cannot borrow `*{}` as mutable, as it is behind a `&` reference", name),
+                        receiver.span,
+                        format!(
+                            "`{}` is a `&` reference, so the data it refers to cannot be borrowed as mutable",
+                            name
+                        ),
+                    ));
+                }
+                self.use_loans(binding, receiver.span);
+                Flow {
+                    loans: self.bindings[binding].loans.clone(),
+                    reference: Some(is_mutable),
+                }
+            }
+            None => {
+                if mutable && !self.bindings[binding].mutable {
+                    self.not_mutable(binding, receiver.span);
+                }
+                let kind = if mutable { Kind::Mutable } else { Kind::Shared };
+                let loan = self.access(binding, kind, receiver.span);
+                Flow {
+                    loans: loan.into_iter().collect(),
+                    reference: Some(mutable),
+                }
+            }
+        }
+    }
+
+    fn call(&mut self, name: &str, args: &[Expr], span: Span) -> Flow {
+        let mut flows = Vec::new();
+        for arg in args {
+            flows.push(self.expr(arg));
+        }
+        let loans: Vec<usize> = flows.iter().flat_map(|flow| flow.loans.clone()).collect();
+        self.end_at_call(&loans, span);
+
+        if BUILTINS.contains(&name) {
+            return Flow::default();
+        }
+        let function = match self.program.function(name) {
+            Some(function) => function,
+            None => {
+                self.errors.push(Diagnostic::error(
+                    Some("E0425"),
+                    format!("cannot find function `{}` in this scope", name),
+                    span,
+                    "not found in this scope",
+                ));
+                return Flow::default();
+            }
+        };
+        if function.params.len() != args.len() {
+            self.errors.push(Diagnostic::error(
+                Some("E0061"),
+                format!(
+                    "this function takes {} arguments but {} arguments were supplied",
+                    function.params.len(),
+                    args.len()
+                ),
+                span,
+                format!("expected {} arguments", function.params.len()),
+            ));
+        }
+        // A returned reference borrows from the references passed in, which is
+        // what lifetime elision assumes.
+        match &function.returns {
+            Some(Type::Ref { mutable, .. }) => Flow {
+                loans,
+                reference: Some(*mutable),
+            },
+            _ => Flow::default(),
+        }
+    }
+
+    fn not_mutable(&mut self, binding: usize, span: Span) {
+        let name = self.bindings[binding].name.clone();
+        self.errors.push(
+            Diagnostic::error(
+                Some("E0596"),
+                format!(
+                    "cannot borrow `{}` as mutable, as it is not declared as mutable",
+                    name
+                ),
+                span,
+                "cannot borrow as mutable",
+            )
+            .with_label(
+                self.bindings[binding].span,
+                format!("help: consider changing this to be mutable: `mut {}`", name),
+            ),
+        );
+    }
+
+    /// Checks every access against the loans live at the time.
+    fn finish(mut self) -> Vec<Diagnostic> {
+        for access in &self.accesses {
+            let conflict = self.loans.iter().enumerate().find(|(id, loan)| {
+                loan.place == access.binding
+                    && Some(*id) != access.loan
+                    && loan.created < access.point
+                    && access.point <= loan.end
+                    && (loan.mutable || matches!(access.kind, Kind::Mutable | Kind::Write))
+            });
+            if let Some((_, loan)) = conflict {
+                let name = &self.bindings[access.binding].name;
+                self.errors.push(conflict_error(name, access, loan));
+            }
+        }
+        self.errors
+    }
+}
+
+fn conflict_error(name: &str, access: &Access, loan: &Loan) -> Diagnostic {
+    let (code, message, here, there, later, rule) = match (access.kind, loan.mutable) {
+        (Kind::Mutable, true) => (
+            "E0499",
+            format!(
+                "cannot borrow `{}` as mutable more than once at a time",
+                name
+            ),
+            "second mutable borrow occurs here",
+            "first mutable borrow occurs here",
+            "first borrow later used",
+            Rule::TwoA,
+        ),
+        (Kind::Mutable, false) => (
+            "E0502",
+            format!(
+                "cannot borrow `{}` as mutable because it is also borrowed as immutable",
+                name
+            ),
+            "mutable borrow occurs here",
+            "immutable borrow occurs here",
+            "immutable borrow later used",
+            Rule::TwoB,
+        ),
+        (Kind::Shared, _) => (
+            "E0502",
+            format!(
+                "cannot borrow `{}` as immutable because it is also borrowed as mutable",
+                name
+            ),
+            "immutable borrow occurs here",
+            "mutable borrow occurs here",
+            "mutable borrow later used",
+            Rule::TwoB,
+        ),
+        (Kind::Write, mutable) => (
+            "E0506",
+            format!("cannot assign to `{}` because it is borrowed", name),
+            "assignment to borrowed value occurs here",
+            "borrow occurs here",
+            "borrow later used",
+            if mutable { Rule::TwoA } else { Rule::One },
+        ),
+        (Kind::Read, _) => (
+            "E0503",
+            format!("cannot use `{}` because it was mutably borrowed", name),
+            "use of borrowed value occurs here",
+            "borrow occurs here",
+            "borrow later used",
+            Rule::TwoB,
+        ),
+    };
+    let mut error = Diagnostic::error(Some(code), message, access.span, here)
+        .with_rule(rule)
+        .with_label(loan.span, there);
+    if let Some((span, by_call)) = loan.used {
+        let later = if by_call {
+            format!("{} by call", later)
+        } else {
+            format!("{} here", later)
+        };
+        error = error.with_label(span, later);
+    }
+    error
+}
@@ -0,0 +1,192 @@
+//! Errors in mini programs, printed the way rustc prints them.
+
+use std::fmt;
+
+/// A range of bytes in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    /// From the start of one span to the end of another.
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start, other.end)
+    }
+
+    /// The 1-based line and column the span starts on.
+    pub fn position(self, source: &str) -> (usize, usize) {
+        let before = &source[..self.start.min(source.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |newline| newline + 1) + 1;
+        (line, column)
+    }
+}
+
+/// The rules from the `borrowing` lesson.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// Have as many immutable references as you would like.
+    One,
+    /// Having two mutable references is not allowed.
+    TwoA,
+    /// Immutable references aren't allowed alongside a mutable one.
+    TwoB,
+}
+
+impl Rule {
+    /// What the lesson says about the rule.
+    pub fn explanation(self) -> &'static str {
+        match self {
+            Rule::One => {
+                "you can have as many immutable references as you would like, since the data \
+                 can not unexpectedly change from beneath them. Here it would."
+            }
+            Rule::TwoA => {
+                "having two mutable references is not allowed, since that can allow for race \
+                 conditions and unexpected behavior."
+            }
+            Rule::TwoB => {
+                "immutable references aren't allowed alongside a mutable one, since the \
+                 immutable one may have some assumption of the referenced value, which can be \
+                 changed by the mutable reference."
+            }
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::One => write!(f, "rule 1"),
+            Rule::TwoA => write!(f, "rule 2a"),
+            Rule::TwoB => write!(f, "rule 2b"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub text: String,
+    /// The primary label gets `^^^`, the others `---`, like rustc.
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The rustc error code this mirrors, like `E0499`. Syntax errors don't
+    /// have one.
+    pub code: Option<&'static str>,
+    pub message: String,
+    /// The borrowing rule that was broken, if it was one of them.
+    pub rule: Option<Rule>,
+    pub labels: Vec<Label>,
+    /// Printed after the code, as `= note: ...`.
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(
+        code: Option<&'static str>,
+        message: impl Into<String>,
+        span: Span,
+        label: impl Into<String>,
+    ) -> Diagnostic {
+        Diagnostic {
+            code,
+            message: message.into(),
+            rule: None,
+            labels: vec![Label {
+                span,
+                text: label.into(),
+                primary: true,
+            }],
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn with_rule(mut self, rule: Rule) -> Diagnostic {
+        self.rule = Some(rule);
+        self
+    }
+
+    pub fn with_label(mut self, span: Span, text: impl Into<String>) -> Diagnostic {
+        self.labels.push(Label {
+            span,
+            text: text.into(),
+            primary: false,
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Diagnostic {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// The primary span.
+    pub fn span(&self) -> Span {
+        self.labels
+            .iter()
+            .find(|label| label.primary)
+            .map_or(Span::new(0, 0), |label| label.span)
+    }
+
+    /// The diagnostic as rustc would print it, with the lines it points at.
+    pub fn render(&self, file: &str, source: &str) -> String {
+        let mut text = match self.code {
+            Some(code) => format!("error[{}]: {}\n", code, self.message),
+            None => format!("error: {}\n", self.message),
+        };
+        let (line, column) = self.span().position(source);
+
+        let mut labels = self.labels.clone();
+        labels.sort_by_key(|label| label.span.start);
+        let last_line = labels
+            .iter()
+            .map(|label| label.span.position(source).0)
+            .max()
+            .unwrap_or(line);
+        let width = last_line.to_string().len();
+        let gutter = " ".repeat(width);
+
+        text.push_str(&format!("{}--> {}:{}:{}\n", gutter, file, line, column));
+        text.push_str(&format!("{} |\n", gutter));
+        let lines: Vec<&str> = source.lines().collect();
+        let mut shown = None;
+        for label in &labels {
+            let (line, column) = label.span.position(source);
+            let code = lines.get(line - 1).copied().unwrap_or("");
+            if shown != Some(line) {
+                text.push_str(&format!("{:>width$} | {}\n", line, code, width = width));
+                shown = Some(line);
+            }
+            // Spans that run past the end of the line are cut off there.
+            let length = (label.span.end - label.span.start)
+                .min(code.len().saturating_sub(column - 1))
+                .max(1);
+            let mark = if label.primary { "^" } else { "-" };
+            text.push_str(&format!(
+                "{} | {}{} {}\n",
+                gutter,
+                " ".repeat(column - 1),
+                mark.repeat(length),
+                label.text
+            ));
+        }
+        text.push_str(&format!("{} |\n", gutter));
+        if let Some(rule) = self.rule {
+            text.push_str(&format!("{} = {}: {}\n", gutter, rule, rule.explanation()));
+        }
+        for note in &self.notes {
+            text.push_str(&format!("{} = note: {}\n", gutter, note));
+        }
+        text
+    }
+}
@@ -0,0 +1,879 @@
+//! Running mini programs, keeping track of what's been moved.
+//!
+//! Every variable lives in a slot. Using a variable by value copies it if its
+//! type is `Copy` (integers, bools, chars, `&str`s and `&` references), and
+//! moves it out otherwise (`String`s and `&mut` references), which leaves the
+//! slot empty. Using an empty slot stops the program with rustc's E0382,
+//! pointing at where the value was moved.
+//!
+//! That's the check rustc does at compile time, done at run time instead.
+//! Mini programs have no branches or loops, so the two always agree, and the
+//! interpreter gets to show *when* each value leaves a variable: everything
+//! that happens is recorded as a [`Step`], so a program can be single-stepped.
+//!
+//! Like rustc, a `&mut` variable passed straight to a function is reborrowed
+//! rather than moved, so it can be passed again. Borrows aren't checked here;
+//! that's the [`borrowck`](crate::borrowck) module's job.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::ast::{Block, Expr, ExprKind, Function, Program, Statement};
+use crate::diagnostic::{Diagnostic, Span};
+
+/// How deep calls can go, since a function that calls itself has no way to
+/// stop.
+const MAX_DEPTH: usize = 64;
+
+/// What a program did, up to the end or the error that stopped it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub steps: Vec<Step>,
+    /// Everything the program printed.
+    pub output: String,
+    pub error: Option<Diagnostic>,
+}
+
+impl Run {
+    /// Every step, under the lines of code they happened on.
+    pub fn trace(&self, source: &str) -> String {
+        let mut previous = None;
+        let mut text = String::new();
+        for step in &self.steps {
+            text.push_str(&step.render(source, previous));
+            previous = Some(step);
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub span: Span,
+    /// How many calls deep it happened.
+    pub depth: usize,
+    pub event: Event,
+}
+
+impl Step {
+    /// The step as part of a trace. The line of code it happened on is only
+    /// shown if the previous step was on a different one.
+    pub fn render(&self, source: &str, previous: Option<&Step>) -> String {
+        let (line, _) = self.span.position(source);
+        let mut text = String::new();
+        if previous.map(|previous| previous.span.position(source).0) != Some(line) {
+            let code = source.lines().nth(line - 1).unwrap_or("").trim();
+            text.push_str(&format!("{:>4} | {}\n", line, code));
+        }
+        let indent = "  ".repeat(self.depth);
+        for event in self.event.to_string().lines() {
+            text.push_str(&format!("     | {}{}\n", indent, event));
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A variable given a new value, like `let x = 5;`.
+    Bind {
+        name: String,
+        value: String,
+    },
+    /// A variable given a copy of another's value. `function` is set when
+    /// `to` is one of its parameters.
+    Copy {
+        from: String,
+        to: String,
+        function: Option<String>,
+        value: String,
+    },
+    /// A value moved from one variable to another, leaving `from` empty.
+    Move {
+        from: String,
+        to: String,
+        function: Option<String>,
+    },
+    /// A variable assigned to, or changed by a method like `push_str`.
+    Change {
+        name: String,
+        value: String,
+    },
+    Call {
+        function: String,
+    },
+    /// A function finishing. Functions that return `()` have no value.
+    Return {
+        function: String,
+        value: Option<String>,
+    },
+    /// What `println!` printed.
+    Print(String),
+    /// A variable going out of scope, which drops its value unless it was
+    /// moved out.
+    Drop {
+        name: String,
+        moved: bool,
+    },
+    /// A variable holding a `Copy` value or a reference going out of scope,
+    /// which has nothing to drop.
+    OutOfScope {
+        name: String,
+    },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let into = |to: &str, function: &Option<String>| match function {
+            Some(function) => format!("`{}`'s `{}`", function, to),
+            None => format!("`{}`", to),
+        };
+        match self {
+            Event::Bind { name, value } => write!(f, "`{}` = {}", name, value),
+            Event::Copy {
+                from,
+                to,
+                function,
+                value,
+            } => write!(
+                f,
+                "`{}` copied into {}, which is now {}",
+                from,
+                into(to, function),
+                value
+            ),
+            Event::Move { from, to, function } => write!(
+                f,
+                "`{}` moved into {}, so `{}` can't be used any more",
+                from,
+                into(to, function),
+                from
+            ),
+            Event::Change { name, value } => write!(f, "`{}` is now {}", name, value),
+            Event::Call { function } => write!(f, "call `{}`", function),
+            Event::Return {
+                function,
+                value: Some(value),
+            } => write!(f, "`{}` returned {}", function, value),
+            Event::Return {
+                function,
+                value: None,
+            } => write!(f, "back from `{}`", function),
+            Event::Print(text) => {
+                let lines: Vec<String> = text.lines().map(|line| format!("> {}", line)).collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+            Event::Drop { name, moved: true } => write!(
+                f,
+                "`{}` goes out of scope, but it was moved, so there's nothing to drop",
+                name
+            ),
+            Event::Drop { name, moved: false } => {
+                write!(f, "`{}` goes out of scope and is dropped", name)
+            }
+            Event::OutOfScope { name } => write!(f, "`{}` goes out of scope", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Unit,
+    Int(i64),
+    Bool(bool),
+    Char(char),
+    /// A string literal, which is a `&str`.
+    Str(String),
+    String(String),
+    /// A reference to the variable in a slot.
+    Ref {
+        slot: usize,
+        mutable: bool,
+    },
+}
+
+impl Value {
+    fn is_copy(&self) -> bool {
+        !matches!(self, Value::String(_) | Value::Ref { mutable: true, .. })
+    }
+}
+
+struct Slot {
+    name: String,
+    ty: String,
+    /// `None` once it's been moved out of.
+    value: Option<Value>,
+    declared: Span,
+    moved: Option<Span>,
+    /// Where it went out of scope.
+    dropped: Option<Span>,
+}
+
+/// A value about to be stored in a variable, and the variable it came from.
+struct Operand {
+    value: Value,
+    from: Option<usize>,
+    copied: bool,
+}
+
+/// Runs `main`, stopping at the first error.
+pub fn run(program: &Program) -> Run {
+    let mut interpreter = Interpreter {
+        program,
+        slots: Vec::new(),
+        frames: Vec::new(),
+        steps: Vec::new(),
+        output: String::new(),
+    };
+    let error = match program.function("main") {
+        Some(main) => interpreter.call(main, Vec::new(), main.span).err(),
+        None => Some(Diagnostic::error(
+            Some("E0601"),
+            "`main` function not found in crate",
+            Span::new(0, 0),
+            "consider adding a `main` function",
+        )),
+    };
+    Run {
+        steps: interpreter.steps,
+        output: interpreter.output,
+        error,
+    }
+}
+
+struct Interpreter<'p> {
+    program: &'p Program,
+    slots: Vec<Slot>,
+    /// The scopes of each call in progress, innermost last, with the slots
+    /// declared in them.
+    frames: Vec<Vec<Vec<usize>>>,
+    steps: Vec<Step>,
+    output: String,
+}
+
+impl<'p> Interpreter<'p> {
+    fn step(&mut self, span: Span, event: Event) {
+        self.steps.push(Step {
+            span,
+            depth: self.frames.len(),
+            event,
+        });
+    }
+
+    fn lookup(&self, name: &str, span: Span) -> Result<usize, Diagnostic> {
+        self.frames
+            .last()
+            .into_iter()
+            .flatten()
+            .flatten()
+            .rev()
+            .find(|slot| self.slots[**slot].name == name)
+            .copied()
+            .ok_or_else(|| {
+                Diagnostic::error(
+                    Some("E0425"),
+                    format!("cannot find value `{}` in this scope", name),
+                    span,
+                    "not found in this scope",
+                )
+            })
+    }
+
+    fn new_slot(&mut self, name: &str, value: Value, declared: Span) -> usize {
+        self.slots.push(Slot {
+            name: name.to_string(),
+            ty: self.type_of(&value),
+            value: Some(value),
+            declared,
+            moved: None,
+            dropped: None,
+        });
+        self.slots.len() - 1
+    }
+
+    /// Puts a value in a new variable, saying where it came from.
+    fn bind(&mut self, name: &str, operand: Operand, span: Span, function: Option<&str>) {
+        let value = self.describe(&operand.value);
+        let function = function.map(str::to_string);
+        let event = match operand.from {
+            Some(from) if operand.copied => Event::Copy {
+                from: self.slots[from].name.clone(),
+                to: name.to_string(),
+                function,
+                value,
+            },
+            Some(from) => Event::Move {
+                from: self.slots[from].name.clone(),
+                to: name.to_string(),
+                function,
+            },
+            None => Event::Bind {
+                name: name.to_string(),
+                value,
+            },
+        };
+        let slot = self.new_slot(name, operand.value, span);
+        let frame = self.frames.last_mut().unwrap();
+        frame.last_mut().unwrap().push(slot);
+        self.step(span, event);
+    }
+
+    /// Drops the variables in the innermost scope, last declared first.
+    fn drop_scope(&mut self, block: Span) {
+        let end = Span::new(block.end - 1, block.end);
+        let scope = self.frames.last_mut().unwrap().pop().unwrap_or_default();
+        for slot in scope.into_iter().rev() {
+            self.slots[slot].dropped = Some(end);
+            let name = self.slots[slot].name.clone();
+            let event = match &self.slots[slot].value {
+                Some(value) if value.is_copy() || matches!(value, Value::Ref { .. }) => {
+                    Event::OutOfScope { name }
+                }
+                value => Event::Drop {
+                    name,
+                    moved: value.is_none(),
+                },
+            };
+            self.step(end, event);
+        }
+    }
+
+    fn call(
+        &mut self,
+        function: &'p Function,
+        args: Vec<Operand>,
+        span: Span,
+    ) -> Result<Value, Diagnostic> {
+        if self.frames.len() == MAX_DEPTH {
+            return Err(Diagnostic::error(
+                None,
+                "thread 'main' has overflowed its stack",
+                span,
+                format!("`{}` called {} deep", function.name, MAX_DEPTH),
+            ));
+        }
+        self.step(
+            span,
+            Event::Call {
+                function: function.name.clone(),
+            },
+        );
+        self.frames.push(vec![Vec::new()]);
+        for (param, arg) in function.params.iter().zip(args) {
+            self.bind(&param.name, arg, param.span, Some(&function.name));
+        }
+        let value = self.block(&function.body)?;
+        // The parameters go last.
+        self.drop_scope(function.body.span);
+        self.frames.pop();
+
+        let described = match value {
+            Value::Unit => None,
+            _ => Some(self.describe(&value)),
+        };
+        self.step(
+            span,
+            Event::Return {
+                function: function.name.clone(),
+                value: described,
+            },
+        );
+        Ok(value)
+    }
+
+    fn block(&mut self, block: &Block) -> Result<Value, Diagnostic> {
+        self.frames.last_mut().unwrap().push(Vec::new());
+        for statement in &block.statements {
+            self.statement(statement)?;
+        }
+        let value = match &block.tail {
+            Some(tail) => self.operand(tail, false)?.value,
+            None => Value::Unit,
+        };
+        self.drop_scope(block.span);
+        Ok(value)
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Result<(), Diagnostic> {
+        match statement {
+            Statement::Let {
+                name, value, span, ..
+            } => {
+                let operand = self.operand(value, false)?;
+                self.bind(name, operand, *span, None);
+            }
+            Statement::Assign { name, value, span } => {
+                let value = self.operand(value, false)?.value;
+                let slot = self.lookup(name, *span)?;
+                let event = Event::Change {
+                    name: name.clone(),
+                    value: self.describe(&value),
+                };
+                self.slots[slot].ty = self.type_of(&value);
+                self.slots[slot].value = Some(value);
+                self.slots[slot].moved = None;
+                self.step(*span, event);
+            }
+            Statement::Expr(expr) => {
+                self.eval(expr)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// A value used by value: a variable's is copied or moved out of it.
+    /// `reborrow` is for arguments, where a `&mut` is reborrowed instead of
+    /// moved.
+    fn operand(&mut self, expr: &Expr, reborrow: bool) -> Result<Operand, Diagnostic> {
+        let name = match &expr.kind {
+            ExprKind::Var(name) => name,
+            _ => {
+                return Ok(Operand {
+                    value: self.eval(expr)?,
+                    from: None,
+                    copied: false,
+                })
+            }
+        };
+        let slot = self.lookup(name, expr.span)?;
+        let value = match &self.slots[slot].value {
+            Some(value) => value.clone(),
+            None => return Err(self.moved(slot, expr.span, false)),
+        };
+        let copied = value.is_copy() || (reborrow && matches!(value, Value::Ref { .. }));
+        if !copied {
+            self.slots[slot].value = None;
+            self.slots[slot].moved = Some(expr.span);
+        }
+        Ok(Operand {
+            value,
+            from: Some(slot),
+            copied,
+        })
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Result<Value, Diagnostic> {
+        match &expr.kind {
+            ExprKind::Int(value) => Ok(Value::Int(*value)),
+            ExprKind::Str(value) => Ok(Value::Str(value.clone())),
+            ExprKind::Char(value) => Ok(Value::Char(*value)),
+            ExprKind::Var(_) => Ok(self.operand(expr, false)?.value),
+            ExprKind::Ref { mutable, value } => Ok(Value::Ref {
+                slot: self.place(value)?,
+                mutable: *mutable,
+            }),
+            ExprKind::Binary { op, left, right } => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                self.binary(*op, &left, &right, expr.span)
+            }
+            ExprKind::Call { function, args } => self.call_named(function, args, expr.span),
+            ExprKind::Method {
+                receiver,
+                method,
+                args,
+            } => self.method(receiver, method, args, expr.span),
+            ExprKind::Macro { name, args } => self.print(name, args, expr.span),
+            ExprKind::Block(block) => self.block(block),
+        }
+    }
+
+    /// The slot `&expr` refers to. References to anything but a variable
+    /// get a slot of their own.
+    fn place(&mut self, expr: &Expr) -> Result<usize, Diagnostic> {
+        match &expr.kind {
+            ExprKind::Var(name) => {
+                let slot = self.lookup(name, expr.span)?;
+                self.check_alive(slot, expr.span)?;
+                Ok(slot)
+            }
+            _ => {
+                let value = self.eval(expr)?;
+                Ok(self.new_slot("a temporary", value, expr.span))
+            }
+        }
+    }
+
+    /// Errors if the value in a slot has been moved out or dropped.
+    fn check_alive(&self, slot: usize, span: Span) -> Result<(), Diagnostic> {
+        if let Some(dropped) = self.slots[slot].dropped {
+            let name = &self.slots[slot].name;
+            return Err(Diagnostic::error(
+                Some("E0597"),
+                format!("`{}` does not live long enough", name),
+                span,
+                "borrow later used here",
+            )
+            .with_label(
+                dropped,
+                format!("`{}` dropped here while still borrowed", name),
+            ));
+        }
+        if self.slots[slot].value.is_none() {
+            return Err(self.moved(slot, span, true));
+        }
+        Ok(())
+    }
+
+    /// What a value refers to, through any number of references.
+    fn deref(&self, value: &Value, span: Span) -> Result<Value, Diagnostic> {
+        let mut value = value.clone();
+        while let Value::Ref { slot, .. } = value {
+            self.check_alive(slot, span)?;
+            value = self.slots[slot].value.clone().unwrap();
+        }
+        Ok(value)
+    }
+
+    fn binary(
+        &self,
+        op: char,
+        left: &Value,
+        right: &Value,
+        span: Span,
+    ) -> Result<Value, Diagnostic> {
+        let (a, b) = match (self.deref(left, span)?, self.deref(right, span)?) {
+            (Value::Int(a), Value::Int(b)) => (a, b),
+            (Value::Int(_), other) | (other, _) => {
+                return Err(Diagnostic::error(
+                    Some("E0369"),
+                    format!(
+                        "binary operation `{}` cannot be applied to type `{}`",
+                        op,
+                        self.type_of(&other)
+                    ),
+                    span,
+                    "",
+                ))
+            }
+        };
+        let (result, name) = match op {
+            '+' => (a.checked_add(b), "add"),
+            '-' => (a.checked_sub(b), "subtract"),
+            _ => (a.checked_mul(b), "multiply"),
+        };
+        // Integers are `i32`s.
+        match result.filter(|result| i32::try_from(*result).is_ok()) {
+            Some(result) => Ok(Value::Int(result)),
+            None => Err(Diagnostic::error(
+                None,
+                format!("attempt to {} with overflow", name),
+                span,
+                "this panicked",
+            )),
+        }
+    }
+
+    fn call_named(&mut self, name: &str, args: &[Expr], span: Span) -> Result<Value, Diagnostic> {
+        match name {
+            "String::from" => {
+                arity(1, args, span)?;
+                let value = self.eval(&args[0])?;
+                return Ok(Value::String(self.text(&value, args[0].span)?));
+            }
+            "String::new" => {
+                arity(0, args, span)?;
+                return Ok(Value::String(String::new()));
+            }
+            _ => {}
+        }
+        let function = self.program.function(name).ok_or_else(|| {
+            Diagnostic::error(
+                Some("E0425"),
+                format!("cannot find function `{}` in this scope", name),
+                span,
+                "not found in this scope",
+            )
+        })?;
+        arity(function.params.len(), args, span)?;
+        let mut operands = Vec::new();
+        for arg in args {
+            operands.push(self.operand(arg, true)?);
+        }
+        self.call(function, operands, span)
+    }
+
+    fn method(
+        &mut self,
+        receiver: &Expr,
+        method: &str,
+        args: &[Expr],
+        span: Span,
+    ) -> Result<Value, Diagnostic> {
+        let mut values = Vec::new();
+        for arg in args {
+            values.push(self.eval(arg)?);
+        }
+        // Methods borrow their receiver, through as many references as it
+        // takes.
+        let mut slot = self.place(receiver)?;
+        while let Some(Value::Ref { slot: to, .. }) = self.slots[slot].value {
+            self.check_alive(to, receiver.span)?;
+            slot = to;
+        }
+
+        let value = self.slots[slot].value.clone().unwrap();
+        let not_found = || {
+            Diagnostic::error(
+                Some("E0599"),
+                format!(
+                    "no method named `{}` found for `{}`",
+                    method,
+                    self.type_of(&value)
+                ),
+                span,
+                "method not found",
+            )
+        };
+        let text = match &value {
+            Value::String(text) => text.clone(),
+            Value::Str(text) if matches!(method, "len" | "is_empty" | "clone") => text.clone(),
+            _ => return Err(not_found()),
+        };
+
+        let changed = match method {
+            "push_str" => {
+                arity(1, args, span)?;
+                text + &self.text(&values[0], args[0].span)?
+            }
+            "push" => {
+                arity(1, args, span)?;
+                match self.deref(&values[0], args[0].span)? {
+                    Value::Char(c) => format!("{}{}", text, c),
+                    other => return Err(mismatched("char", &self.type_of(&other), args[0].span)),
+                }
+            }
+            "clear" => {
+                arity(0, args, span)?;
+                String::new()
+            }
+            "truncate" => {
+                arity(1, args, span)?;
+                let length = match self.deref(&values[0], args[0].span)? {
+                    Value::Int(length) if length >= 0 => length as usize,
+                    other => return Err(mismatched("usize", &self.type_of(&other), args[0].span)),
+                };
+                if length >= text.len() {
+                    text
+                } else if text.is_char_boundary(length) {
+                    text[..length].to_string()
+                } else {
+                    return Err(Diagnostic::error(
+                        None,
+                        "assertion failed: self.is_char_boundary(new_len)",
+                        span,
+                        "this panicked",
+                    ));
+                }
+            }
+            "len" => {
+                arity(0, args, span)?;
+                return Ok(Value::Int(text.len() as i64));
+            }
+            "is_empty" => {
+                arity(0, args, span)?;
+                return Ok(Value::Bool(text.is_empty()));
+            }
+            "clone" => {
+                arity(0, args, span)?;
+                return Ok(value);
+            }
+            _ => return Err(not_found()),
+        };
+
+        let changed = Value::String(changed);
+        let event = Event::Change {
+            name: self.slots[slot].name.clone(),
+            value: self.describe(&changed),
+        };
+        self.slots[slot].value = Some(changed);
+        self.step(span, event);
+        Ok(Value::Unit)
+    }
+
+    /// `println!` and `print!`, which borrow their arguments rather than
+    /// moving them.
+    fn print(&mut self, name: &str, args: &[Expr], span: Span) -> Result<Value, Diagnostic> {
+        if name != "println" && name != "print" {
+            return Err(Diagnostic::error(
+                None,
+                format!("cannot find macro `{}` in this scope", name),
+                span,
+                "",
+            ));
+        }
+        let format = match args.first().map(|arg| &arg.kind) {
+            Some(ExprKind::Str(format)) => format,
+            _ => {
+                return Err(Diagnostic::error(
+                    None,
+                    "format argument must be a string literal",
+                    args.first().map_or(span, |arg| arg.span),
+                    "",
+                ))
+            }
+        };
+        let mut values = Vec::new();
+        for arg in &args[1..] {
+            let value = match &arg.kind {
+                ExprKind::Var(_) => Value::Ref {
+                    slot: self.place(arg)?,
+                    mutable: false,
+                },
+                _ => self.eval(arg)?,
+            };
+            values.push(self.deref(&value, arg.span)?);
+        }
+
+        let mut text = String::new();
+        let mut values = values.iter();
+        let mut placeholders = 0;
+        let mut rest = format.as_str();
+        while !rest.is_empty() {
+            let (piece, length) = if rest.starts_with("{{") {
+                (Some("{"), 2)
+            } else if rest.starts_with("}}") {
+                (Some("}"), 2)
+            } else if rest.starts_with("{}") || rest.starts_with("{:?}") {
+                placeholders += 1;
+                let debug = rest.starts_with("{:?}");
+                if let Some(value) = values.next() {
+                    text.push_str(&display(value, debug));
+                }
+                (None, if debug { 4 } else { 2 })
+            } else {
+                let c = rest.chars().next().unwrap();
+                text.push(c);
+                (None, c.len_utf8())
+            };
+            text.extend(piece);
+            rest = &rest[length..];
+        }
+        if placeholders != args.len() - 1 {
+            return Err(Diagnostic::error(
+                None,
+                format!(
+                    "{} positional arguments in format string, but there are {} arguments",
+                    placeholders,
+                    args.len() - 1
+                ),
+                span,
+                "",
+            ));
+        }
+
+        if name == "println" {
+            text.push('\n');
+        }
+        self.output.push_str(&text);
+        self.step(span, Event::Print(text));
+        Ok(Value::Unit)
+    }
+
+    /// The text in a `&str`, `String` or reference to one.
+    fn text(&self, value: &Value, span: Span) -> Result<String, Diagnostic> {
+        match self.deref(value, span)? {
+            Value::Str(text) | Value::String(text) => Ok(text),
+            other => Err(mismatched("&str", &self.type_of(&other), span)),
+        }
+    }
+
+    fn type_of(&self, value: &Value) -> String {
+        match value {
+            Value::Unit => String::from("()"),
+            Value::Int(_) => String::from("i32"),
+            Value::Bool(_) => String::from("bool"),
+            Value::Char(_) => String::from("char"),
+            Value::Str(_) => String::from("&str"),
+            Value::String(_) => String::from("String"),
+            Value::Ref { slot, mutable } => format!(
+                "&{}{}",
+                if *mutable { "mut " } else { "" },
+                self.slots[*slot].ty
+            ),
+        }
+    }
+
+    /// A value as the trace shows it.
+    fn describe(&self, value: &Value) -> String {
+        match value {
+            Value::Ref { slot, mutable } => format!(
+                "&{}{}",
+                if *mutable { "mut " } else { "" },
+                self.slots[*slot].name
+            ),
+            other => display(other, true),
+        }
+    }
+
+    /// rustc's E0382, for using or borrowing a variable that's been moved out
+    /// of.
+    fn moved(&self, slot: usize, span: Span, borrow: bool) -> Diagnostic {
+        let slot = &self.slots[slot];
+        let (message, label) = if borrow {
+            ("borrow of moved value", "value borrowed here after move")
+        } else {
+            ("use of moved value", "value used here after move")
+        };
+        let mut error = Diagnostic::error(
+            Some("E0382"),
+            format!("{}: `{}`", message, slot.name),
+            span,
+            label,
+        )
+        .with_label(
+            slot.declared,
+            format!(
+                "move occurs because `{}` has type `{}`, which does not implement the `Copy` trait",
+                slot.name, slot.ty
+            ),
+        );
+        if let Some(moved) = slot.moved {
+            error = error.with_label(moved, "value moved here");
+        }
+        error
+    }
+}
+
+/// A value as `{}` or `{:?}` prints it. References have been followed
+/// already.
+fn display(value: &Value, debug: bool) -> String {
+    match value {
+        Value::Unit => String::from("()"),
+        Value::Int(value) => value.to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Char(value) if debug => format!("{:?}", value),
+        Value::Char(value) => value.to_string(),
+        Value::Str(text) | Value::String(text) if debug => format!("{:?}", text),
+        Value::Str(text) | Value::String(text) => text.clone(),
+        Value::Ref { .. } => String::from("&_"),
+    }
+}
+
+fn arity(expected: usize, args: &[Expr], span: Span) -> Result<(), Diagnostic> {
+    if args.len() == expected {
+        return Ok(());
+    }
+    let plural = |count: usize| if count == 1 { "" } else { "s" };
+    Err(Diagnostic::error(
+        Some("E0061"),
+        format!(
+            "this function takes {} argument{} but {} argument{} were supplied",
+            expected,
+            plural(expected),
+            args.len(),
+            plural(args.len())
+        ),
+        span,
+        format!("expected {} argument{}", expected, plural(expected)),
+    ))
+}
+
+fn mismatched(expected: &str, found: &str, span: Span) -> Diagnostic {
+    Diagnostic::error(
+        Some("E0308"),
+        "mismatched types",
+        span,
+        format!("expected `{}`, found `{}`", expected, found),
+    )
+}
@@ -0,0 +1,145 @@
+//! Splitting mini source into tokens.
+
+use crate::diagnostic::{Diagnostic, Span};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Ident(String),
+    /// `println!`, without the `!`.
+    Macro(String),
+    Int(i64),
+    Str(String),
+    Char(char),
+    Keyword(&'static str),
+    /// Punctuation, like `(` or `->`.
+    Punct(&'static str),
+}
+
+const KEYWORDS: &[&str] = &["fn", "let", "mut"];
+
+/// Longest first, so `->` isn't read as `-` and `>`.
+const PUNCTUATION: &[&str] = &[
+    "->", "::", "(", ")", "{", "}", ",", ";", ":", "&", "=", ".", "+", "-", "*", "<", ">", "|",
+];
+
+/// Every token, with its span. Comments are skipped.
+pub fn tokenize(source: &str) -> Result<Vec<(Token, Span)>, Diagnostic> {
+    let mut tokens = Vec::new();
+    let bytes = source.as_bytes();
+    let mut at = 0;
+
+    while at < source.len() {
+        let rest = &source[at..];
+        let c = rest.chars().next().unwrap();
+
+        if c.is_whitespace() {
+            at += c.len_utf8();
+            continue;
+        }
+        if rest.starts_with("//") {
+            at += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+
+        let start = at;
+        let token = if c.is_ascii_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            at += end;
+            if bytes.get(at) == Some(&b'!') {
+                at += 1;
+                Token::Macro(word.to_string())
+            } else if let Some(keyword) = KEYWORDS.iter().find(|keyword| **keyword == word) {
+                Token::Keyword(keyword)
+            } else {
+                Token::Ident(word.to_string())
+            }
+        } else if c.is_ascii_digit() {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_digit() || c == '_'))
+                .unwrap_or(rest.len());
+            at += end;
+            let digits = rest[..end].replace('_', "");
+            let value = digits.parse().map_err(|_| {
+                Diagnostic::error(
+                    None,
+                    "integer literal is too large",
+                    Span::new(start, at),
+                    "",
+                )
+            })?;
+            Token::Int(value)
+        } else if c == '"' {
+            let (value, length) = quoted(rest, '"').ok_or_else(|| {
+                Diagnostic::error(
+                    None,
+                    "unterminated double quote string",
+                    Span::new(start, source.len()),
+                    "",
+                )
+            })?;
+            at += length;
+            Token::Str(value)
+        } else if c == '\'' {
+            let (value, length) = quoted(rest, '\'').ok_or_else(|| {
+                Diagnostic::error(
+                    None,
+                    "unterminated character literal",
+                    Span::new(start, start + 1),
+                    "",
+                )
+            })?;
+            let mut chars = value.chars();
+            let c = match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => {
+                    return Err(Diagnostic::error(
+                        None,
+                        "character literal may only contain one codepoint",
+                        Span::new(start, start + length),
+                        "",
+                    ))
+                }
+            };
+            at += length;
+            Token::Char(c)
+        } else if let Some(punct) = PUNCTUATION.iter().find(|punct| rest.starts_with(**punct)) {
+            at += punct.len();
+            Token::Punct(punct)
+        } else {
+            return Err(Diagnostic::error(
+                None,
+                format!("unknown start of token: {}", c),
+                Span::new(start, start + c.len_utf8()),
+                "",
+            ));
+        };
+        tokens.push((token, Span::new(start, at)));
+    }
+    Ok(tokens)
+}
+
+/// A quoted literal at the start of `text`, unescaped, and its length
+/// including the quotes.
+fn quoted(text: &str, quote: char) -> Option<(String, usize)> {
+    let mut value = String::new();
+    let mut chars = text.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                let (_, escaped) = chars.next()?;
+                value.push(match escaped {
+                    'n' => '\n',
+                    't' => '\t',
+                    '0' => '\0',
+                    other => other,
+                });
+            }
+            c if c == quote => return Some((value, index + 1)),
+            c => value.push(c),
+        }
+    }
+    None
+}
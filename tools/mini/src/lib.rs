@@ -0,0 +1,58 @@
+//! mini: a tiny Rust-like language, for showing what the compiler does with
+//! ownership and borrowing without the rest of Rust in the way.
+//!
+//! ```text
+//! fn main() {
+//!     let mut name = String::from("Gabe");
+//!     say_hello(&name);
+//!     append_a(&mut name);
+//! }
+//!
+//! fn say_hello(name: &String) {
+//!     println!("Hello, {}!", name);
+//! }
+//!
+//! fn append_a(name: &mut String) {
+//!     name.push('a');
+//! }
+//! ```
+//!
+//! It has `let` and `let mut`, assignment, `&` and `&mut`, functions and
+//! calls, blocks, integers, strings and chars, `+`, `-` and `*`, a few of
+//! String's methods, and `println!`. There are no branches, loops, structs or
+//! lifetimes. The [`borrowck`] module checks a program against the borrowing
+//! rules from the `borrowing` lesson, and the [`interpreter`] runs it, keeping
+//! track of which variables have been moved out of, like the
+//! `variables_and_functions` lesson describes. Example programs are in
+//! `programs/`.
+
+pub mod ast;
+pub mod borrowck;
+pub mod diagnostic;
+pub mod interpreter;
+pub mod lexer;
+pub mod parser;
+
+use crate::diagnostic::Diagnostic;
+use crate::interpreter::Run;
+
+/// Parses and borrow checks a program, returning every error in it.
+pub fn check(source: &str) -> Vec<Diagnostic> {
+    match parser::parse(source) {
+        Ok(program) => borrowck::check(&program),
+        Err(error) => vec![error],
+    }
+}
+
+/// Parses and runs a program, stopping at a syntax error or the first use of
+/// a moved value.
+pub fn run(source: &str) -> Run {
+    match parser::parse(source) {
+        Ok(program) => interpreter::run(&program),
+        Err(error) => Run {
+            steps: Vec::new(),
+            output: String::new(),
+            error: Some(error),
+        },
+    }
+}
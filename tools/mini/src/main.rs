@@ -0,0 +1,100 @@
+//! `mini` checks mini programs and runs them.
+
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+const USAGE: &str = "\
+usage: mini <command>
+
+commands:
+    check <file>...
+                  borrow check programs, and explain the rules they break
+    run <file>    run a program, showing every copy, move and drop, and stop
+                  at the first use of a moved value
+    step <file>   the same, one step at a time: Enter for the next step, q to
+                  stop";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match mini(&args) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("mini: {}", error);
+            process::exit(2);
+        }
+    }
+}
+
+/// Runs a command, returning whether every program was fine.
+fn mini(args: &[&str]) -> Result<bool, String> {
+    match args {
+        ["check", files @ ..] if !files.is_empty() => {
+            let mut all_ok = true;
+            for file in files {
+                let source =
+                    fs::read_to_string(file).map_err(|error| format!("{}: {}", file, error))?;
+                let errors = mini::check(&source);
+                for error in &errors {
+                    println!("{}", error.render(file, &source));
+                }
+                if errors.is_empty() {
+                    println!("{}: no borrow errors", file);
+                } else {
+                    println!(
+                        "{}: {} error{}",
+                        file,
+                        errors.len(),
+                        if errors.len() == 1 { "" } else { "s" }
+                    );
+                    all_ok = false;
+                }
+            }
+            Ok(all_ok)
+        }
+        ["run", file] => run(file, false),
+        ["step", file] => run(file, true),
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{}", USAGE);
+            Ok(true)
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+/// Runs a program and prints its trace, waiting for Enter after each step if
+/// `step` is set. Returns whether it ran to the end.
+fn run(file: &str, step: bool) -> Result<bool, String> {
+    let source = fs::read_to_string(file).map_err(|error| format!("{}: {}", file, error))?;
+    let run = mini::run(&source);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut previous = None;
+    for current in &run.steps {
+        print!("{}", current.render(&source, previous));
+        previous = Some(current);
+        if step {
+            io::stdout().flush().map_err(|error| error.to_string())?;
+            match lines.next() {
+                Some(Ok(line)) if line.trim() == "q" => return Ok(true),
+                Some(Ok(_)) => {}
+                Some(Err(error)) => return Err(error.to_string()),
+                None => break,
+            }
+        }
+    }
+
+    match &run.error {
+        Some(error) => {
+            println!();
+            println!("{}", error.render(file, &source));
+            Ok(false)
+        }
+        None => Ok(true),
+    }
+}
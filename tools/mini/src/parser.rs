@@ -0,0 +1,461 @@
+//! Turning tokens into a [`Program`].
+//!
+//! ```text
+//! program   = function*
+//! function  = "fn" ident "(" (param ("," param)*)? ")" ("->" type)? block
+//! param     = "mut"? ident ":" type
+//! type      = "&" "mut"? type | ident
+//! block     = "{" statement* expr? "}"
+//! statement = "let" "mut"? ident (":" type)? "=" expr ";"
+//!           | ident "=" expr ";"
+//!           | expr ";" | block
+//! expr      = unary (("+" | "-" | "*") unary)*
+//! unary     = "&" "mut"? unary | postfix
+//! postfix   = primary ("." ident "(" args ")")*
+//! primary   = int | string | char | path ("(" args ")")? | macro "(" args ")"
+//!           | "(" expr ")" | block
+//! ```
+//!
+//! Checking and running a program recurse through it, so how deep its tree
+//! can get is limited, to report an error rather than overflow the stack.
+//! Brackets, blocks, `&`s and calls can nest [`MAX_DEPTH`] deep, and chains
+//! of operators and method calls, which the tree nests even though the source
+//! doesn't, can be [`MAX_LENGTH`] operators long, counting the chains inside
+//! them and a method call as [`METHOD_LENGTH`] operators.
+
+use std::mem;
+
+use crate::ast::{Block, Expr, ExprKind, Function, Param, Program, Statement, Type};
+use crate::diagnostic::{Diagnostic, Span};
+use crate::lexer::{self, Token};
+
+/// How deeply expressions and types can nest.
+pub const MAX_DEPTH: usize = 128;
+
+/// How long chains of operators and method calls can be, added up over the
+/// chains inside them.
+pub const MAX_LENGTH: usize = 512;
+
+/// How much of [`MAX_LENGTH`] a method call takes up. Running one takes
+/// about four times the stack an operator does.
+pub const METHOD_LENGTH: usize = 4;
+
+pub fn parse(source: &str) -> Result<Program, Diagnostic> {
+    let tokens = lexer::tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        at: 0,
+        end: source.len(),
+        depth: 0,
+        links: 0,
+    };
+    let mut functions = Vec::new();
+    while parser.peek().is_some() {
+        functions.push(parser.function()?);
+    }
+    Ok(Program { functions })
+}
+
+struct Parser {
+    tokens: Vec<(Token, Span)>,
+    at: usize,
+    /// The length of the source, for errors at the end of it.
+    end: usize,
+    /// How deeply nested the next expression or type will be.
+    depth: usize,
+    /// How long, in operators, the longest chain on any one path down through
+    /// the expressions parsed since this was last reset is.
+    links: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.at).map(|(token, _)| token)
+    }
+
+    fn peek_at(&self, ahead: usize) -> Option<&Token> {
+        self.tokens.get(self.at + ahead).map(|(token, _)| token)
+    }
+
+    /// The span of the next token, or the end of the source.
+    fn span(&self) -> Span {
+        self.tokens
+            .get(self.at)
+            .map_or(Span::new(self.end, self.end), |(_, span)| *span)
+    }
+
+    /// The span of the token just taken.
+    fn previous(&self) -> Span {
+        self.tokens[self.at - 1].1
+    }
+
+    fn is(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punct(p)) if *p == punct)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Keyword(k)) if *k == keyword)
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let is = self.is(punct);
+        if is {
+            self.at += 1;
+        }
+        is
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let is = self.is_keyword(keyword);
+        if is {
+            self.at += 1;
+        }
+        is
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<Span, Diagnostic> {
+        if self.eat(punct) {
+            Ok(self.previous())
+        } else {
+            Err(self.unexpected(&format!("`{}`", punct)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<(String, Span), Diagnostic> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.at += 1;
+                Ok((name, self.previous()))
+            }
+            _ => Err(self.unexpected("an identifier")),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Diagnostic {
+        let found = match self.peek() {
+            None => String::from("the end of the file"),
+            Some(Token::Ident(name)) => format!("`{}`", name),
+            Some(Token::Macro(name)) => format!("`{}!`", name),
+            Some(Token::Int(value)) => format!("`{}`", value),
+            Some(Token::Str(value)) => format!("{:?}", value),
+            Some(Token::Char(value)) => format!("{:?}", value),
+            Some(Token::Keyword(keyword)) => format!("`{}`", keyword),
+            Some(Token::Punct(punct)) => format!("`{}`", punct),
+        };
+        Diagnostic::error(
+            None,
+            format!("expected {}, found {}", expected, found),
+            self.span(),
+            format!("expected {}", expected),
+        )
+    }
+
+    /// The error for a chain that makes the tree too deep, at the operator or
+    /// method call that went over.
+    fn too_long(&self, at: Span) -> Diagnostic {
+        Diagnostic::error(None, "this expression is too long to check", at, "too long").with_note(
+            format!(
+                "chains can only be {} operators long, counting the chains inside them, and a method call counts as {}",
+                MAX_LENGTH, METHOD_LENGTH
+            ),
+        )
+    }
+
+    /// Goes one level deeper into the tree, unless that's too deep. Whoever
+    /// calls this puts `depth` back once they've parsed what's under it.
+    fn descend(&mut self) -> Result<(), Diagnostic> {
+        if self.depth == MAX_DEPTH {
+            return Err(Diagnostic::error(
+                None,
+                format!("this is nested more than {} levels deep", MAX_DEPTH),
+                self.span(),
+                "too deep",
+            )
+            .with_note("each `(`, block, `&` and call's arguments go a level deeper"));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn function(&mut self) -> Result<Function, Diagnostic> {
+        let start = self.span();
+        if !self.eat_keyword("fn") {
+            return Err(self.unexpected("`fn`"));
+        }
+        let (name, _) = self.ident()?;
+        self.expect("(")?;
+        let mut params = Vec::new();
+        while !self.is(")") {
+            let start = self.span();
+            let mutable = self.eat_keyword("mut");
+            let (name, _) = self.ident()?;
+            self.expect(":")?;
+            let ty = self.ty()?;
+            params.push(Param {
+                name,
+                mutable,
+                ty,
+                span: start.to(self.previous()),
+            });
+            if !self.eat(",") {
+                break;
+            }
+        }
+        self.expect(")")?;
+        let returns = if self.eat("->") {
+            Some(self.ty()?)
+        } else {
+            None
+        };
+        let body = self.block()?;
+        Ok(Function {
+            name,
+            params,
+            returns,
+            span: start.to(body.span),
+            body,
+        })
+    }
+
+    fn ty(&mut self) -> Result<Type, Diagnostic> {
+        if self.eat("&") {
+            let depth = self.depth;
+            self.descend()?;
+            let mutable = self.eat_keyword("mut");
+            let inner = Box::new(self.ty()?);
+            self.depth = depth;
+            return Ok(Type::Ref { mutable, inner });
+        }
+        let (name, _) = self.ident()?;
+        Ok(Type::Named(name))
+    }
+
+    fn block(&mut self) -> Result<Block, Diagnostic> {
+        let start = self.expect("{")?;
+        let mut statements = Vec::new();
+        let mut tail = None;
+
+        while !self.is("}") {
+            if self.peek().is_none() {
+                return Err(self.unexpected("`}`"));
+            }
+            if self.is_keyword("let") {
+                statements.push(self.let_statement()?);
+                continue;
+            }
+            if matches!(self.peek(), Some(Token::Ident(_)))
+                && matches!(self.peek_at(1), Some(Token::Punct("=")))
+            {
+                let (name, start) = self.ident()?;
+                self.expect("=")?;
+                let value = self.expr()?;
+                let end = self.expect(";")?;
+                statements.push(Statement::Assign {
+                    name,
+                    value,
+                    span: start.to(end),
+                });
+                continue;
+            }
+
+            let expr = self.expr()?;
+            if self.eat(";") {
+                statements.push(Statement::Expr(expr));
+            } else if self.is("}") {
+                tail = Some(Box::new(expr));
+            } else if matches!(expr.kind, ExprKind::Block(_)) {
+                statements.push(Statement::Expr(expr));
+            } else {
+                return Err(self.unexpected("`;`"));
+            }
+        }
+        let end = self.expect("}")?;
+        Ok(Block {
+            statements,
+            tail,
+            span: start.to(end),
+        })
+    }
+
+    fn let_statement(&mut self) -> Result<Statement, Diagnostic> {
+        let start = self.span();
+        self.eat_keyword("let");
+        let mutable = self.eat_keyword("mut");
+        let (name, _) = self.ident()?;
+        let ty = if self.eat(":") {
+            Some(self.ty()?)
+        } else {
+            None
+        };
+        self.expect("=")?;
+        let value = self.expr()?;
+        let end = self.expect(";")?;
+        Ok(Statement::Let {
+            name,
+            mutable,
+            ty,
+            value,
+            span: start.to(end),
+        })
+    }
+
+    fn expr(&mut self) -> Result<Expr, Diagnostic> {
+        let depth = self.depth;
+        self.descend()?;
+        let outer = mem::take(&mut self.links);
+        let mut left = self.unary()?;
+        // The chain is parsed in a loop, but the tree it makes is as deep as
+        // it is long: `1 + 2 + 3` is `(1 + 2) + 3`.
+        let mut longest = self.links;
+        let mut length = 0;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Punct("+")) => '+',
+                Some(Token::Punct("-")) => '-',
+                Some(Token::Punct("*")) => '*',
+                _ => break,
+            };
+            let at = self.span();
+            self.at += 1;
+            self.links = 0;
+            let right = self.unary()?;
+            length += 1;
+            longest = longest.max(self.links);
+            if longest + length > MAX_LENGTH {
+                return Err(self.too_long(at));
+            }
+            left = Expr {
+                span: left.span.to(right.span),
+                kind: ExprKind::Binary {
+                    op,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+            };
+        }
+        self.links = outer.max(longest + length);
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.span();
+        if self.eat("&") {
+            let depth = self.depth;
+            self.descend()?;
+            let mutable = self.eat_keyword("mut");
+            let value = self.unary()?;
+            self.depth = depth;
+            return Ok(Expr {
+                span: start.to(value.span),
+                kind: ExprKind::Ref {
+                    mutable,
+                    value: Box::new(value),
+                },
+            });
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Result<Expr, Diagnostic> {
+        let outer = mem::take(&mut self.links);
+        let mut expr = self.primary()?;
+        let mut longest = self.links;
+        let mut length = 0;
+        while self.eat(".") {
+            let at = self.previous();
+            let (method, _) = self.ident()?;
+            self.links = 0;
+            let (args, end) = self.args()?;
+            length += METHOD_LENGTH;
+            longest = longest.max(self.links);
+            if longest + length > MAX_LENGTH {
+                return Err(self.too_long(at));
+            }
+            expr = Expr {
+                span: expr.span.to(end),
+                kind: ExprKind::Method {
+                    receiver: Box::new(expr),
+                    method,
+                    args,
+                },
+            };
+        }
+        self.links = outer.max(longest + length);
+        Ok(expr)
+    }
+
+    /// `(a, b, c)`, returning the span of the `)`.
+    fn args(&mut self) -> Result<(Vec<Expr>, Span), Diagnostic> {
+        self.expect("(")?;
+        let mut args = Vec::new();
+        while !self.is(")") {
+            args.push(self.expr()?);
+            if !self.eat(",") {
+                break;
+            }
+        }
+        let end = self.expect(")")?;
+        Ok((args, end))
+    }
+
+    fn primary(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.span();
+        let kind = match self.peek().cloned() {
+            Some(Token::Int(value)) => {
+                self.at += 1;
+                ExprKind::Int(value)
+            }
+            Some(Token::Str(value)) => {
+                self.at += 1;
+                ExprKind::Str(value)
+            }
+            Some(Token::Char(value)) => {
+                self.at += 1;
+                ExprKind::Char(value)
+            }
+            Some(Token::Macro(name)) => {
+                self.at += 1;
+                let (args, end) = self.args()?;
+                return Ok(Expr {
+                    kind: ExprKind::Macro { name, args },
+                    span: start.to(end),
+                });
+            }
+            Some(Token::Ident(_)) => {
+                let (mut path, _) = self.ident()?;
+                while self.eat("::") {
+                    let (segment, _) = self.ident()?;
+                    path = format!("{}::{}", path, segment);
+                }
+                if self.is("(") {
+                    let (args, end) = self.args()?;
+                    return Ok(Expr {
+                        kind: ExprKind::Call {
+                            function: path,
+                            args,
+                        },
+                        span: start.to(end),
+                    });
+                }
+                ExprKind::Var(path)
+            }
+            Some(Token::Punct("(")) => {
+                self.at += 1;
+                let expr = self.expr()?;
+                self.expect(")")?;
+                return Ok(expr);
+            }
+            Some(Token::Punct("{")) => {
+                let block = self.block()?;
+                return Ok(Expr {
+                    span: block.span,
+                    kind: ExprKind::Block(block),
+                });
+            }
+            _ => return Err(self.unexpected("an expression")),
+        };
+        Ok(Expr { kind, span: start })
+    }
+}
@@ -0,0 +1,118 @@
+//! Borrow checks the example programs, which say what they expect on their
+//! first line: `// expect: ok`, or the error code and the rule it breaks.
+
+use std::fs;
+use std::path::Path;
+
+use mini::diagnostic::{Rule, Span};
+
+#[test]
+fn programs_get_the_errors_they_expect() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("programs");
+    let mut checked = 0;
+    let mut failures = Vec::new();
+
+    let mut paths: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "mini")
+        })
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let source = fs::read_to_string(&path).unwrap();
+        let expect = source
+            .lines()
+            .next()
+            .and_then(|line| line.strip_prefix("// expect: "))
+            .unwrap_or_else(|| panic!("{} doesn't say what it expects", path.display()));
+        let errors = mini::check(&source);
+        let got = match errors.as_slice() {
+            [] => String::from("ok"),
+            [error] => match error.rule {
+                Some(rule) => format!("{} {}", error.code.unwrap_or("?"), rule),
+                None => error.code.unwrap_or("?").to_string(),
+            },
+            _ => format!("{} errors", errors.len()),
+        };
+        if got != expect {
+            failures.push(format!(
+                "{}: expected {}, got {}",
+                path.display(),
+                expect,
+                got
+            ));
+        }
+        checked += 1;
+    }
+
+    assert!(checked >= 6, "only found {} programs", checked);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn errors_point_at_the_second_borrow() {
+    let source = "\
+fn main() {
+    let mut name = String::from(\"Gabe\");
+    let first = &mut name;
+    let second = &mut name;
+    first.push('a');
+}
+";
+    let errors = mini::check(source);
+    assert_eq!(errors.len(), 1);
+    let error = &errors[0];
+    assert_eq!(error.code, Some("E0499"));
+    assert_eq!(error.rule, Some(Rule::TwoA));
+    assert_eq!(error.span().position(source), (4, 18));
+    // The first borrow, and where it's used later.
+    let lines: Vec<usize> = error
+        .labels
+        .iter()
+        .map(|label| label.span.position(source).0)
+        .collect();
+    assert_eq!(lines, [4, 3, 5]);
+}
+
+#[test]
+fn borrows_end_at_their_last_use() {
+    let source = "\
+fn main() {
+    let mut name = String::from(\"Gabe\");
+    let reference = &name;
+    println!(\"{}\", reference);
+    name.push_str(\" Love\");
+    let unused = &mut name;
+    name.push('a');
+}
+";
+    assert_eq!(mini::check(source), []);
+}
+
+#[test]
+fn using_the_owner_during_a_mutable_borrow() {
+    let source = "\
+fn main() {
+    let mut name = String::from(\"Gabe\");
+    let mutable = &mut name;
+    println!(\"{}\", name);
+    mutable.push('a');
+}
+";
+    let errors = mini::check(source);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code, Some("E0502"));
+    assert_eq!(errors[0].rule, Some(Rule::TwoB));
+}
+
+#[test]
+fn syntax_errors_have_spans() {
+    let errors = mini::check("fn main() {\n    let x = ;\n}\n");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code, None);
+    assert_eq!(errors[0].span(), Span::new(24, 25));
+}
@@ -0,0 +1,174 @@
+//! Runs the example programs that say what running them should do on their
+//! second line: `// run: ok`, or the error code it stops with.
+
+use std::fs;
+use std::path::Path;
+
+use mini::interpreter::Event;
+
+#[test]
+fn programs_run_the_way_they_expect() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("programs");
+    let mut ran = 0;
+    let mut failures = Vec::new();
+
+    let mut paths: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "mini")
+        })
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let source = fs::read_to_string(&path).unwrap();
+        let expect = match source
+            .lines()
+            .nth(1)
+            .and_then(|line| line.strip_prefix("// run: "))
+        {
+            Some(expect) => expect,
+            None => continue,
+        };
+        let run = mini::run(&source);
+        let got = match &run.error {
+            None => "ok",
+            Some(error) => error.code.unwrap_or("?"),
+        };
+        if got != expect {
+            failures.push(format!(
+                "{}: expected {}, got {}",
+                path.display(),
+                expect,
+                got
+            ));
+        }
+        ran += 1;
+    }
+
+    assert!(ran >= 2, "only found {} programs to run", ran);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn integers_are_copied_and_strings_are_moved() {
+    let source = "\
+fn main() {
+    let x = 5;
+    let y = x;
+    let name = String::from(\"Gabe\");
+    let other = name;
+    println!(\"{} {} {}\", x, y, other);
+}
+";
+    let run = mini::run(source);
+    assert_eq!(run.error, None);
+    assert_eq!(run.output, "5 5 Gabe\n");
+    let events: Vec<&Event> = run.steps.iter().map(|step| &step.event).collect();
+    assert!(events.contains(&&Event::Copy {
+        from: String::from("x"),
+        to: String::from("y"),
+        function: None,
+        value: String::from("5"),
+    }));
+    assert!(events.contains(&&Event::Move {
+        from: String::from("name"),
+        to: String::from("other"),
+        function: None,
+    }));
+    // `name` was moved out, so only `other` drops the string.
+    assert!(events.contains(&&Event::Drop {
+        name: String::from("name"),
+        moved: true,
+    }));
+    assert!(events.contains(&&Event::Drop {
+        name: String::from("other"),
+        moved: false,
+    }));
+}
+
+#[test]
+fn use_after_move_points_at_the_move() {
+    let source = "\
+fn main() {
+    let name = String::from(\"Gabe\");
+    take(name);
+    println!(\"{}\", name);
+}
+
+fn take(name: String) {}
+";
+    let run = mini::run(source);
+    let error = run.error.expect("it should stop");
+    assert_eq!(error.code, Some("E0382"));
+    assert_eq!(error.message, "borrow of moved value: `name`");
+    assert_eq!(error.span().position(source), (4, 20));
+    let lines: Vec<usize> = error
+        .labels
+        .iter()
+        .map(|label| label.span.position(source).0)
+        .collect();
+    assert_eq!(lines, [4, 2, 3]);
+    assert!(error.labels[1].text.contains("type `String`"));
+    // Nothing runs after the error.
+    assert_eq!(run.output, "");
+}
+
+#[test]
+fn mutable_references_are_reborrowed_when_passed() {
+    let source = "\
+fn main() {
+    let mut name = String::from(\"Gabe\");
+    let reference = &mut name;
+    append_a(reference);
+    append_a(reference);
+    let moved = reference;
+    append_a(reference);
+}
+
+fn append_a(name: &mut String) {
+    name.push('a');
+}
+";
+    let run = mini::run(source);
+    let error = run.error.expect("it should stop");
+    assert_eq!(error.code, Some("E0382"));
+    assert_eq!(error.span().position(source).0, 7);
+    assert!(run.steps.iter().any(|step| step.event
+        == Event::Change {
+            name: String::from("name"),
+            value: String::from("\"Gabeaa\""),
+        }));
+}
+
+#[test]
+fn returning_moves_the_value_out() {
+    let source = "\
+fn main() {
+    let name = make();
+    println!(\"{}\", name);
+}
+
+fn make() -> String {
+    let made = String::from(\"Gabe\");
+    made
+}
+";
+    let run = mini::run(source);
+    assert_eq!(run.error, None);
+    assert_eq!(run.output, "Gabe\n");
+    assert!(run.steps.iter().any(|step| step.event
+        == Event::Drop {
+            name: String::from("made"),
+            moved: true,
+        }));
+}
+
+#[test]
+fn overflow_panics() {
+    let run = mini::run("fn main() {\n    let x = 2147483647 + 1;\n}\n");
+    let error = run.error.expect("it should stop");
+    assert_eq!(error.message, "attempt to add with overflow");
+}
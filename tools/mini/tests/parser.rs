@@ -0,0 +1,96 @@
+//! Parses programs that nest too deeply or chain too long to check or run,
+//! and ones that only just fit.
+
+use mini::diagnostic::Span;
+use mini::parser::{self, MAX_DEPTH, MAX_LENGTH, METHOD_LENGTH};
+
+/// `let x = <expr>;` in a `main`, and where `<expr>` starts.
+fn program(expr: &str) -> (String, usize) {
+    let start = "fn main() { let x = ";
+    (format!("{}{}; }}", start, expr), start.len())
+}
+
+/// The span of the error from parsing `source`, which must fail to parse for
+/// being too deep.
+fn too_deep(source: &str) -> Span {
+    let error = parser::parse(source).expect_err("it should be too deep");
+    assert_eq!(
+        error.message,
+        format!("this is nested more than {} levels deep", MAX_DEPTH)
+    );
+    error.span()
+}
+
+/// The span of the error from parsing `source`, which must fail to parse for
+/// being too long.
+fn too_long(source: &str) -> Span {
+    let error = parser::parse(source).expect_err("it should be too long");
+    assert_eq!(error.message, "this expression is too long to check");
+    error.span()
+}
+
+/// Checks and runs `expr`, which should be fine.
+fn fits(expr: &str) {
+    let (source, _) = program(expr);
+    assert!(parser::parse(&source).is_ok(), "{}", expr);
+    assert_eq!(mini::check(&source), [], "{}", expr);
+    let run = mini::run(&source);
+    assert!(run.error.is_none(), "{}: {:?}", expr, run.error);
+}
+
+#[test]
+fn a_hundred_thousand_brackets_are_an_error_instead_of_a_crash() {
+    let depth = 100_000;
+    let (source, start) = program(&format!("{}1{}", "(".repeat(depth), ")".repeat(depth)));
+    // The bracket that would go past the limit.
+    let at = start + MAX_DEPTH;
+    assert_eq!(too_deep(&source), Span::new(at, at + 1));
+    assert_eq!(mini::check(&source).len(), 1);
+    assert!(mini::run(&source).error.is_some());
+}
+
+#[test]
+fn nesting_too_deep() {
+    for expr in [
+        "&".repeat(100_000) + "1",
+        "{ ".repeat(100_000) + "1" + &" }".repeat(100_000),
+    ] {
+        too_deep(&program(&expr).0);
+    }
+
+    let ty = format!("fn f(x: {}i32) {{}}", "&".repeat(100_000));
+    too_deep(&ty);
+}
+
+#[test]
+fn long_flat_sums_are_not_nested() {
+    fits(&format!("1{}", " + 1".repeat(500)));
+}
+
+#[test]
+fn long_chains_are_too_long_instead_of_a_crash() {
+    let chain = |link: &str| format!("1{}", link.repeat(100_000));
+    for (link, length) in [(" + 1", 1), (".clone()", METHOD_LENGTH)] {
+        let (source, start) = program(&chain(link));
+        // The operator or method call that would go past the limit.
+        let links = MAX_LENGTH / length;
+        let at = start + 1 + links * link.len() + link.find(['+', '.']).unwrap();
+        assert_eq!(too_long(&source), Span::new(at, at + 1), "{}", link);
+    }
+
+    // A chain inside the first operand of another still makes the tree that
+    // much deeper.
+    let half = MAX_LENGTH / 2 + 1;
+    let inner = format!("(1{})", " + 1".repeat(half));
+    too_long(&program(&format!("{}{}", inner, " * 1".repeat(half))).0);
+}
+
+#[test]
+fn programs_just_inside_the_limits_check_and_run() {
+    let depth = MAX_DEPTH - 1;
+    fits(&format!("{}1{}", "(".repeat(depth), ")".repeat(depth)));
+    fits(&format!("{}1{}", "{ ".repeat(depth), " }".repeat(depth)));
+    fits(&format!("1{}", " + 1".repeat(MAX_LENGTH)));
+    let methods = MAX_LENGTH / METHOD_LENGTH;
+    fits(&format!("String::new(){}", ".clone()".repeat(methods)));
+}
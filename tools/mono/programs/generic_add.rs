@@ -0,0 +1,29 @@
+// `add` is written once, and the compiler writes out a copy of it for each
+// type it's used with: `add::<i32>`, `add::<u64>` and `add::<f64>`.
+// `add_i32` is the copy we'd have written by hand, so that `add::<i32>` has
+// something to be compared with.
+//
+// `black_box` keeps the compiler from working out every sum while it
+// compiles, and `#[inline(never)]` keeps each function's code in one place,
+// instead of copied into `main`, so that there's something to look at in
+// release.
+
+use std::hint::black_box;
+use std::ops::Add;
+
+#[inline(never)]
+fn add<T: Add<Output = T>>(first: T, second: T) -> T {
+    first + second
+}
+
+#[inline(never)]
+fn add_i32(first: i32, second: i32) -> i32 {
+    first + second
+}
+
+fn main() {
+    println!("{}", add(black_box(5i32), black_box(6)));
+    println!("{}", add(black_box(5u64), black_box(6)));
+    println!("{}", add(black_box(0.5f64), black_box(0.25)));
+    println!("{}", add_i32(black_box(5), black_box(6)));
+}
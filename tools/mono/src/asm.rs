@@ -0,0 +1,146 @@
+//! The functions in an assembly file rustc emitted.
+//!
+//! This reads the ELF flavour of x86-64 and AArch64 assembly that rustc emits
+//! on Linux, where each function is announced with `.type <symbol>,@function`
+//! and ends at a `.Lfunc_end` label.
+
+use crate::demangle::{self, split_generics};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    pub symbol: String,
+    /// The demangled name, like `generic_add::add::<i32>`.
+    pub name: String,
+    /// Its instructions and the labels between them, with symbols
+    /// demangled and the assembler directives left out.
+    pub lines: Vec<String>,
+}
+
+impl Function {
+    /// How many instructions it has, not counting labels.
+    pub fn instructions(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|line| !line.ends_with(':'))
+            .count()
+    }
+}
+
+/// Every function in a build, in the order rustc emitted them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Assembly {
+    pub functions: Vec<Function>,
+}
+
+impl Assembly {
+    pub fn parse(text: &str) -> Assembly {
+        let symbols: Vec<&str> = text
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix(".type"))
+            .filter_map(|rest| {
+                let rest = rest.trim();
+                rest.strip_suffix(",@function")
+                    .or_else(|| rest.strip_suffix(",%function"))
+            })
+            .collect();
+
+        let mut functions = Vec::new();
+        let mut current: Option<Function> = None;
+        for line in text.lines() {
+            if let Some(label) = line.strip_suffix(':') {
+                if symbols.contains(&label) {
+                    current = Some(Function {
+                        symbol: label.to_string(),
+                        name: demangle::demangle(label).unwrap_or_else(|| label.to_string()),
+                        lines: Vec::new(),
+                    });
+                    continue;
+                }
+                if label.starts_with(".Lfunc_end") {
+                    functions.extend(current.take());
+                    continue;
+                }
+            }
+            let function = match &mut current {
+                Some(function) => function,
+                None => continue,
+            };
+            let line = strip_comment(line).trim();
+            if line.is_empty() || line.starts_with(".Lfunc_begin") || line.starts_with(".Ltmp") {
+                continue;
+            }
+            // Directives, but not the labels jumps go to.
+            if line.starts_with('.') && !line.ends_with(':') {
+                continue;
+            }
+            function.lines.push(instruction(line));
+        }
+        Assembly { functions }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+
+    /// Every instance of the function called `function` in the crate
+    /// `krate`: one for each set of generic arguments it was used with, or
+    /// just the one if it isn't generic. `function` can be a bare name like
+    /// `add`, or a path inside the crate like `Point::new`.
+    pub fn instances(&self, krate: &str, function: &str) -> Vec<&Function> {
+        let mut instances: Vec<&Function> = self
+            .functions
+            .iter()
+            .filter(|candidate| is_instance(&candidate.name, krate, function))
+            .collect();
+        instances.sort_by(|a, b| a.name.cmp(&b.name));
+        instances
+    }
+}
+
+fn is_instance(name: &str, krate: &str, function: &str) -> bool {
+    // `<generic_add::Point>::new` is `Point::new`, as far as finding it goes.
+    let (path, _) = split_generics(name);
+    let path: String = path.chars().filter(|c| *c != '<' && *c != '>').collect();
+    path.starts_with(&format!("{}::", krate)) && path.ends_with(&format!("::{}", function))
+}
+
+/// `\tmovl\t%edi, %eax` is `movl    %edi, %eax`, with its symbols
+/// demangled.
+fn instruction(line: &str) -> String {
+    let line = shorten_constants(&demangle::demangle_line(line));
+    match line.split_once(char::is_whitespace) {
+        Some((mnemonic, operands)) if !line.ends_with(':') => {
+            format!("{:<7} {}", mnemonic, operands.trim())
+        }
+        _ => line,
+    }
+}
+
+/// Constants like panic messages are named `.Lanon.<hash>.<n>`, and the
+/// hash is the same for every one of them.
+fn shorten_constants(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find(".Lanon.") {
+        let after = &rest[start + ".Lanon.".len()..];
+        let hash = after
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(after.len());
+        out.push_str(&rest[..start]);
+        out.push_str(".Lanon");
+        rest = &after[hash..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Comments start with `#` on x86-64 and `//` on AArch64, where `#` is for
+/// immediates instead.
+fn strip_comment(line: &str) -> &str {
+    let comment = if cfg!(target_arch = "aarch64") {
+        "//"
+    } else {
+        "#"
+    };
+    line.find(comment).map_or(line, |end| &line[..end])
+}
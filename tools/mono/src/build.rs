@@ -0,0 +1,126 @@
+//! Compiling a program to assembly, the way cargo would build it.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use lessons::Workspace;
+
+use crate::asm::Assembly;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    Debug,
+    Release,
+}
+
+impl Profile {
+    pub const ALL: [Profile; 2] = [Profile::Debug, Profile::Release];
+
+    /// What cargo calls its target directory.
+    pub fn name(self) -> &'static str {
+        match self {
+            Profile::Debug => "debug",
+            Profile::Release => "release",
+        }
+    }
+
+    /// What cargo's `dev` and `release` profiles pass to rustc, give or take
+    /// debug info.
+    fn flags(self) -> &'static [&'static str] {
+        match self {
+            Profile::Debug => &["-C", "opt-level=0", "-C", "debug-assertions=on"],
+            Profile::Release => &["-C", "opt-level=3", "-C", "debug-assertions=off"],
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Profile::Debug => write!(f, "debug (opt-level=0)"),
+            Profile::Release => write!(f, "release (opt-level=3)"),
+        }
+    }
+}
+
+/// A single-file program to build: a lesson, or any `.rs` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    /// The crate name, which is the first part of the path of everything
+    /// it defines.
+    pub name: String,
+    pub source: PathBuf,
+    pub edition: String,
+}
+
+impl Program {
+    /// A lesson by its package name, or a path to a `.rs` file.
+    pub fn find(target: &str) -> Result<Program, String> {
+        if target.ends_with(".rs") {
+            let source = PathBuf::from(target);
+            let name = source
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().replace('-', "_"))
+                .unwrap_or_default();
+            return Ok(Program {
+                name,
+                source,
+                edition: String::from("2018"),
+            });
+        }
+        let workspace = Workspace::find().map_err(|error| error.to_string())?;
+        let lesson = workspace
+            .get(target)
+            .ok_or_else(|| format!("there's no lesson called `{}`", target))?;
+        Ok(Program {
+            name: lesson.name.clone(),
+            source: lesson.main(),
+            edition: lesson.edition.clone(),
+        })
+    }
+}
+
+/// Compiles `program` to assembly in `scratch`, and reads it.
+///
+/// Symbols are mangled with the v0 scheme, so that each instance of a
+/// generic function says what it was instantiated with. Everything goes in
+/// one codegen unit so that it all ends up in one file.
+pub fn build(program: &Program, profile: Profile, scratch: &Path) -> Result<Assembly, String> {
+    let io = |path: &Path| {
+        let path = path.display().to_string();
+        move |error| format!("{}: {}", path, error)
+    };
+
+    fs::create_dir_all(scratch).map_err(io(scratch))?;
+    let output = scratch.join(format!("{}.s", program.name));
+    let compiled = Command::new(rustc())
+        .args(["--edition", &program.edition])
+        .args(["--crate-name", &program.name, "--crate-type", "bin"])
+        .args(profile.flags())
+        .args(["-C", "codegen-units=1", "-C", "symbol-mangling-version=v0"])
+        .args(["--emit", "asm", "--color", "never", "-A", "warnings"])
+        .arg("-o")
+        .arg(&output)
+        .arg(&program.source)
+        .output()
+        .map_err(io(Path::new("rustc")))?;
+    if !compiled.status.success() {
+        return Err(format!(
+            "could not build `{}`:\n{}",
+            program.name,
+            String::from_utf8_lossy(&compiled.stderr).trim_end()
+        ));
+    }
+
+    let text = fs::read_to_string(&output).map_err(io(&output))?;
+    Ok(Assembly::parse(&text))
+}
+
+fn rustc() -> PathBuf {
+    env::var_os("RUSTC")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("rustc"))
+}
@@ -0,0 +1,526 @@
+//! Reading the names rustc gives functions in the assembly.
+//!
+//! Programs are built with the v0 mangling scheme, which keeps the generic
+//! arguments of each instance in its symbol: `_RINvCs1a2b_11generic_add3addlEB2_`
+//! is `generic_add::add::<i32>`. The standard library is built with the
+//! legacy scheme, which doesn't, so calls into it come out as plain paths
+//! like `std::io::stdio::_print`.
+//!
+//! Lifetimes, crate disambiguators and hashes are left out, since they only
+//! get in the way when reading assembly.
+
+use std::convert::TryFrom;
+
+/// Nested paths and types deeper than this aren't a symbol rustc would make.
+const MAX_DEPTH: u32 = 64;
+
+/// The readable name of a symbol, or `None` if it isn't a Rust symbol.
+pub fn demangle(symbol: &str) -> Option<String> {
+    if let Some(rest) = symbol.strip_prefix("_R") {
+        v0(rest)
+    } else if let Some(rest) = symbol.strip_prefix("_ZN") {
+        legacy(rest)
+    } else {
+        None
+    }
+}
+
+/// Replaces every Rust symbol in a line of assembly with its name.
+pub fn demangle_line(line: &str) -> String {
+    let symbol_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '.';
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find(|c: char| symbol_char(c)) {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(|c: char| !symbol_char(c)).unwrap_or(rest.len());
+        let word = &rest[..end];
+        match demangle(word) {
+            Some(name) => out.push_str(&name),
+            None => out.push_str(word),
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `generic_add::add::<i32>` is `generic_add::add` with `i32`. Names
+/// without generic arguments come back whole.
+pub fn split_generics(name: &str) -> (&str, Option<&str>) {
+    if !name.ends_with('>') {
+        return (name, None);
+    }
+    let mut depth = 0;
+    for (index, c) in name.char_indices().rev() {
+        match c {
+            '>' => depth += 1,
+            '<' => {
+                depth -= 1;
+                if depth == 0 {
+                    return match name[..index].strip_suffix("::") {
+                        Some(path) => (path, Some(&name[index + 1..name.len() - 1])),
+                        None => (name, None),
+                    };
+                }
+            }
+            _ => {}
+        }
+    }
+    (name, None)
+}
+
+fn v0(text: &str) -> Option<String> {
+    // A version number would come first, and only version 0 exists.
+    if text.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut parser = Parser {
+        text: text.as_bytes(),
+        at: 0,
+        depth: 0,
+    };
+    let name = parser.path(true)?;
+    // Then the crate that instantiated it, which isn't worth showing, and
+    // anything LLVM tacked on after a `.`.
+    if parser.at < parser.text.len() && parser.peek() != Some(b'.') {
+        parser.path(false)?;
+    }
+    match parser.peek() {
+        None | Some(b'.') => Some(name),
+        Some(_) => None,
+    }
+}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    at: usize,
+    depth: u32,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.at).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.at += 1;
+        Some(byte)
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.at += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// `_` is 0, and digits ending in `_` are one more than their value.
+    fn base62(&mut self) -> Option<u64> {
+        if self.eat(b'_') {
+            return Some(0);
+        }
+        let mut value: u64 = 0;
+        loop {
+            let digit = match self.next()? {
+                byte @ b'0'..=b'9' => byte - b'0',
+                byte @ b'a'..=b'z' => byte - b'a' + 10,
+                byte @ b'A'..=b'Z' => byte - b'A' + 36,
+                b'_' => return value.checked_add(1),
+                _ => return None,
+            };
+            value = value.checked_mul(62)?.checked_add(u64::from(digit))?;
+        }
+    }
+
+    /// A base-62 number after `tag`, plus one, or 0 if there's no `tag`.
+    fn tagged(&mut self, tag: u8) -> Option<u64> {
+        if self.eat(tag) {
+            self.base62()?.checked_add(1)
+        } else {
+            Some(0)
+        }
+    }
+
+    fn decimal(&mut self) -> Option<usize> {
+        let start = self.at;
+        while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            self.at += 1;
+        }
+        std::str::from_utf8(&self.text[start..self.at])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    /// An identifier, and the number that tells it apart from others with
+    /// the same name.
+    fn ident(&mut self) -> Option<(String, u64)> {
+        let disambiguator = self.tagged(b's')?;
+        Some((self.undisambiguated()?, disambiguator))
+    }
+
+    fn undisambiguated(&mut self) -> Option<String> {
+        let punycode = self.eat(b'u');
+        let length = self.decimal()?;
+        self.eat(b'_');
+        let bytes = self.text.get(self.at..self.at + length)?;
+        self.at += length;
+        let ident = String::from_utf8(bytes.to_vec()).ok()?;
+        // Non-ASCII identifiers are punycode, which we leave encoded.
+        Some(if punycode {
+            format!("{{punycode:{}}}", ident)
+        } else {
+            ident
+        })
+    }
+
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        if self.depth >= MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let parsed = parse(self);
+        self.depth -= 1;
+        parsed
+    }
+
+    /// Parses whatever an earlier part of the symbol already did.
+    fn backref<T>(&mut self, parse: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        let target = usize::try_from(self.base62()?).ok()?;
+        if target >= self.at {
+            return None;
+        }
+        let resume = self.at;
+        self.at = target;
+        let parsed = self.nested(parse);
+        self.at = resume;
+        parsed
+    }
+
+    /// A path. In a `value` path generic arguments are written `::<...>`,
+    /// the way they are on a call; in a type they're just `<...>`.
+    fn path(&mut self, value: bool) -> Option<String> {
+        self.nested(|parser| match parser.next()? {
+            b'C' => parser.ident().map(|(name, _)| name),
+            b'M' => {
+                parser.impl_path()?;
+                Some(format!("<{}>", parser.ty()?))
+            }
+            b'X' => {
+                parser.impl_path()?;
+                let ty = parser.ty()?;
+                Some(format!("<{} as {}>", ty, parser.path(false)?))
+            }
+            b'Y' => {
+                let ty = parser.ty()?;
+                Some(format!("<{} as {}>", ty, parser.path(false)?))
+            }
+            b'N' => {
+                let namespace = parser.next()?;
+                let inner = parser.path(value)?;
+                let (name, disambiguator) = parser.ident()?;
+                if !namespace.is_ascii_uppercase() {
+                    return Some(format!("{}::{}", inner, name));
+                }
+                let kind = match namespace {
+                    b'C' => "closure",
+                    b'S' => "shim",
+                    _ => "?",
+                };
+                Some(if name.is_empty() {
+                    format!("{}::{{{}#{}}}", inner, kind, disambiguator)
+                } else {
+                    format!("{}::{{{}:{}#{}}}", inner, kind, name, disambiguator)
+                })
+            }
+            b'I' => {
+                let inner = parser.path(value)?;
+                let args = parser.generic_args()?;
+                Some(if args.is_empty() {
+                    inner
+                } else {
+                    let separator = if value { "::" } else { "" };
+                    format!("{}{}<{}>", inner, separator, args.join(", "))
+                })
+            }
+            b'B' => parser.backref(|parser| parser.path(value)),
+            _ => None,
+        })
+    }
+
+    /// Where an impl is, which only matters for telling impls apart.
+    fn impl_path(&mut self) -> Option<()> {
+        self.tagged(b's')?;
+        self.path(false).map(|_| ())
+    }
+
+    /// Generic arguments up to an `E`, without the lifetimes.
+    fn generic_args(&mut self) -> Option<Vec<String>> {
+        let mut args = Vec::new();
+        while !self.eat(b'E') {
+            if self.eat(b'L') {
+                self.base62()?;
+            } else if self.eat(b'K') {
+                args.push(self.konst()?);
+            } else {
+                args.push(self.ty()?);
+            }
+        }
+        Some(args)
+    }
+
+    fn ty(&mut self) -> Option<String> {
+        let basic = match self.peek()? {
+            b'a' => "i8",
+            b'b' => "bool",
+            b'c' => "char",
+            b'd' => "f64",
+            b'e' => "str",
+            b'f' => "f32",
+            b'h' => "u8",
+            b'i' => "isize",
+            b'j' => "usize",
+            b'l' => "i32",
+            b'm' => "u32",
+            b'n' => "i128",
+            b'o' => "u128",
+            b's' => "i16",
+            b't' => "u16",
+            b'u' => "()",
+            b'v' => "...",
+            b'x' => "i64",
+            b'y' => "u64",
+            b'z' => "!",
+            b'p' => "_",
+            _ => "",
+        };
+        if !basic.is_empty() {
+            self.at += 1;
+            return Some(basic.to_string());
+        }
+
+        self.nested(|parser| match parser.peek()? {
+            b'A' => {
+                parser.at += 1;
+                let element = parser.ty()?;
+                Some(format!("[{}; {}]", element, parser.konst()?))
+            }
+            b'S' => {
+                parser.at += 1;
+                Some(format!("[{}]", parser.ty()?))
+            }
+            b'T' => {
+                parser.at += 1;
+                let mut elements = Vec::new();
+                while !parser.eat(b'E') {
+                    elements.push(parser.ty()?);
+                }
+                Some(match elements.as_slice() {
+                    [element] => format!("({},)", element),
+                    _ => format!("({})", elements.join(", ")),
+                })
+            }
+            byte @ b'R' | byte @ b'Q' => {
+                parser.at += 1;
+                if parser.eat(b'L') {
+                    parser.base62()?;
+                }
+                let mutable = if byte == b'Q' { "mut " } else { "" };
+                Some(format!("&{}{}", mutable, parser.ty()?))
+            }
+            b'P' => {
+                parser.at += 1;
+                Some(format!("*const {}", parser.ty()?))
+            }
+            b'O' => {
+                parser.at += 1;
+                Some(format!("*mut {}", parser.ty()?))
+            }
+            b'F' => {
+                parser.at += 1;
+                parser.fn_sig()
+            }
+            b'D' => {
+                parser.at += 1;
+                parser.dyn_bounds()
+            }
+            b'B' => {
+                parser.at += 1;
+                parser.backref(Parser::ty)
+            }
+            _ => parser.path(false),
+        })
+    }
+
+    fn fn_sig(&mut self) -> Option<String> {
+        if self.eat(b'G') {
+            self.base62()?;
+        }
+        let mut sig = String::new();
+        if self.eat(b'U') {
+            sig.push_str("unsafe ");
+        }
+        if self.eat(b'K') {
+            let abi = if self.eat(b'C') {
+                String::from("C")
+            } else {
+                self.undisambiguated()?.replace('_', "-")
+            };
+            sig.push_str(&format!("extern {:?} ", abi));
+        }
+        let mut params = Vec::new();
+        while !self.eat(b'E') {
+            params.push(self.ty()?);
+        }
+        sig.push_str(&format!("fn({})", params.join(", ")));
+        let returns = self.ty()?;
+        if returns != "()" {
+            sig.push_str(&format!(" -> {}", returns));
+        }
+        Some(sig)
+    }
+
+    fn dyn_bounds(&mut self) -> Option<String> {
+        if self.eat(b'G') {
+            self.base62()?;
+        }
+        let mut traits = Vec::new();
+        while !self.eat(b'E') {
+            let mut bound = self.path(false)?;
+            // Associated types, like `Output = i32`, go with the generic
+            // arguments.
+            let mut bindings = Vec::new();
+            while self.eat(b'p') {
+                let name = self.undisambiguated()?;
+                bindings.push(format!("{} = {}", name, self.ty()?));
+            }
+            if !bindings.is_empty() {
+                bound = match bound.strip_suffix('>') {
+                    Some(bound) => format!("{}, {}>", bound, bindings.join(", ")),
+                    None => format!("{}<{}>", bound, bindings.join(", ")),
+                };
+            }
+            traits.push(bound);
+        }
+        if !self.eat(b'L') {
+            return None;
+        }
+        self.base62()?;
+        Some(format!("dyn {}", traits.join(" + ")))
+    }
+
+    /// A const generic argument, like the `4` in `[u8; 4]`.
+    fn konst(&mut self) -> Option<String> {
+        if self.eat(b'p') {
+            return Some(String::from("_"));
+        }
+        if self.eat(b'B') {
+            return self.backref(Parser::konst);
+        }
+        let ty = self.next()?;
+        let negative = self.eat(b'n');
+        let start = self.at;
+        while self.peek()? != b'_' {
+            self.at += 1;
+        }
+        let hex = std::str::from_utf8(&self.text[start..self.at]).ok()?;
+        self.at += 1;
+        let value = if hex.is_empty() {
+            0
+        } else {
+            u128::from_str_radix(hex, 16).ok()?
+        };
+        match ty {
+            b'b' => match value {
+                0 => Some(String::from("false")),
+                1 => Some(String::from("true")),
+                _ => None,
+            },
+            b'c' => {
+                let c = char::from_u32(u32::try_from(value).ok()?)?;
+                Some(format!("{:?}", c))
+            }
+            b'a' | b'h' | b'i' | b'j' | b'l' | b'm' | b'n' | b'o' | b's' | b't' | b'x' | b'y' => {
+                Some(format!("{}{}", if negative { "-" } else { "" }, value))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// `_ZN` paths: each segment is its length and then its text, up to an `E`.
+/// The last segment is a hash, which we leave off.
+fn legacy(text: &str) -> Option<String> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    loop {
+        if let Some(after) = rest.strip_prefix('E') {
+            if !after.is_empty() && !after.starts_with('.') {
+                return None;
+            }
+            break;
+        }
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let length: usize = rest[..digits].parse().ok()?;
+        let segment = rest.get(digits..digits + length)?;
+        segments.push(segment);
+        rest = &rest[digits + length..];
+    }
+    if let Some(last) = segments.last() {
+        let is_hash = last.len() == 17
+            && last.starts_with('h')
+            && last[1..].chars().all(|c| c.is_ascii_hexdigit());
+        if is_hash {
+            segments.pop();
+        }
+    }
+    if segments.is_empty() {
+        return None;
+    }
+    let segments: Option<Vec<String>> = segments.iter().map(|segment| unescape(segment)).collect();
+    Some(segments?.join("::"))
+}
+
+/// Legacy symbols spell punctuation with escapes like `$LT$` for `<`.
+fn unescape(segment: &str) -> Option<String> {
+    let segment = segment
+        .strip_prefix('_')
+        .filter(|rest| rest.starts_with('$'))
+        .unwrap_or(segment);
+    let mut out = String::new();
+    let mut rest = segment;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("..") {
+            out.push_str("::");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('$') {
+            let end = after.find('$')?;
+            let escape = &after[..end];
+            let c = match escape {
+                "SP" => '@',
+                "BP" => '*',
+                "RF" => '&',
+                "LT" => '<',
+                "GT" => '>',
+                "LP" => '(',
+                "RP" => ')',
+                "C" => ',',
+                _ => {
+                    let hex = escape.strip_prefix('u')?;
+                    char::from_u32(u32::from_str_radix(hex, 16).ok()?)?
+                }
+            };
+            out.push(c);
+            rest = &after[end + 1..];
+        } else {
+            let c = rest.chars().next()?;
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Some(out)
+}
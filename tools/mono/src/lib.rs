@@ -0,0 +1,69 @@
+//! mono: what the compiler actually made of a lesson.
+//!
+//! `variables_and_functions` says Rust monomorphizes generics, with "no
+//! performance penalty versus hand-writing each and every version", and
+//! `hello_world` credits the compiler's "_very_ aggressive optimizations".
+//! This [`build`]s a lesson, or any single-file program, in debug and in
+//! release, reads the [`asm`] rustc emits, and shows what became of each
+//! function: the instances of a generic function, one for each type it was
+//! used with, and each one's code in both builds, side by side.
+
+pub mod asm;
+pub mod build;
+pub mod demangle;
+
+use crate::asm::Function;
+
+/// How wide a column of [`side_by_side`] gets before its lines are cut.
+const COLUMN_WIDTH: usize = 48;
+
+/// Two functions' assembly in columns, headed with the builds they came
+/// from. A function that isn't in one of them gets a note in its place.
+pub fn side_by_side(titles: [&str; 2], functions: [Option<&Function>; 2]) -> String {
+    let column = |function: Option<&Function>| match function {
+        Some(function) => function.lines.clone(),
+        None => vec![
+            String::from("(no code of its own: inlined into"),
+            String::from("every call, or merged with a function"),
+            String::from("that compiled to the same code)"),
+        ],
+    };
+    let left = column(functions[0]);
+    let right = column(functions[1]);
+    let width = left
+        .iter()
+        .chain(std::iter::once(&titles[0].to_string()))
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(COLUMN_WIDTH);
+
+    let mut text = format!("{:<width$} | {}\n", titles[0], titles[1], width = width);
+    text.push_str(&format!(
+        "{:-<width$}-+-{:-<width$}\n",
+        "",
+        "",
+        width = width
+    ));
+    for index in 0..left.len().max(right.len()) {
+        let line = |lines: &[String]| {
+            lines
+                .get(index)
+                .map_or(String::new(), |line| cut(line, width))
+        };
+        text.push_str(
+            format!("{:<width$} | {}", line(&left), line(&right), width = width).trim_end(),
+        );
+        text.push('\n');
+    }
+    text
+}
+
+fn cut(line: &str, width: usize) -> String {
+    if line.chars().count() <= width {
+        return line.to_string();
+    }
+    let mut cut: String = line.chars().take(width - 3).collect();
+    cut.push_str("...");
+    cut
+}
@@ -0,0 +1,172 @@
+//! `mono` shows the assembly rustc makes of a lesson's functions, in debug
+//! and in release.
+
+use std::env;
+use std::process;
+
+use mono::asm::Assembly;
+use mono::build::{self, Profile, Program};
+
+const USAGE: &str = "\
+usage: mono <command>
+
+<program> is a lesson's package name, or a path to a .rs file.
+
+commands:
+    symbols <program>
+                  list every function in the program's assembly, with how
+                  many instructions it is in debug and in release
+    instances <program> <function>
+                  list every monomorphized instance of a generic function,
+                  one for each set of types it was used with
+    asm <program> <function>...
+                  show each instance of each function in debug and in
+                  release, side by side";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match mono(&args) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("mono: {}", error);
+            process::exit(2);
+        }
+    }
+}
+
+/// Runs a command, returning whether it found the functions it was asked
+/// about.
+fn mono(args: &[&str]) -> Result<bool, String> {
+    match args {
+        ["symbols", program] => {
+            let (program, [debug, release]) = build_both(program)?;
+            println!(
+                "{} in {} and {}",
+                program.name,
+                Profile::Debug,
+                Profile::Release
+            );
+            let mut names: Vec<&str> = debug.functions.iter().map(|f| f.name.as_str()).collect();
+            for function in &release.functions {
+                if !names.contains(&function.name.as_str()) {
+                    names.push(&function.name);
+                }
+            }
+            println!("{:>7} {:>9}  function", "debug", "release");
+            for name in names {
+                println!(
+                    "{:>7} {:>9}  {}",
+                    count(&debug, name),
+                    count(&release, name),
+                    name
+                );
+            }
+            Ok(true)
+        }
+        ["instances", program, function] => {
+            let (program, [debug, release]) = build_both(program)?;
+            let instances = debug.instances(&program.name, function);
+            if instances.is_empty() {
+                println!("there's no `{}` in {}", function, program.name);
+                return Ok(false);
+            }
+            let plural = if instances.len() == 1 { "" } else { "s" };
+            println!(
+                "`{}` in {} has {} instance{}:",
+                function,
+                program.name,
+                instances.len(),
+                plural
+            );
+            println!();
+            let width = instances.iter().map(|f| f.name.len()).max().unwrap_or(0);
+            println!(
+                "    {:<width$}  {:>7} {:>9}",
+                "instance",
+                "debug",
+                "release",
+                width = width
+            );
+            for instance in instances {
+                println!(
+                    "    {:<width$}  {:>7} {:>9}",
+                    instance.name,
+                    count(&debug, &instance.name),
+                    count(&release, &instance.name),
+                    width = width
+                );
+            }
+            println!();
+            println!(
+                "(instructions in each build; `gone` means it has no code of its own, because"
+            );
+            println!("it was inlined into every call or merged with an identical function)");
+            Ok(true)
+        }
+        ["asm", program, functions @ ..] if !functions.is_empty() => {
+            let (program, [debug, release]) = build_both(program)?;
+            let mut found_all = true;
+            for function in functions {
+                let mut names: Vec<&str> = debug
+                    .instances(&program.name, function)
+                    .into_iter()
+                    .chain(release.instances(&program.name, function))
+                    .map(|f| f.name.as_str())
+                    .collect();
+                names.sort_unstable();
+                names.dedup();
+                if names.is_empty() {
+                    println!("there's no `{}` in {}", function, program.name);
+                    found_all = false;
+                    continue;
+                }
+                for name in names {
+                    println!("{}", name);
+                    let titles = Profile::ALL.map(|profile| profile.to_string());
+                    print!(
+                        "{}",
+                        mono::side_by_side(
+                            [&titles[0], &titles[1]],
+                            [debug.get(name), release.get(name)]
+                        )
+                    );
+                    println!();
+                }
+            }
+            Ok(found_all)
+        }
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{}", USAGE);
+            Ok(true)
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+/// Builds a program in debug and in release, under `target/mono`.
+fn build_both(target: &str) -> Result<(Program, [Assembly; 2]), String> {
+    let program = Program::find(target)?;
+    let root = lessons::find_root().map_err(|error| error.to_string())?;
+    let scratch = root.join("target").join("mono");
+    let debug = build::build(
+        &program,
+        Profile::Debug,
+        &scratch.join(Profile::Debug.name()),
+    )?;
+    let release = build::build(
+        &program,
+        Profile::Release,
+        &scratch.join(Profile::Release.name()),
+    )?;
+    Ok((program, [debug, release]))
+}
+
+fn count(assembly: &Assembly, name: &str) -> String {
+    match assembly.get(name) {
+        Some(function) => function.instructions().to_string(),
+        None => String::from("gone"),
+    }
+}
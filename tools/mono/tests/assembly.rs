@@ -0,0 +1,118 @@
+//! Demangles symbols, and builds the example program to find the instances
+//! of `add` in it.
+
+use std::path::Path;
+
+use mono::asm::Assembly;
+use mono::build::{self, Profile, Program};
+use mono::demangle::{demangle, demangle_line, split_generics};
+
+#[test]
+fn v0_symbols_keep_their_generic_arguments() {
+    let cases = [
+        (
+            "_RINvCs2cEj6liUDda_11generic_add3addlEB2_",
+            "generic_add::add::<i32>",
+        ),
+        (
+            "_RINvCs2cEj6liUDda_11generic_add3adddEB2_",
+            "generic_add::add::<f64>",
+        ),
+        (
+            "_RNvXs7_NtNtCsgEmfK2I1SDS_4core3ops5arithlNtB5_3Add3addCs2cEj6liUDda_11generic_add",
+            "<i32 as core::ops::arith::Add>::add",
+        ),
+        (
+            "_RINvMs2_NtCsgEmfK2I1SDS_4core3fmtNtB6_9Arguments3newKj4_Kj1_ECshhNdl82Kjmm_1g",
+            "<core::fmt::Arguments>::new::<4, 1>",
+        ),
+        (
+            "_RNCINvNtCsjrHSEGnQ3l9_3std2rt10lang_startuE0CshhNdl82Kjmm_1g",
+            "std::rt::lang_start::<()>::{closure#0}",
+        ),
+        ("_RNvCshhNdl82Kjmm_1g4main", "g::main"),
+    ];
+    for (symbol, name) in &cases {
+        assert_eq!(demangle(symbol).as_deref(), Some(*name), "{}", symbol);
+    }
+}
+
+#[test]
+fn legacy_symbols_lose_their_hash() {
+    assert_eq!(
+        demangle("_ZN3std2io5stdio6_print17h0123456789abcdefE").as_deref(),
+        Some("std::io::stdio::_print")
+    );
+    assert_eq!(
+        demangle("_ZN4core3ptr42drop_in_place$LT$alloc..string..String$GT$17h0123456789abcdefE")
+            .as_deref(),
+        Some("core::ptr::drop_in_place<alloc::string::String>")
+    );
+    assert_eq!(demangle("main"), None);
+    assert_eq!(demangle("_RNvC"), None);
+}
+
+#[test]
+fn symbols_in_instructions_are_demangled() {
+    assert_eq!(
+        demangle_line("callq\t_RNvCshhNdl82Kjmm_1g4main@PLT"),
+        "callq\tg::main@PLT"
+    );
+    assert_eq!(
+        split_generics("g::add::<alloc::vec::Vec<i32>>"),
+        ("g::add", Some("alloc::vec::Vec<i32>"))
+    );
+    assert_eq!(split_generics("<g::Point>::new"), ("<g::Point>::new", None));
+}
+
+#[test]
+fn functions_are_read_out_of_the_assembly() {
+    let text = "\
+\t.section\t.text._RNvCshhNdl82Kjmm_1g3add,\"ax\",@progbits
+\t.p2align\t4
+\t.type\t_RNvCshhNdl82Kjmm_1g3add,@function
+_RNvCshhNdl82Kjmm_1g3add:
+\t.cfi_startproc
+\tleal\t(%rdi,%rsi), %eax
+\tretq
+.Lfunc_end0:
+\t.size\t_RNvCshhNdl82Kjmm_1g3add, .Lfunc_end0-_RNvCshhNdl82Kjmm_1g3add
+\t.cfi_endproc
+";
+    let assembly = Assembly::parse(text);
+    assert_eq!(assembly.functions.len(), 1);
+    let add = assembly.get("g::add").unwrap();
+    assert_eq!(add.lines, ["leal    (%rdi,%rsi), %eax", "retq"]);
+    assert_eq!(add.instructions(), 2);
+    assert_eq!(assembly.instances("g", "add").len(), 1);
+    assert!(assembly.instances("g", "sub").is_empty());
+}
+
+#[test]
+fn generic_add_has_an_instance_for_each_type() {
+    let source = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("programs")
+        .join("generic_add.rs");
+    let program = Program::find(source.to_str().unwrap()).unwrap();
+    assert_eq!(program.name, "generic_add");
+
+    let scratch = Path::new(env!("CARGO_TARGET_TMPDIR")).join("mono");
+    for profile in &Profile::ALL {
+        let assembly = build::build(&program, *profile, &scratch.join(profile.name())).unwrap();
+        let instances: Vec<&str> = assembly
+            .instances("generic_add", "add")
+            .iter()
+            .map(|function| function.name.as_str())
+            .collect();
+        assert_eq!(
+            instances,
+            [
+                "generic_add::add::<f64>",
+                "generic_add::add::<i32>",
+                "generic_add::add::<u64>"
+            ],
+            "in {}",
+            profile
+        );
+    }
+}
@@ -0,0 +1,15 @@
+// Exercise: add
+//
+// Write the body of `add`, which returns the sum of its two parameters. Look
+// back at the lesson if you're stuck: the last expression in a function is
+// what it returns, so you don't need the `return` keyword or a semicolon.
+//
+// `cargo run -p club -- exercise` runs the tests every time you save.
+
+fn main() {
+    println!("{}", add(5, 6));
+}
+
+fn add(first: i32, second: i32) -> i32 {
+    todo!()
+}
@@ -0,0 +1,17 @@
+// Exercise: say_hello_to_name
+//
+// In the lesson, `say_hello_to_name` printed its greeting. This one returns it
+// instead, so that `main` can decide what to do with it. Make it return
+// "Hello, <name>!" for whatever name it's given.
+//
+// Hint: `format!` takes the same format strings as `println!`, but gives you
+// back a `String` instead of printing it.
+
+fn main() {
+    let name = String::from("Gabe");
+    println!("{}", say_hello_to_name(name));
+}
+
+fn say_hello_to_name(name: String) -> String {
+    todo!()
+}
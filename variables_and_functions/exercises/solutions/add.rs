@@ -0,0 +1,12 @@
+// Solution: add
+//
+// The instructor's answer to the exercise of the same name. `club student
+// --check` makes sure that it passes the hidden tests.
+
+fn main() {
+    println!("{}", add(5, 6));
+}
+
+fn add(first: i32, second: i32) -> i32 {
+    first + second
+}
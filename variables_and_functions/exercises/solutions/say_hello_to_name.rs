@@ -0,0 +1,13 @@
+// Solution: say_hello_to_name
+//
+// The instructor's answer to the exercise of the same name. `club student
+// --check` makes sure that it passes the hidden tests.
+
+fn main() {
+    let name = String::from("Gabe");
+    println!("{}", say_hello_to_name(name));
+}
+
+fn say_hello_to_name(name: String) -> String {
+    format!("Hello, {}!", name)
+}
@@ -0,0 +1,21 @@
+#[test]
+fn adds_two_numbers() {
+    assert_eq!(add(5, 6), 11);
+}
+
+#[test]
+fn adds_negative_numbers() {
+    assert_eq!(add(-3, -4), -7);
+    assert_eq!(add(10, -4), 6);
+}
+
+#[test]
+fn zero_changes_nothing() {
+    assert_eq!(add(0, 42), 42);
+    assert_eq!(add(42, 0), 42);
+}
+
+#[test]
+fn order_doesnt_matter() {
+    assert_eq!(add(2, 9), add(9, 2));
+}
@@ -0,0 +1,15 @@
+#[test]
+fn greets_gabe() {
+    assert_eq!(say_hello_to_name(String::from("Gabe")), "Hello, Gabe!");
+}
+
+#[test]
+fn greets_anyone() {
+    assert_eq!(say_hello_to_name(String::from("Ferris")), "Hello, Ferris!");
+    assert_eq!(say_hello_to_name(String::from("你好")), "Hello, 你好!");
+}
+
+#[test]
+fn greets_nobody() {
+    assert_eq!(say_hello_to_name(String::new()), "Hello, !");
+}
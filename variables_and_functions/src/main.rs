@@ -23,13 +23,16 @@ fn main() {
     // just prints to stdout.
     say_hello_to_name(name);
 
+    // EXPLAIN: moves
     // There's something odd about `name`, though. Notice that if you attempt
     // to call say_hello_to_name again, it will throw a compiler error. This
     // is because of a concept known as Ownership, and it is core to Rust's
     // design. Ownership will be talked about more in a bit.
     // use of moved value: `name`
     // value used here after move. rustc(E0382)
+    // ```compile_fail(E0382): use of moved value: `name`
     // say_hello_to_name(name);
+    // ```
 
     // Calling a function with 2 parameters and
     // printing its result.
@@ -44,6 +47,7 @@ fn main() {
     // inexpensive operation, versus copying a whole Vec or String into a function.
     println!("x = {} and y = {}", x, y);
 
+    // EXPLAIN: inference
     // We talked about type inference earlier, but there are some cases where
     // the compiler can not infer the type that you want. This is when you
     // must manually annotate the type. In this case, Vec is generic over type T,
@@ -51,7 +55,9 @@ fn main() {
     // Vec you really want.
     // type annotations needed
     // cannot infer type for `T` rustc(E0282)
+    // ```compile_fail(E0282): type annotations needed
     // let broken_vec = Vec::new();
+    // ```
 
     // In this case, we can use the <name>: <type> syntax talked about earlier.
     // The underscore is just so that the compiler does not complain, since we
@@ -83,18 +89,22 @@ fn say_hello() {
 // Multiple arguments are seperated with commas. This function doesn't seem to 
 // return anything, so there's no need to denote a return type.
 fn say_hello_to_name(name: String) {
+    // SOLUTION BEGIN: todo!("print a greeting for `name`")
     // {} is a type of format string. It will read the first arguments after
     // the comma, and apply it to the first brace pair. It effectively replaces
     // the braces in the final version.
     println!("Hello, {}!", name);
+    // SOLUTION END
 }
 
 // A function's return type is shown with the `->` operator.
 fn add(first: i32, second: i32) -> i32 {
+    // SOLUTION BEGIN: todo!("return the sum of `first` and `second`")
     // If an expression is the last expression in the function, the return 
     // keyword and semicolon can be omitted, and that expression will
     // implicitly be returned.
     first + second
+    // SOLUTION END
 }
 
 // Let's talk for a moment about no return type. Every function in Rust must